@@ -0,0 +1,144 @@
+//! Compares the heap allocations of a custom processing block that writes its output to a new
+//! buffer for every frame, with one that allocates its output frames from the block's frame pool.
+//!
+//! Requires a connected D400 series device. Run with:
+//!
+//! ```sh
+//! cargo bench --features test-single-device --bench frame_pool
+//! ```
+
+use realsense_rust::{
+    config::Config,
+    context::Context,
+    frame::{DepthFrame, FrameEx},
+    kind::{Rs2CameraInfo, Rs2Format, Rs2ProductLine, Rs2StreamKind},
+    pipeline::{ActivePipeline, InactivePipeline},
+    processing_blocks::CustomProcessingBlock,
+};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    collections::HashSet,
+    convert::TryFrom,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Allocator which counts the allocations made through it.
+struct CountingAllocator;
+
+/// The number of allocations made so far.
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of bytes allocated so far.
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// The number of frames processed by each block.
+const FRAME_COUNT: usize = 300;
+
+/// Gets the data of `frame` as bytes.
+fn frame_bytes(frame: &DepthFrame) -> &[u8] {
+    unsafe {
+        let data = frame.get_data() as *const _ as *const u8;
+        std::slice::from_raw_parts(data, frame.get_data_size())
+    }
+}
+
+/// Processes [`FRAME_COUNT`] depth frames from `pipeline` with `block`, and prints the heap
+/// allocations made meanwhile along with the number of distinct buffers of the output frames.
+fn measure(name: &str, pipeline: &mut ActivePipeline, mut block: CustomProcessingBlock) {
+    let mut buffers = HashSet::new();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let allocated_bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+
+    for _ in 0..FRAME_COUNT {
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+        block.queue(depth_frame).unwrap();
+
+        let output: DepthFrame = block.wait(None).unwrap();
+        buffers.insert(frame_bytes(&output).as_ptr() as usize);
+    }
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    let allocated_bytes = ALLOCATED_BYTES.load(Ordering::Relaxed) - allocated_bytes;
+    println!(
+        "{:<24} {:>8} allocations ({:>6.1} per frame) {:>12} bytes {:>6} output buffers",
+        name,
+        allocations,
+        allocations as f64 / FRAME_COUNT as f64,
+        allocated_bytes,
+        buffers.len(),
+    );
+}
+
+fn main() {
+    let context = Context::new().unwrap();
+    let device = context
+        .query_devices_by_product_line(Rs2ProductLine::D400)
+        .into_iter()
+        .next()
+        .expect("No D400 series device is connected.");
+
+    let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+    let mut config = Config::new();
+    config
+        .enable_device_from_serial(serial)
+        .unwrap()
+        .disable_all_streams()
+        .unwrap()
+        .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+        .unwrap();
+
+    let pipeline = InactivePipeline::try_from(&context).unwrap();
+    let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+    // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+    for _ in 0..5 {
+        let _ = pipeline.wait(None).unwrap();
+    }
+
+    println!("Processing {} depth frames per block", FRAME_COUNT);
+
+    // Before: the processed data is written to a new buffer for every frame.
+    let new_buffer = CustomProcessingBlock::from_fn(|frame: DepthFrame, source| {
+        let processed = frame_bytes(&frame).to_vec();
+        std::hint::black_box(&processed);
+        source.frame_ready(frame).unwrap();
+    })
+    .unwrap();
+    measure("new buffer per frame", &mut pipeline, new_buffer);
+
+    // After: the processed data is written to a frame allocated from the block's pool.
+    let frame_pool = CustomProcessingBlock::from_fn(|frame: DepthFrame, source| {
+        let mut output = source
+            .pool()
+            .allocate_uninitialized_video_frame(
+                &frame,
+                frame.stream_profile(),
+                frame.width(),
+                frame.height(),
+            )
+            .unwrap();
+        output
+            .data_mut()
+            .unwrap()
+            .copy_from_slice(frame_bytes(&frame));
+        source.frame_ready(output).unwrap();
+    })
+    .unwrap();
+    measure("frame pool", &mut pipeline, frame_pool);
+}
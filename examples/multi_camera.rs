@@ -0,0 +1,59 @@
+//! Stream from every connected device at the same time, with one pipeline per device.
+//!
+//! This is the usual setup for e.g. robots with several cameras. Every pipeline is created from
+//! the same context, and bound to its device by serial number so that the pipelines don't compete
+//! for the same device.
+
+use anyhow::{ensure, Result};
+use realsense_rust::{
+    config::Config,
+    context::Context,
+    frame::{DepthFrame, FrameEx},
+    kind::Rs2CameraInfo,
+    pipeline::InactivePipeline,
+};
+use std::{collections::HashSet, convert::TryFrom};
+
+/// Main function
+pub fn main() -> Result<()> {
+    let context = Context::new()?;
+    let devices = context.query_devices(HashSet::new());
+    ensure!(!devices.is_empty(), "No devices found");
+
+    let mut pipelines = Vec::new();
+    for device in devices {
+        let serial = match device.info(Rs2CameraInfo::SerialNumber) {
+            Some(serial) => serial,
+            None => continue,
+        };
+
+        // Only the device is set, so the default streams of the device are enabled.
+        let mut config = Config::new();
+        config.enable_device_from_serial(serial)?;
+
+        let pipeline = InactivePipeline::try_from(&context)?;
+        let pipeline = pipeline.start(Some(config))?;
+        pipelines.push((serial.to_str()?.to_owned(), pipeline));
+    }
+
+    for _ in 0..100 {
+        for (serial, pipeline) in pipelines.iter_mut() {
+            let frames = pipeline.wait(None)?;
+            for depth_frame in frames.frames_of_type::<DepthFrame>() {
+                let distance =
+                    depth_frame.distance(depth_frame.width() / 2, depth_frame.height() / 2)?;
+                println!(
+                    "{}: depth frame #{} has a center distance of {:.3}m",
+                    serial,
+                    depth_frame.frame_number(),
+                    distance
+                );
+            }
+        }
+    }
+
+    for (_, pipeline) in pipelines {
+        let _pipeline = pipeline.stop();
+    }
+    Ok(())
+}
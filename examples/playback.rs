@@ -0,0 +1,63 @@
+//! Stream frames from a previously recorded `.bag` file.
+//!
+//! Playback from file behaves exactly like streaming from a connected device, which means no
+//! hardware is required to run this example. Pass the path to the bag file as the first argument:
+//!
+//! ```text
+//! cargo run --example playback -- /path/to/recording.bag
+//! ```
+
+use anyhow::{ensure, Context as _, Result};
+use realsense_rust::{
+    config::Config,
+    context::Context,
+    frame::{DepthFrame, FrameEx},
+    pipeline::{FrameWaitError, InactivePipeline},
+};
+use std::{convert::TryFrom, time::Duration};
+
+/// Main function
+pub fn main() -> Result<()> {
+    let path = std::env::args()
+        .nth(1)
+        .context("Usage: playback <path to .bag file>")?;
+
+    let context = Context::new()?;
+    let pipeline = InactivePipeline::try_from(&context)?;
+
+    // Every stream recorded in the file is enabled by default. Don't loop the playback, so that
+    // the pipeline stops delivering frames once the end of the file has been reached.
+    let mut config = Config::new();
+    config.enable_device_from_file(&path, false)?;
+
+    // Change pipeline's type from InactivePipeline -> ActivePipeline
+    let mut pipeline = pipeline.start(Some(config))?;
+
+    let mut count = 0;
+    loop {
+        // Once the end of the file is reached, no more frames arrive and the wait times out.
+        let frames = match pipeline.wait(Some(Duration::from_millis(1000))) {
+            Ok(frames) => frames,
+            Err(FrameWaitError::DidTimeoutBeforeFrameArrival) => break,
+            Err(e) => return Err(e.into()),
+        };
+        count += 1;
+
+        for depth_frame in frames.frames_of_type::<DepthFrame>() {
+            let distance =
+                depth_frame.distance(depth_frame.width() / 2, depth_frame.height() / 2)?;
+            println!(
+                "Depth frame #{} at {:.3}ms: center distance is {:.3}m",
+                depth_frame.frame_number(),
+                depth_frame.timestamp(),
+                distance
+            );
+        }
+    }
+    ensure!(count > 0, "No frames were found in {}", path);
+    println!("Read {} framesets from {}", count, path);
+
+    // Change pipeline's type from ActivePipeline -> InactivePipeline
+    let _pipeline = pipeline.stop();
+    Ok(())
+}
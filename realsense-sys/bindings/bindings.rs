@@ -2780,6 +2780,182 @@ extern "C" {
         error: *mut *mut rs2_error,
     );
 }
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct STDepthControlGroup {
+    pub plusIncrement: u32,
+    pub minusDecrement: u32,
+    pub deepSeaMedianThreshold: u32,
+    pub scoreThreshA: u32,
+    pub scoreThreshB: u32,
+    pub textureDifferenceThreshold: u32,
+    pub textureCountThreshold: u32,
+    pub deepSeaSecondPeakThreshold: u32,
+    pub deepSeaNeighborThreshold: u32,
+    pub lrAgreeThreshold: u32,
+}
+#[test]
+fn bindgen_test_layout_STDepthControlGroup() {
+    assert_eq!(
+        ::std::mem::size_of::<STDepthControlGroup>(),
+        40usize,
+        concat!("Size of: ", stringify!(STDepthControlGroup))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<STDepthControlGroup>(),
+        4usize,
+        concat!("Alignment of ", stringify!(STDepthControlGroup))
+    );
+    assert_eq!(
+        unsafe {
+            &(*(::std::ptr::null::<STDepthControlGroup>())).plusIncrement as *const _ as usize
+        },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(STDepthControlGroup),
+            "::",
+            stringify!(plusIncrement)
+        )
+    );
+    assert_eq!(
+        unsafe {
+            &(*(::std::ptr::null::<STDepthControlGroup>())).minusDecrement as *const _ as usize
+        },
+        4usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(STDepthControlGroup),
+            "::",
+            stringify!(minusDecrement)
+        )
+    );
+    assert_eq!(
+        unsafe {
+            &(*(::std::ptr::null::<STDepthControlGroup>())).deepSeaMedianThreshold as *const _ as usize
+        },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(STDepthControlGroup),
+            "::",
+            stringify!(deepSeaMedianThreshold)
+        )
+    );
+    assert_eq!(
+        unsafe {
+            &(*(::std::ptr::null::<STDepthControlGroup>())).scoreThreshA as *const _ as usize
+        },
+        12usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(STDepthControlGroup),
+            "::",
+            stringify!(scoreThreshA)
+        )
+    );
+    assert_eq!(
+        unsafe {
+            &(*(::std::ptr::null::<STDepthControlGroup>())).scoreThreshB as *const _ as usize
+        },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(STDepthControlGroup),
+            "::",
+            stringify!(scoreThreshB)
+        )
+    );
+    assert_eq!(
+        unsafe {
+            &(*(::std::ptr::null::<STDepthControlGroup>())).textureDifferenceThreshold as *const _ as usize
+        },
+        20usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(STDepthControlGroup),
+            "::",
+            stringify!(textureDifferenceThreshold)
+        )
+    );
+    assert_eq!(
+        unsafe {
+            &(*(::std::ptr::null::<STDepthControlGroup>())).textureCountThreshold as *const _ as usize
+        },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(STDepthControlGroup),
+            "::",
+            stringify!(textureCountThreshold)
+        )
+    );
+    assert_eq!(
+        unsafe {
+            &(*(::std::ptr::null::<STDepthControlGroup>())).deepSeaSecondPeakThreshold as *const _ as usize
+        },
+        28usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(STDepthControlGroup),
+            "::",
+            stringify!(deepSeaSecondPeakThreshold)
+        )
+    );
+    assert_eq!(
+        unsafe {
+            &(*(::std::ptr::null::<STDepthControlGroup>())).deepSeaNeighborThreshold as *const _ as usize
+        },
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(STDepthControlGroup),
+            "::",
+            stringify!(deepSeaNeighborThreshold)
+        )
+    );
+    assert_eq!(
+        unsafe {
+            &(*(::std::ptr::null::<STDepthControlGroup>())).lrAgreeThreshold as *const _ as usize
+        },
+        36usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(STDepthControlGroup),
+            "::",
+            stringify!(lrAgreeThreshold)
+        )
+    );
+}
+extern "C" {
+    pub fn rs2_toggle_advanced_mode(
+        dev: *mut rs2_device,
+        enable: ::std::os::raw::c_int,
+        error: *mut *mut rs2_error,
+    );
+}
+extern "C" {
+    pub fn rs2_is_enabled(
+        dev: *mut rs2_device,
+        enabled: *mut ::std::os::raw::c_int,
+        error: *mut *mut rs2_error,
+    );
+}
+extern "C" {
+    pub fn rs2_set_depth_control(
+        dev: *mut rs2_device,
+        group: *const STDepthControlGroup,
+        error: *mut *mut rs2_error,
+    );
+}
+extern "C" {
+    pub fn rs2_get_depth_control(
+        dev: *mut rs2_device,
+        group: *mut STDepthControlGroup,
+        mode: ::std::os::raw::c_int,
+        error: *mut *mut rs2_error,
+    );
+}
 extern "C" {
     pub fn rs2_serialize_json(
         dev: *mut rs2_device,
@@ -74,6 +74,7 @@ fn main() {
                     .to_str()
                     .unwrap(),
             )
+            .header(include_dir.join("rs_advanced_mode.h").to_str().unwrap())
             .header(include_dir.join("h").join("rs_config.h").to_str().unwrap())
             .whitelist_var("RS2_.*")
             .whitelist_type("rs2_.*")
@@ -3,7 +3,10 @@
 use crate::kind::Rs2DistortionModel;
 use num_traits::FromPrimitive;
 use realsense_sys as sys;
-use std::{ffi::CString, time::Duration};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{ffi::CString, fmt, str::FromStr, time::Duration};
+use thiserror::Error;
 
 /// The default timeout duration in librealsense2
 pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(sys::RS2_DEFAULT_TIMEOUT as u64);
@@ -139,10 +142,209 @@ impl Rs2Intrinsics {
             coeffs: self.0.coeffs,
         }
     }
+
+    /// Projects a 3D point, in meters relative to the camera, onto a pixel in the image.
+    ///
+    /// This is a port of `rs2_project_point_to_pixel` from librealsense2, and applies the
+    /// distortion model of the image to the projected point.
+    pub fn project(&self, point: [f32; 3]) -> [f32; 2] {
+        let distortion = self.distortion();
+        let c = distortion.coeffs;
+
+        let mut x = point[0] / point[2];
+        let mut y = point[1] / point[2];
+
+        match distortion.model {
+            Rs2DistortionModel::BrownConradyModified | Rs2DistortionModel::BrownConradyInverse => {
+                let r2 = x * x + y * y;
+                let f = 1.0 + c[0] * r2 + c[1] * r2 * r2 + c[4] * r2 * r2 * r2;
+                x *= f;
+                y *= f;
+                let dx = x + 2.0 * c[2] * x * y + c[3] * (r2 + 2.0 * x * x);
+                let dy = y + 2.0 * c[3] * x * y + c[2] * (r2 + 2.0 * y * y);
+                x = dx;
+                y = dy;
+            }
+            Rs2DistortionModel::BrownConrady => {
+                let r2 = x * x + y * y;
+                let f = 1.0 + c[0] * r2 + c[1] * r2 * r2 + c[4] * r2 * r2 * r2;
+                let dx = x * f + 2.0 * c[2] * x * y + c[3] * (r2 + 2.0 * x * x);
+                let dy = y * f + 2.0 * c[3] * x * y + c[2] * (r2 + 2.0 * y * y);
+                x = dx;
+                y = dy;
+            }
+            Rs2DistortionModel::FThetaFisheye => {
+                let r = (x * x + y * y).sqrt().max(f32::EPSILON);
+                let rd = 1.0 / c[0] * (2.0 * r * (c[0] / 2.0).tan()).atan();
+                x *= rd / r;
+                y *= rd / r;
+            }
+            Rs2DistortionModel::KannalaBrandt => {
+                let r = (x * x + y * y).sqrt().max(f32::EPSILON);
+                let theta = r.atan();
+                let theta2 = theta * theta;
+                let series =
+                    1.0 + theta2 * (c[0] + theta2 * (c[1] + theta2 * (c[2] + theta2 * c[3])));
+                let rd = theta * series;
+                x *= rd / r;
+                y *= rd / r;
+            }
+            Rs2DistortionModel::None => {}
+        }
+
+        [x * self.0.fx + self.0.ppx, y * self.0.fy + self.0.ppy]
+    }
+
+    /// Deprojects a pixel in the image, at the given depth in meters, to a 3D point in meters
+    /// relative to the camera.
+    ///
+    /// This is a port of `rs2_deproject_pixel_to_point` from librealsense2, and removes the
+    /// distortion of the image from the pixel before deprojecting it. Images using
+    /// [`Rs2DistortionModel::BrownConradyModified`] are distorted in the forward direction, which
+    /// cannot be undone, so for those images the distortion is ignored.
+    ///
+    /// Like in librealsense2, deprojection is not the exact inverse of [`project`](Self::project)
+    /// for [`Rs2DistortionModel::FThetaFisheye`].
+    pub fn deproject(&self, pixel: [f32; 2], depth: f32) -> [f32; 3] {
+        let distortion = self.distortion();
+        let c = distortion.coeffs;
+
+        let mut x = (pixel[0] - self.0.ppx) / self.0.fx;
+        let mut y = (pixel[1] - self.0.ppy) / self.0.fy;
+        let xo = x;
+        let yo = y;
+
+        match distortion.model {
+            Rs2DistortionModel::BrownConradyInverse => {
+                // Iterate until convergence. 10 iterations were determined empirically by
+                // librealsense2.
+                for _ in 0..10 {
+                    let r2 = x * x + y * y;
+                    let icdist = 1.0 / (1.0 + ((c[4] * r2 + c[1]) * r2 + c[0]) * r2);
+                    let xq = x / icdist;
+                    let yq = y / icdist;
+                    let delta_x = 2.0 * c[2] * xq * yq + c[3] * (r2 + 2.0 * xq * xq);
+                    let delta_y = 2.0 * c[3] * xq * yq + c[2] * (r2 + 2.0 * yq * yq);
+                    x = (xo - delta_x) * icdist;
+                    y = (yo - delta_y) * icdist;
+                }
+            }
+            Rs2DistortionModel::BrownConrady => {
+                for _ in 0..10 {
+                    let r2 = x * x + y * y;
+                    let icdist = 1.0 / (1.0 + ((c[4] * r2 + c[1]) * r2 + c[0]) * r2);
+                    let delta_x = 2.0 * c[2] * x * y + c[3] * (r2 + 2.0 * x * x);
+                    let delta_y = 2.0 * c[3] * x * y + c[2] * (r2 + 2.0 * y * y);
+                    x = (xo - delta_x) * icdist;
+                    y = (yo - delta_y) * icdist;
+                }
+            }
+            Rs2DistortionModel::KannalaBrandt => {
+                let rd = (x * x + y * y).sqrt().max(f32::EPSILON);
+                let mut theta = rd;
+                let mut theta2 = rd * rd;
+                for _ in 0..4 {
+                    let f = theta
+                        * (1.0
+                            + theta2 * (c[0] + theta2 * (c[1] + theta2 * (c[2] + theta2 * c[3]))))
+                        - rd;
+                    if f.abs() < f32::EPSILON {
+                        break;
+                    }
+                    let df = 1.0
+                        + theta2
+                            * (3.0 * c[0]
+                                + theta2
+                                    * (5.0 * c[1] + theta2 * (7.0 * c[2] + 9.0 * theta2 * c[3])));
+                    theta -= f / df;
+                    theta2 = theta * theta;
+                }
+                let r = theta.tan();
+                x *= r / rd;
+                y *= r / rd;
+            }
+            Rs2DistortionModel::FThetaFisheye => {
+                let rd = (x * x + y * y).sqrt().max(f32::EPSILON);
+                // This is not the exact inverse of the projection above, but it is what
+                // librealsense2 uses, so it is kept for consistency with its point clouds.
+                let r = (c[0] * rd).tan() / (2.0 * (c[0] / 2.0).tan()).atan();
+                x *= r / rd;
+                y *= r / rd;
+            }
+            Rs2DistortionModel::BrownConradyModified | Rs2DistortionModel::None => {}
+        }
+
+        [depth * x, depth * y, depth]
+    }
 }
 
 unsafe impl Send for Rs2Intrinsics {}
 
+/// Serialized form of [`Rs2Intrinsics`], which names the distortion model rather than storing its
+/// raw value.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Rs2Intrinsics")]
+struct SerializedIntrinsics {
+    /// Width of the image in pixels.
+    width: i32,
+    /// Height of the image in pixels.
+    height: i32,
+    /// Horizontal coordinate of the principal point of the image.
+    ppx: f32,
+    /// Vertical coordinate of the principal point of the image.
+    ppy: f32,
+    /// Focal length of the image plane, as a multiple of pixel width.
+    fx: f32,
+    /// Focal length of the image plane, as a multiple of pixel height.
+    fy: f32,
+    /// Distortion model of the image.
+    model: Rs2DistortionModel,
+    /// Distortion coefficients.
+    coeffs: [f32; 5usize],
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Rs2Intrinsics {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        SerializedIntrinsics {
+            width: self.0.width,
+            height: self.0.height,
+            ppx: self.0.ppx,
+            ppy: self.0.ppy,
+            fx: self.0.fx,
+            fy: self.0.fy,
+            model: self.distortion().model,
+            coeffs: self.0.coeffs,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Rs2Intrinsics {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let intrinsics = SerializedIntrinsics::deserialize(deserializer)?;
+
+        Ok(Self(sys::rs2_intrinsics {
+            width: intrinsics.width,
+            height: intrinsics.height,
+            ppx: intrinsics.ppx,
+            ppy: intrinsics.ppy,
+            fx: intrinsics.fx,
+            fy: intrinsics.fy,
+            model: intrinsics.model as sys::rs2_distortion,
+            coeffs: intrinsics.coeffs,
+        }))
+    }
+}
+
 /// The topology describing how the different devices are oriented.
 ///
 /// Use the function `stream_profile.extrinsics()` to retrieve these extrinsics from a certain stream in relation to
@@ -159,10 +361,136 @@ impl Rs2Extrinsics {
     pub fn translation(&self) -> [f32; 3usize] {
         self.0.translation
     }
+
+    /// Transforms a 3D point, in meters, from the coordinate frame of the origin stream to that of
+    /// the target stream.
+    ///
+    /// This is a port of `rs2_transform_point_to_point` from librealsense2.
+    pub fn transform_point(&self, point: [f32; 3]) -> [f32; 3] {
+        let r = self.0.rotation;
+        let t = self.0.translation;
+
+        [
+            r[0] * point[0] + r[3] * point[1] + r[6] * point[2] + t[0],
+            r[1] * point[0] + r[4] * point[1] + r[7] * point[2] + t[1],
+            r[2] * point[0] + r[5] * point[1] + r[8] * point[2] + t[2],
+        ]
+    }
+
+    /// Predicate for whether the extrinsics leave points unchanged, i.e. the origin and target
+    /// streams share the same coordinate frame.
+    ///
+    /// Every element of the rotation and translation has to be within `tolerance` of the identity
+    /// transform. Extrinsics that librealsense2 registers as identical, e.g. between an
+    /// [aligned](crate::processing_blocks::Align) frame and the stream it was aligned to, are
+    /// exactly the identity.
+    pub fn is_identity(&self, tolerance: f32) -> bool {
+        const IDENTITY: [f32; 9] = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+
+        let rotation_matches = self
+            .0
+            .rotation
+            .iter()
+            .zip(IDENTITY.iter())
+            .all(|(r, i)| (r - i).abs() <= tolerance);
+        rotation_matches && self.0.translation.iter().all(|t| t.abs() <= tolerance)
+    }
 }
 
 unsafe impl Send for Rs2Extrinsics {}
 
+/// Serialized form of [`Rs2Extrinsics`].
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Rs2Extrinsics")]
+struct SerializedExtrinsics {
+    /// Column-major 3x3 rotation matrix.
+    rotation: [f32; 9usize],
+    /// Three-element translation vector, in meters.
+    translation: [f32; 3usize],
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Rs2Extrinsics {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        SerializedExtrinsics {
+            rotation: self.0.rotation,
+            translation: self.0.translation,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Rs2Extrinsics {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let extrinsics = SerializedExtrinsics::deserialize(deserializer)?;
+
+        Ok(Self(sys::rs2_extrinsics {
+            rotation: extrinsics.rotation,
+            translation: extrinsics.translation,
+        }))
+    }
+}
+
+/// Resolution of a video stream, in pixels.
+///
+/// Use the function `stream_profile.resolution()` to retrieve the resolution of a certain stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Rs2Resolution {
+    /// Width of the frames in the stream.
+    pub width: usize,
+    /// Height of the frames in the stream.
+    pub height: usize,
+}
+
+impl Rs2Resolution {
+    /// Gets the number of pixels in a frame of this resolution.
+    pub fn area(&self) -> usize {
+        self.width * self.height
+    }
+
+    /// Gets the ratio of the width to the height, e.g. `16.0 / 9.0` for 1280x720.
+    pub fn aspect_ratio(&self) -> f32 {
+        self.width as f32 / self.height as f32
+    }
+}
+
+impl fmt::Display for Rs2Resolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+/// Occurs when a string cannot be parsed as a [`Rs2Resolution`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Could not parse \"{0}\" as a resolution of the form \"WIDTHxHEIGHT\".")]
+pub struct ParseResolutionError(pub String);
+
+impl FromStr for Rs2Resolution {
+    type Err = ParseResolutionError;
+
+    /// Parses a resolution of the form `"WIDTHxHEIGHT"`, e.g. `"1280x720"`.
+    ///
+    /// This makes it straightforward to take resolutions as command-line arguments.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseResolutionError(s.to_owned());
+
+        let (width, height) = s.split_once('x').ok_or_else(error)?;
+        Ok(Self {
+            width: width.parse().map_err(|_| error())?,
+            height: height.parse().map_err(|_| error())?,
+        })
+    }
+}
+
 /// Region of interest for the auto exposure algorithm.
 #[derive(Debug, Clone)]
 pub struct Rs2Roi {
@@ -175,3 +503,257 @@ pub struct Rs2Roi {
     /// Bottom coordinate of the region of interest.
     pub max_y: i32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intrinsics(model: Rs2DistortionModel, coeffs: [f32; 5]) -> Rs2Intrinsics {
+        Rs2Intrinsics(sys::rs2_intrinsics {
+            width: 640,
+            height: 480,
+            ppx: 321.5,
+            ppy: 238.25,
+            fx: 615.0,
+            fy: 614.5,
+            model: model as sys::rs2_distortion,
+            coeffs,
+        })
+    }
+
+    fn assert_round_trips(intrinsics: &Rs2Intrinsics) {
+        for pixel in [
+            [321.5, 238.25],
+            [10.0, 20.0],
+            [600.0, 450.0],
+            [150.5, 400.25],
+        ] {
+            let point = intrinsics.deproject(pixel, 1.5);
+            assert!((point[2] - 1.5).abs() < f32::EPSILON);
+
+            let projected = intrinsics.project(point);
+            assert!(
+                (projected[0] - pixel[0]).abs() < 1e-2 && (projected[1] - pixel[1]).abs() < 1e-2,
+                "{:?} round-tripped to {:?} with {:?}",
+                pixel,
+                projected,
+                intrinsics.distortion().model,
+            );
+        }
+    }
+
+    #[test]
+    fn principal_point_deprojects_onto_optical_axis() {
+        let intrinsics = intrinsics(Rs2DistortionModel::None, [0.0; 5]);
+        let point = intrinsics.deproject([321.5, 238.25], 2.0);
+
+        assert_eq!(point, [0.0, 0.0, 2.0]);
+    }
+
+    #[test]
+    fn extrinsics_rotate_then_translate_points() {
+        // A quarter turn about the z-axis, stored column-major.
+        let extrinsics = Rs2Extrinsics(sys::rs2_extrinsics {
+            rotation: [0.0, 1.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0, 1.0],
+            translation: [0.5, 0.0, -0.25],
+        });
+
+        assert_eq!(
+            extrinsics.transform_point([1.0, 2.0, 3.0]),
+            [-1.5, 1.0, 2.75]
+        );
+    }
+
+    #[test]
+    fn only_extrinsics_within_tolerance_of_identity_are_identity() {
+        let mut extrinsics = Rs2Extrinsics(sys::rs2_extrinsics {
+            rotation: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+            translation: [0.0; 3],
+        });
+        assert!(extrinsics.is_identity(0.0));
+
+        extrinsics.0.translation[0] = 0.015;
+        assert!(!extrinsics.is_identity(1e-6));
+        assert!(extrinsics.is_identity(0.02));
+
+        extrinsics.0.translation[0] = 0.0;
+        extrinsics.0.rotation[1] = -0.004;
+        assert!(!extrinsics.is_identity(1e-6));
+    }
+
+    #[test]
+    fn deproject_and_project_round_trip() {
+        assert_round_trips(&intrinsics(Rs2DistortionModel::None, [0.0; 5]));
+        assert_round_trips(&intrinsics(
+            Rs2DistortionModel::BrownConrady,
+            [0.12, -0.25, 0.001, -0.0005, 0.08],
+        ));
+        assert_round_trips(&intrinsics(
+            Rs2DistortionModel::KannalaBrandt,
+            [-0.005, 0.04, -0.037, 0.006, 0.0],
+        ));
+    }
+
+    #[test]
+    fn project_and_deproject_match_librealsense() {
+        // Reference values computed with `rs2_project_point_to_pixel` and
+        // `rs2_deproject_pixel_to_point` from librealsense2's `rsutil.h`.
+        let brown_conrady = [0.12, -0.25, 0.001, -0.0005, 0.08];
+        let cases = [
+            (
+                intrinsics(Rs2DistortionModel::None, [0.0; 5]),
+                [424.0, 187.041_66],
+                [0.679_268_2, 0.516_883_6],
+            ),
+            (
+                intrinsics(Rs2DistortionModel::BrownConradyModified, brown_conrady),
+                [424.351_44, 186.882_1],
+                [0.679_268_2, 0.516_883_6],
+            ),
+            (
+                intrinsics(Rs2DistortionModel::BrownConradyInverse, brown_conrady),
+                [424.351_44, 186.882_1],
+                [0.668_967_5, 0.508_405_7],
+            ),
+            (
+                intrinsics(Rs2DistortionModel::FThetaFisheye, [0.9, 0.0, 0.0, 0.0, 0.0]),
+                [430.363_2, 183.862_66],
+                [0.873_596, 0.664_755_7],
+            ),
+            (
+                intrinsics(Rs2DistortionModel::BrownConrady, brown_conrady),
+                [424.351_7, 186.881_96],
+                [0.668_972_25, 0.508_409_26],
+            ),
+            (
+                intrinsics(
+                    Rs2DistortionModel::KannalaBrandt,
+                    [-0.005, 0.04, -0.037, 0.006, 0.0],
+                ),
+                [422.825_1, 187.628_63],
+                [0.762_201_1, 0.579_990_7],
+            ),
+        ];
+
+        for (intrinsics, pixel, point) in cases.iter() {
+            let model = intrinsics.distortion().model;
+
+            let projected = intrinsics.project([0.25, -0.125, 1.5]);
+            assert!(
+                (projected[0] - pixel[0]).abs() < 1e-3 && (projected[1] - pixel[1]).abs() < 1e-3,
+                "{:?} projected to {:?} instead of {:?}",
+                model,
+                projected,
+                pixel,
+            );
+
+            let deprojected = intrinsics.deproject([600.0, 450.0], 1.5);
+            assert!(
+                (deprojected[0] - point[0]).abs() < 1e-5
+                    && (deprojected[1] - point[1]).abs() < 1e-5
+                    && (deprojected[2] - 1.5).abs() < f32::EPSILON,
+                "{:?} deprojected to {:?} instead of {:?}",
+                model,
+                deprojected,
+                point,
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn intrinsics_round_trip_through_serde() {
+        let original = intrinsics(
+            Rs2DistortionModel::BrownConradyInverse,
+            [0.12, -0.25, 0.001, -0.0005, 0.08],
+        );
+
+        let json = serde_json::to_string(&original).unwrap();
+        assert!(json.contains("\"model\":\"BrownConradyInverse\""));
+
+        let intrinsics: Rs2Intrinsics = serde_json::from_str(&json).unwrap();
+        assert_eq!(intrinsics.width(), original.width());
+        assert_eq!(intrinsics.height(), original.height());
+        assert_eq!(
+            [intrinsics.ppx(), intrinsics.ppy()],
+            [original.ppx(), original.ppy()]
+        );
+        assert_eq!(
+            [intrinsics.fx(), intrinsics.fy()],
+            [original.fx(), original.fy()]
+        );
+        assert_eq!(intrinsics.distortion().model, original.distortion().model);
+        assert_eq!(intrinsics.distortion().coeffs, original.distortion().coeffs);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn extrinsics_round_trip_through_serde() {
+        let original = Rs2Extrinsics(sys::rs2_extrinsics {
+            rotation: [0.0, 1.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0, 1.0],
+            translation: [0.5, 0.0, -0.25],
+        });
+
+        let json = serde_json::to_string(&original).unwrap();
+        let extrinsics: Rs2Extrinsics = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(extrinsics.rotation(), original.rotation());
+        assert_eq!(extrinsics.translation(), original.translation());
+    }
+
+    #[test]
+    fn resolution_round_trips_through_strings() {
+        for (width, height) in [(1280, 720), (640, 480), (848, 100), (1, 1)] {
+            let resolution = Rs2Resolution { width, height };
+            let string = resolution.to_string();
+
+            assert_eq!(string, format!("{}x{}", width, height));
+            assert_eq!(string.parse::<Rs2Resolution>(), Ok(resolution));
+        }
+    }
+
+    #[test]
+    fn invalid_resolutions_do_not_parse() {
+        for string in [
+            "",
+            "1280",
+            "1280x",
+            "x720",
+            "1280X720",
+            "1280x720x3",
+            "-1x720",
+            "a x b",
+        ] {
+            assert_eq!(
+                string.parse::<Rs2Resolution>(),
+                Err(ParseResolutionError(string.to_owned()))
+            );
+        }
+    }
+
+    #[test]
+    fn resolution_area_and_aspect_ratio() {
+        let resolution = Rs2Resolution {
+            width: 1280,
+            height: 720,
+        };
+
+        assert_eq!(resolution.area(), 921_600);
+        assert!((resolution.aspect_ratio() - 16.0 / 9.0).abs() < f32::EPSILON);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn resolution_round_trips_through_serde() {
+        let original = Rs2Resolution {
+            width: 1280,
+            height: 720,
+        };
+
+        let json = serde_json::to_string(&original).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Rs2Resolution>(&json).unwrap(),
+            original
+        );
+    }
+}
@@ -0,0 +1,323 @@
+//! Convenience functions for capturing frames in one call.
+//!
+//! The most common first step with a depth camera is to grab a color image along with the
+//! distance to every one of its pixels. Doing this with the primitives of this crate takes an
+//! [`Align`] block, a [`UnitsTransform`] and the intrinsics of the color stream, which
+//! [`snapshot`] packages into a single call. It is built entirely on the public API, so when more
+//! control is needed, e.g. to reuse the processing blocks across frames, the same steps can be
+//! performed by hand.
+//!
+//! Similarly, [`Capture`] packages the common pattern of waiting for frames on a dedicated thread,
+//! so that e.g. a UI thread can pick up the latest frames without blocking.
+
+use crate::{
+    base::Rs2Intrinsics,
+    frame::{ColorFrame, CompositeFrame, DepthFrame, FrameEx, MissingFrameError},
+    frame_queue::{FrameQueue, FrameQueueConstructionError},
+    kind::Rs2StreamKind,
+    pipeline::{ActivePipeline, FrameWaitError, InactivePipeline},
+    processing_blocks::{Align, UnitsTransform},
+};
+use anyhow::Result;
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+use thiserror::Error;
+
+/// How long the capture thread waits for frames before checking if the capture was stopped.
+const WAIT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The name of the capture thread.
+const THREAD_NAME: &str = "rs2-capture";
+
+/// Occurs when a [`Capture`] cannot be started.
+#[derive(Error, Debug)]
+pub enum CaptureError {
+    /// Could not create the queue holding the framesets of the capture.
+    #[error("{0}")]
+    CouldNotCreateQueue(FrameQueueConstructionError),
+    /// Could not spawn the thread waiting for frames.
+    #[error("Could not spawn capture thread. Reason: {0}")]
+    CouldNotSpawnThread(io::Error),
+}
+
+/// A color image and the distance to every one of its pixels, captured at the same time.
+///
+/// Captured with [`snapshot`].
+#[derive(Debug)]
+pub struct Snapshot {
+    /// The color image.
+    pub color: ColorFrame,
+    /// The distance in meters to every pixel of [`color`](Self::color), in row-major order.
+    ///
+    /// A distance of zero means that the depth of the pixel is unknown.
+    pub depth_meters: Vec<f32>,
+    /// The intrinsics of the color stream, which the depth has been aligned to.
+    ///
+    /// These can be used to e.g. [deproject](Rs2Intrinsics::deproject) a pixel to a 3D point.
+    pub intrinsics: Rs2Intrinsics,
+}
+
+impl Snapshot {
+    /// Gets the width of the snapshot in pixels.
+    pub fn width(&self) -> usize {
+        self.color.width()
+    }
+
+    /// Gets the height of the snapshot in pixels.
+    pub fn height(&self) -> usize {
+        self.color.height()
+    }
+
+    /// Gets the distance in meters to the pixel at `(col, row)`.
+    ///
+    /// Returns `None` if the pixel is outside of the snapshot.
+    pub fn distance(&self, col: usize, row: usize) -> Option<f32> {
+        if col >= self.width() || row >= self.height() {
+            return None;
+        }
+        self.depth_meters.get(row * self.width() + col).copied()
+    }
+}
+
+/// Captures the next color and depth frames of the pipeline, with the depth aligned to color.
+///
+/// The pipeline must stream both [`Rs2StreamKind::Color`] and [`Rs2StreamKind::Depth`]. This
+/// blocks until the next frameset arrives, using the default timeout of
+/// [`ActivePipeline::wait`].
+///
+/// The processing blocks are created for every call, which is fine for capturing the occasional
+/// snapshot. To process every frame of a stream, create the blocks once instead.
+///
+/// # Errors
+///
+/// Returns [`MissingFrameError`] if the frameset does not hold a color and a depth frame.
+///
+/// Returns an error if waiting for the frameset, or any of the processing steps, fails.
+pub fn snapshot(pipeline: &mut ActivePipeline) -> Result<Snapshot> {
+    let frames = pipeline.wait(None)?;
+    let aligned = Align::new(Rs2StreamKind::Color)?.process(frames)?;
+
+    let color = aligned
+        .frames_of_type::<ColorFrame>()
+        .pop()
+        .ok_or(MissingFrameError(Rs2StreamKind::Color))?;
+    let depth = aligned
+        .frames_of_type::<DepthFrame>()
+        .pop()
+        .ok_or(MissingFrameError(Rs2StreamKind::Depth))?;
+
+    let distance = UnitsTransform::new()?.process(depth)?;
+    let depth_meters = pack_rows(
+        distance.as_slice_f32()?,
+        distance.width(),
+        distance.height(),
+        distance.stride() / std::mem::size_of::<f32>(),
+    );
+    let intrinsics = color.stream_profile().intrinsics()?;
+
+    Ok(Snapshot {
+        color,
+        depth_meters,
+        intrinsics,
+    })
+}
+
+/// State shared between a [`Capture`] and the thread waiting for frames on its behalf.
+#[derive(Debug)]
+struct Shared {
+    /// Framesets received from the pipeline that have not been taken yet.
+    queue: FrameQueue,
+    /// Whether the capture has asked the thread to stop waiting for frames.
+    is_stopped: AtomicBool,
+    /// The error that ended the thread, if it has not been reported yet.
+    error: Mutex<Option<FrameWaitError>>,
+}
+
+impl Shared {
+    /// Takes the error that ended the thread, if any.
+    fn take_error(&self) -> Option<FrameWaitError> {
+        self.error.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+}
+
+/// Waits for the framesets of a pipeline on a dedicated thread, and holds them until they are
+/// taken with [`next_frame`](Self::next_frame).
+///
+/// The thread moves every frameset into a [`FrameQueue`] holding at most `capacity` framesets. If
+/// framesets are not taken fast enough, the oldest ones are dropped, so the capture always holds
+/// the most recent framesets. This keeps the pipeline from dropping frames while the thread that
+/// consumes them is busy, e.g. rendering a UI.
+///
+/// If an error occurs while waiting, e.g. because the device was disconnected, the thread stops
+/// and the error is returned by `next_frame` once the framesets received before it are taken.
+///
+/// Dropping the capture stops and joins the thread, then drops the pipeline along with any
+/// framesets that were not taken. To get the pipeline back instead, use [`stop`](Self::stop).
+///
+/// The thread is named `rs2-capture`, so it can be told apart in debuggers and profilers.
+///
+/// ```no_run
+/// use realsense_rust::{capture::Capture, pipeline::ActivePipeline};
+/// use std::time::Duration;
+///
+/// # fn run(pipeline: ActivePipeline) -> anyhow::Result<()> {
+/// let capture = Capture::new(pipeline, 2)?;
+/// loop {
+///     if let Some(frames) = capture.next_frame(Duration::from_millis(10))? {
+///         println!("Got {} frames", frames.count());
+///     }
+///     // Do other work in between.
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Capture {
+    /// State shared with the capture thread.
+    shared: Arc<Shared>,
+    /// Handle to the capture thread, which returns the pipeline once it is done.
+    thread: Option<JoinHandle<ActivePipeline>>,
+}
+
+impl Drop for Capture {
+    fn drop(&mut self) {
+        if let Some(thread) = self.stop_thread() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Capture {
+    /// Starts waiting for the framesets of `pipeline` on a dedicated thread, holding at most
+    /// `capacity` framesets until they are taken.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CaptureError::CouldNotCreateQueue`] if the queue of framesets cannot be created,
+    /// or [`CaptureError::CouldNotSpawnThread`] if the capture thread cannot be spawned. The
+    /// pipeline is dropped in either case.
+    pub fn new(pipeline: ActivePipeline, capacity: usize) -> Result<Self, CaptureError> {
+        let queue = FrameQueue::new(capacity.max(1)).map_err(CaptureError::CouldNotCreateQueue)?;
+        let shared = Arc::new(Shared {
+            queue,
+            is_stopped: AtomicBool::new(false),
+            error: Mutex::new(None),
+        });
+
+        let thread_shared = Arc::clone(&shared);
+        let thread = thread::Builder::new()
+            .name(THREAD_NAME.to_owned())
+            .spawn(move || Self::wait_for_frames(pipeline, &thread_shared))
+            .map_err(CaptureError::CouldNotSpawnThread)?;
+
+        Ok(Self {
+            shared,
+            thread: Some(thread),
+        })
+    }
+
+    /// Takes the oldest frameset that has not been taken yet, waiting up to `timeout` for one to
+    /// arrive.
+    ///
+    /// Returns `Ok(None)` if no frameset arrives before the timeout.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameWaitError`] if waiting for frames failed on the capture thread. The error
+    /// is returned once, after every frameset received before it has been taken. Afterwards, no
+    /// more framesets arrive.
+    ///
+    /// Returns an error if the frame queue fails while waiting.
+    pub fn next_frame(&self, timeout: Duration) -> Result<Option<CompositeFrame>> {
+        if self.shared.queue.is_empty() {
+            if let Some(e) = self.shared.take_error() {
+                return Err(e.into());
+            }
+        }
+
+        match self.shared.queue.wait::<CompositeFrame>(Some(timeout)) {
+            Ok(frames) => Ok(Some(frames)),
+            Err(e) if is_timeout(&e) => match self.shared.take_error() {
+                Some(e) => Err(e.into()),
+                None => Ok(None),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Stops waiting for frames and stops the pipeline.
+    ///
+    /// The capture thread is joined before the pipeline is stopped, so it has exited once this
+    /// returns. Any framesets that have not been taken are dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the capture thread panicked.
+    pub fn stop(mut self) -> InactivePipeline {
+        let thread = self.stop_thread().unwrap();
+        thread.join().expect("capture thread panicked").stop()
+    }
+
+    /// Asks the capture thread to stop, and returns its handle if it has not been joined yet.
+    fn stop_thread(&mut self) -> Option<JoinHandle<ActivePipeline>> {
+        self.shared.is_stopped.store(true, Ordering::SeqCst);
+        self.thread.take()
+    }
+
+    /// Moves framesets from `pipeline` into the queue until the capture is stopped or an error
+    /// occurs, then returns the pipeline.
+    fn wait_for_frames(mut pipeline: ActivePipeline, shared: &Shared) -> ActivePipeline {
+        while !shared.is_stopped.load(Ordering::SeqCst) {
+            match pipeline.try_wait(Some(WAIT_INTERVAL)) {
+                Ok(Some(frames)) => shared.queue.enqueue_composite(frames),
+                Ok(None) => {}
+                Err(e) => {
+                    *shared.error.lock().unwrap_or_else(|e| e.into_inner()) = Some(e);
+                    break;
+                }
+            }
+        }
+        pipeline
+    }
+}
+
+/// Predicate for whether `error` is a timeout while waiting on a frame queue.
+fn is_timeout(error: &anyhow::Error) -> bool {
+    matches!(
+        error.downcast_ref::<FrameWaitError>(),
+        Some(FrameWaitError::DidTimeoutBeforeFrameArrival)
+    )
+}
+
+/// Copies the first `width` values of each of the first `height` rows of `data`, which start
+/// every `row_len` values, dropping the padding at the end of each row.
+fn pack_rows(data: &[f32], width: usize, height: usize, row_len: usize) -> Vec<f32> {
+    data.chunks(row_len)
+        .take(height)
+        .flat_map(|row| &row[..width])
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_rows_drops_row_padding() {
+        let data = [1.0, 2.0, 0.0, 3.0, 4.0, 0.0];
+        assert_eq!(pack_rows(&data, 2, 2, 3), vec![1.0, 2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn pack_rows_of_unpadded_data_is_a_copy() {
+        let data = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(pack_rows(&data, 2, 2, 2), data.to_vec());
+    }
+}
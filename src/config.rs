@@ -1,13 +1,21 @@
 //! A type representing the [`Pipeline`](crate::pipeline::InactivePipeline) configuration.
 
 use crate::{
-    base::from_path,
+    base::{from_path, Rs2Resolution},
     check_rs2_error,
     kind::{Rs2Exception, Rs2Format, Rs2StreamKind},
+    pipeline::{InactivePipeline, PipelineProfile},
+    stream_profile::StreamProfile,
 };
 use anyhow::Result;
 use realsense_sys as sys;
-use std::{convert::TryInto, ffi::CStr, path::Path, ptr::NonNull};
+use std::{
+    convert::{TryFrom, TryInto},
+    ffi::{CStr, CString},
+    fmt,
+    path::{Path, PathBuf},
+    ptr::NonNull,
+};
 use thiserror::Error;
 
 /// Type describing all possible errors that can occur when trying to configure a pipeline.
@@ -31,6 +39,63 @@ pub enum ConfigurationError {
     /// Recording to file could not be enabled for the specified device.
     #[error("Could not enable recording to file from device. Type: {0}; Reason: {1}")]
     CouldNotEnableRecordingToFile(Rs2Exception, String),
+    /// The configuration could not be resolved to a device and set of streams.
+    #[error("Could not resolve configuration. Type: {0}; Reason: {1}")]
+    CouldNotResolve(Rs2Exception, String),
+}
+
+/// A stream that a device offers, as listed by [`UnsupportedStreamsError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OfferedStream {
+    /// The kind of stream.
+    pub kind: Rs2StreamKind,
+    /// The index of the stream.
+    pub index: usize,
+    /// The resolution of the stream, if it is a video stream.
+    pub resolution: Option<Rs2Resolution>,
+    /// The data format of the stream.
+    pub format: Rs2Format,
+    /// The framerate of the stream in frames per second.
+    pub framerate: usize,
+}
+
+impl From<&StreamProfile> for OfferedStream {
+    fn from(profile: &StreamProfile) -> Self {
+        Self {
+            kind: profile.kind(),
+            index: profile.index(),
+            resolution: profile.resolution().ok(),
+            format: profile.format(),
+            framerate: profile.framerate() as usize,
+        }
+    }
+}
+
+impl fmt::Display for OfferedStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.kind, self.index)?;
+        if let Some(resolution) = self.resolution {
+            write!(f, " {}", resolution)?;
+        }
+        write!(f, " {} @ {} fps", self.format, self.framerate)
+    }
+}
+
+/// Occurs when a [`ConfigBuilder`] is resolved against a device that does not offer the requested
+/// streams.
+///
+/// The error lists every stream that the device offers, so that a configuration can be picked
+/// from them, e.g. a lower resolution or a different format.
+#[derive(Error, Debug)]
+#[error(
+    "Could not resolve configuration: {reason}. The device offers: {}",
+    offered.iter().map(|s| s.to_string()).collect::<Vec<_>>().join(", ")
+)]
+pub struct UnsupportedStreamsError {
+    /// The reason reported by librealsense2.
+    pub reason: String,
+    /// The streams that the device offers.
+    pub offered: Vec<OfferedStream>,
 }
 
 /// Type representing the [`Pipeline`](crate::pipeline::InactivePipeline) configuration.
@@ -70,7 +135,10 @@ impl Config {
             // there isn't going to be a whole lot you can do.
             //
             // We're better off letting Rust panic due to NonNull::new(ptr).unwrap() than to add
-            // any extra logic on top.
+            // any extra logic on top. The error still has to be freed so that it does not leak.
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+            }
 
             Self {
                 config_ptr: NonNull::new(ptr).unwrap(),
@@ -331,6 +399,45 @@ impl Config {
         Ok(self)
     }
 
+    /// Predicate for whether the configuration can be resolved against a pipeline.
+    ///
+    /// This is the same check as [`InactivePipeline::can_resolve`], and has no side effects on the
+    /// pipeline or any device. It is useful as a pre-flight check before starting the pipeline,
+    /// e.g. to fall back to a lower resolution if the device cannot stream the requested one. Use
+    /// [`resolve`](Self::resolve) instead to find out why a configuration cannot be resolved.
+    pub fn can_resolve(&self, pipeline: &InactivePipeline) -> bool {
+        pipeline.can_resolve(self)
+    }
+
+    /// Resolve the configuration against a pipeline, reporting why it fails if it cannot.
+    ///
+    /// This is similar to [`InactivePipeline::resolve`], and returns the pipeline profile (device
+    /// and streams) that would be used if the pipeline were started with this configuration.
+    /// Unlike that method, the reason the configuration could not be resolved is reported back as
+    /// an error. This is useful for diagnosing invalid stream / format combinations, since these
+    /// are not checked when calling [`Config::enable_stream`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigurationError::CouldNotResolve`] if no device or set of streams satisfies
+    /// the configuration.
+    ///
+    /// Returns an error if the resolved pipeline profile cannot be constructed.
+    ///
+    pub fn resolve(&self, pipeline: &InactivePipeline) -> Result<PipelineProfile> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let profile_ptr = sys::rs2_config_resolve(
+                self.config_ptr.as_ptr(),
+                pipeline.get_raw().as_ptr(),
+                &mut err,
+            );
+            check_rs2_error!(err, ConfigurationError::CouldNotResolve)?;
+
+            PipelineProfile::try_from(NonNull::new(profile_ptr).unwrap())
+        }
+    }
+
     /// Get the underlying low-level pointer to the configuration object.
     ///
     /// # Safety
@@ -343,3 +450,244 @@ impl Config {
         self.config_ptr
     }
 }
+
+/// A single stream requested through a [`ConfigBuilder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct StreamRequest {
+    /// The kind of stream.
+    kind: Rs2StreamKind,
+    /// The index of the stream, or `None` to let librealsense2 pick.
+    index: Option<usize>,
+    /// The width of the stream in pixels.
+    width: usize,
+    /// The height of the stream in pixels.
+    height: usize,
+    /// The data format of the stream.
+    format: Rs2Format,
+    /// The framerate of the stream in frames per second.
+    framerate: usize,
+}
+
+/// Builder for the most common [`Config`]urations.
+///
+/// Each method requests one stream, with a format that every RealSense device supports for that
+/// stream unless the format is given explicitly. Only the requested streams are enabled in the
+/// built configuration. For example, a typical depth and color configuration is:
+///
+/// ```no_run
+/// # use realsense_rust::{config::ConfigBuilder, kind::Rs2Format};
+/// let config = ConfigBuilder::new()
+///     .depth(640, 480, 30)
+///     .color(640, 480, 30, Rs2Format::Rgb8)
+///     .build()?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// As with [`Config::enable_stream`], the requested streams are not checked against any device
+/// until the configuration is resolved, e.g. with [`Config::resolve`]. Passing zero for the width,
+/// height, or framerate of a stream lets librealsense2 pick a suitable value.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigBuilder {
+    /// The requested streams, in the order they were requested.
+    streams: Vec<StreamRequest>,
+    /// The serial number of the device to use, if any.
+    serial: Option<CString>,
+    /// The recording to play back instead of using a connected device, if any.
+    file: Option<PathBuf>,
+}
+
+impl ConfigBuilder {
+    /// Constructs a builder without any requested streams.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests a depth stream with [`Rs2Format::Z16`] data.
+    pub fn depth(self, width: usize, height: usize, framerate: usize) -> Self {
+        self.stream(
+            Rs2StreamKind::Depth,
+            None,
+            width,
+            height,
+            Rs2Format::Z16,
+            framerate,
+        )
+    }
+
+    /// Requests a color stream with data in the given `format`.
+    pub fn color(self, width: usize, height: usize, framerate: usize, format: Rs2Format) -> Self {
+        self.stream(Rs2StreamKind::Color, None, width, height, format, framerate)
+    }
+
+    /// Requests the infrared stream at `index` with [`Rs2Format::Y8`] data.
+    ///
+    /// On stereo devices, the left imager has index 1 and the right imager has index 2.
+    pub fn infrared(self, index: usize, width: usize, height: usize, framerate: usize) -> Self {
+        self.stream(
+            Rs2StreamKind::Infrared,
+            Some(index),
+            width,
+            height,
+            Rs2Format::Y8,
+            framerate,
+        )
+    }
+
+    /// Requests an arbitrary stream. See [`Config::enable_stream`] for the meaning of each
+    /// argument.
+    pub fn stream(
+        mut self,
+        kind: Rs2StreamKind,
+        index: Option<usize>,
+        width: usize,
+        height: usize,
+        format: Rs2Format,
+        framerate: usize,
+    ) -> Self {
+        self.streams.push(StreamRequest {
+            kind,
+            index,
+            width,
+            height,
+            format,
+            framerate,
+        });
+        self
+    }
+
+    /// Restricts the configuration to the device with the given serial number.
+    pub fn serial(mut self, serial: &CStr) -> Self {
+        self.serial = Some(serial.to_owned());
+        self
+    }
+
+    /// Plays back the recording at `path` once, instead of using a connected device.
+    pub fn file<P>(mut self, path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        self.file = Some(path.as_ref().to_owned());
+        self
+    }
+
+    /// Builds the configuration, enabling only the requested streams.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NulError`](std::ffi::NulError) if the path of the [recording](Self::file) cannot
+    /// be represented as a [`CString`].
+    ///
+    /// Returns [`ConfigurationError`] if the device or any of the streams cannot be enabled.
+    pub fn build(&self) -> Result<Config> {
+        let mut config = self.build_device()?;
+        config.disable_all_streams()?;
+        for stream in &self.streams {
+            config.enable_stream(
+                stream.kind,
+                stream.index,
+                stream.width,
+                stream.height,
+                stream.format,
+                stream.framerate,
+            )?;
+        }
+        Ok(config)
+    }
+
+    /// Builds the configuration and resolves it against `pipeline`.
+    ///
+    /// This is the same as [`Config::resolve`], except that a device which does not offer the
+    /// requested streams is reported with the streams that it does offer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UnsupportedStreamsError`] if the device does not offer the requested streams.
+    ///
+    /// Returns [`ConfigurationError::CouldNotResolve`] if there is no device to resolve against.
+    ///
+    /// Otherwise fails like [`build`](Self::build) and [`Config::resolve`].
+    pub fn resolve(&self, pipeline: &InactivePipeline) -> Result<PipelineProfile> {
+        let error = match self.build()?.resolve(pipeline) {
+            Ok(profile) => return Ok(profile),
+            Err(e) => e,
+        };
+
+        let reason = match error.downcast_ref::<ConfigurationError>() {
+            Some(ConfigurationError::CouldNotResolve(_, reason)) => reason.clone(),
+            _ => return Err(error),
+        };
+
+        // Resolving every stream of the device only fails if there is no device at all, in which
+        // case there are no streams to offer either.
+        let mut device_config = self.build_device()?;
+        device_config.enable_all_streams()?;
+        let device_profile = match device_config.resolve(pipeline) {
+            Ok(profile) => profile,
+            Err(_) => return Err(error),
+        };
+
+        let offered = device_profile
+            .device()
+            .sensors()
+            .iter()
+            .flat_map(|sensor| sensor.stream_profiles())
+            .map(|profile| OfferedStream::from(&profile))
+            .collect();
+        Err(UnsupportedStreamsError { reason, offered }.into())
+    }
+
+    /// Builds a configuration which selects the device, without changing the enabled streams.
+    fn build_device(&self) -> Result<Config> {
+        let mut config = Config::new();
+        if let Some(serial) = &self.serial {
+            config.enable_device_from_serial(serial)?;
+        }
+        if let Some(file) = &self.file {
+            config.enable_device_from_file(file, false)?;
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsupported_streams_error_lists_offered_streams() {
+        let error = UnsupportedStreamsError {
+            reason: String::from("Couldn't resolve requests"),
+            offered: vec![
+                OfferedStream {
+                    kind: Rs2StreamKind::Depth,
+                    index: 0,
+                    resolution: Some(Rs2Resolution {
+                        width: 640,
+                        height: 480,
+                    }),
+                    format: Rs2Format::Z16,
+                    framerate: 30,
+                },
+                OfferedStream {
+                    kind: Rs2StreamKind::Gyro,
+                    index: 0,
+                    resolution: None,
+                    format: Rs2Format::MotionXyz32F,
+                    framerate: 200,
+                },
+            ],
+        };
+
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Could not resolve configuration: Couldn't resolve requests. The device offers: \
+                 {} 0 640x480 {} @ 30 fps, {} 0 {} @ 200 fps",
+                Rs2StreamKind::Depth,
+                Rs2Format::Z16,
+                Rs2StreamKind::Gyro,
+                Rs2Format::MotionXyz32F,
+            )
+        );
+    }
+}
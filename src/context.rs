@@ -5,19 +5,247 @@ use crate::{
     check_rs2_error,
     device::Device,
     device_hub::DeviceHub,
-    kind::{Rs2Exception, Rs2ProductLine},
+    kind::{product_line_mask, Rs2Exception, Rs2ProductLine},
 };
 use anyhow::Result;
-use num_traits::ToPrimitive;
 use realsense_sys as sys;
-use std::{collections::HashSet, convert::From, path::Path, ptr::NonNull};
+use std::{
+    collections::HashSet,
+    convert::From,
+    ops::Range,
+    os::raw::c_void,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    ptr::NonNull,
+    thread,
+    time::{Duration, Instant},
+};
 use thiserror::Error;
 
+/// How often [`Context::wait_for_device`] queries the connected devices.
+const DEVICE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The boxed closure type that is invoked whenever devices are connected or disconnected.
+type DevicesChangedCallback = Box<dyn FnMut(DevicesChangedEvent) + Send>;
+
 /// Type describing a RealSense context, used by the rest of the API.
-#[derive(Debug)]
+///
+/// Devices, sensors and pipelines created from a context each hold a reference to the underlying
+/// librealsense2 context, which is only destroyed once the last of them is dropped. A context can
+/// therefore be dropped in any order relative to what was created from it, e.g. while a pipeline
+/// is still streaming. See the [architecture docs](crate::docs::architecture) for more on the
+/// ownership model.
 pub struct Context {
     /// A non-null pointer to the underlying librealsense context.
     context_ptr: NonNull<sys::rs2_context>,
+    /// The callback registered through [`Context::set_devices_changed_callback`], if any.
+    ///
+    /// This is double-boxed so that a thin pointer to it can be handed to librealsense2. It is
+    /// owned by the context so that the closure lives exactly as long as librealsense2 may call
+    /// it.
+    devices_changed_callback: Option<Box<DevicesChangedCallback>>,
+    /// Callbacks that have since been replaced by another call to
+    /// [`Context::set_devices_changed_callback`].
+    ///
+    /// librealsense2 may still be running a replaced callback on its notification thread when the
+    /// new one is registered, so these are only dropped along with the context. They stay boxed so
+    /// that the pointers handed to librealsense2 remain valid.
+    #[allow(clippy::vec_box)]
+    replaced_devices_changed_callbacks: Vec<Box<DevicesChangedCallback>>,
+}
+
+impl std::fmt::Debug for Context {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Context")
+            .field("context_ptr", &self.context_ptr)
+            .field(
+                "has_devices_changed_callback",
+                &self.devices_changed_callback.is_some(),
+            )
+            .finish()
+    }
+}
+
+/// An event describing which devices were connected or disconnected from the host.
+///
+/// These events are delivered to the callback registered through
+/// [`Context::set_devices_changed_callback`].
+#[derive(Debug)]
+pub struct DevicesChangedEvent {
+    /// The list of devices that were disconnected.
+    removed: Option<DeviceList>,
+    /// The list of devices that were connected.
+    added: Option<DeviceList>,
+}
+
+impl DevicesChangedEvent {
+    /// Get the devices that were connected to the host.
+    pub fn added_devices(&self) -> Vec<Device> {
+        match &self.added {
+            Some(added) => added.devices().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Get the devices that were disconnected from the host.
+    ///
+    /// Since these devices are no longer connected, some devices may not be able to be
+    /// constructed at all. Such devices are skipped. To check if a specific device that you are
+    /// holding on to was disconnected, prefer [`DevicesChangedEvent::was_removed`].
+    pub fn removed_devices(&self) -> Vec<Device> {
+        match &self.removed {
+            Some(removed) => removed.devices().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Predicate for checking if `device` was disconnected from the host.
+    pub fn was_removed(&self, device: &Device) -> bool {
+        let removed = match &self.removed {
+            Some(removed) => removed,
+            None => return false,
+        };
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let contains = sys::rs2_device_list_contains(
+                removed.list_ptr.as_ptr(),
+                device.get_raw().as_ptr(),
+                &mut err,
+            );
+
+            if err.as_ref().is_none() {
+                contains != 0
+            } else {
+                sys::rs2_free_error(err);
+                false
+            }
+        }
+    }
+}
+
+/// Trampoline through which librealsense2 invokes the devices-changed callback.
+///
+/// # Safety
+///
+/// `user` must either point to the [`DevicesChangedCallback`] owned by the [`Context`] that
+/// registered this trampoline, or be null if the context has been dropped, in which case the event
+/// is discarded. Ownership of both device lists is taken by this function.
+unsafe extern "C" fn devices_changed_trampoline(
+    removed: *mut sys::rs2_device_list,
+    added: *mut sys::rs2_device_list,
+    user: *mut c_void,
+) {
+    // The device lists passed to the callback are owned by the receiver.
+    let event = DevicesChangedEvent {
+        removed: NonNull::new(removed).map(DeviceList::new),
+        added: NonNull::new(added).map(DeviceList::new),
+    };
+    if user.is_null() {
+        return;
+    }
+
+    let callback = &mut *(user as *mut DevicesChangedCallback);
+
+    // Unwinding across the FFI boundary is undefined behaviour, so any panic in the callback stops
+    // here.
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| callback(event)));
+}
+
+/// A list of devices, which is deleted when dropped.
+#[derive(Debug)]
+struct DeviceList {
+    /// A (non-null) pointer to the device list.
+    list_ptr: NonNull<sys::rs2_device_list>,
+    /// The number of devices in the list.
+    len: i32,
+}
+
+impl Drop for DeviceList {
+    fn drop(&mut self) {
+        unsafe {
+            sys::rs2_delete_device_list(self.list_ptr.as_ptr());
+        }
+    }
+}
+
+unsafe impl Send for DeviceList {}
+
+impl DeviceList {
+    /// Takes ownership of the device list behind `list_ptr`.
+    ///
+    /// The list is treated as empty if the number of devices in it cannot be read.
+    fn new(list_ptr: NonNull<sys::rs2_device_list>) -> Self {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let len = sys::rs2_get_device_count(list_ptr.as_ptr(), &mut err);
+
+            let len = if err.as_ref().is_none() {
+                len
+            } else {
+                sys::rs2_free_error(err);
+                0
+            };
+
+            Self { list_ptr, len }
+        }
+    }
+
+    /// Constructs the device at `index`, or returns `None` if it cannot be constructed.
+    fn device(&self, index: i32) -> Option<Device> {
+        Device::try_create(&self.list_ptr, index).ok()
+    }
+
+    /// Constructs all the devices in the list, skipping those that cannot be constructed.
+    fn devices(&self) -> impl Iterator<Item = Device> + '_ {
+        (0..self.len).filter_map(move |index| self.device(index))
+    }
+}
+
+/// Iterator over the devices connected to the host, which constructs each device lazily.
+///
+/// Constructed with [`Context::devices`].
+#[derive(Debug)]
+pub struct DeviceListIter {
+    /// The list of devices, which is deleted when the iterator is dropped.
+    ///
+    /// This is `None` if the devices could not be queried.
+    device_list: Option<DeviceList>,
+    /// The indices of the devices in the list that have not been reached yet.
+    indices: Range<i32>,
+}
+
+impl DeviceListIter {
+    /// Takes ownership of `device_list` to iterate over its devices.
+    fn new(device_list: DeviceList) -> Self {
+        Self {
+            indices: 0..device_list.len,
+            device_list: Some(device_list),
+        }
+    }
+
+    /// Constructs an iterator which yields no devices.
+    fn empty() -> Self {
+        Self {
+            device_list: None,
+            indices: 0..0,
+        }
+    }
+}
+
+impl Iterator for DeviceListIter {
+    type Item = Device;
+
+    fn next(&mut self) -> Option<Device> {
+        let device_list = self.device_list.as_ref()?;
+        self.indices
+            .by_ref()
+            .find_map(|index| device_list.device(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.indices.len()))
+    }
 }
 
 /// An error type describing failure to construct a context.
@@ -40,9 +268,31 @@ pub struct CouldNotAddDeviceError(pub Rs2Exception, pub String);
 #[error("Could not remove device from file. Type: {0}; Reason: {1}")]
 pub struct CouldNotRemoveDeviceError(pub Rs2Exception, pub String);
 
+/// An error type describing failure to set the devices-changed callback.
+#[derive(Error, Debug)]
+#[error("Could not set the devices changed callback. Type: {0}; Reason: {1}")]
+pub struct CouldNotSetDevicesChangedCallbackError(pub Rs2Exception, pub String);
+
 impl Drop for Context {
     fn drop(&mut self) {
-        unsafe { sys::rs2_delete_context(self.context_ptr.as_ptr()) }
+        unsafe {
+            // Devices and pipelines created from the context keep the underlying librealsense2
+            // context alive, and with it the devices-changed callback. Detach the callback before
+            // it is dropped, so that librealsense2 never calls into freed memory.
+            if self.devices_changed_callback.is_some() {
+                let mut err = std::ptr::null_mut::<sys::rs2_error>();
+                sys::rs2_set_devices_changed_callback(
+                    self.context_ptr.as_ptr(),
+                    Some(devices_changed_trampoline),
+                    std::ptr::null_mut(),
+                    &mut err,
+                );
+                if err.as_ref().is_some() {
+                    sys::rs2_free_error(err);
+                }
+            }
+            sys::rs2_delete_context(self.context_ptr.as_ptr())
+        }
     }
 }
 
@@ -63,6 +313,8 @@ impl Context {
 
             Ok(Self {
                 context_ptr: NonNull::new(ptr).unwrap(),
+                devices_changed_callback: None,
+                replaced_devices_changed_callbacks: Vec::new(),
             })
         }
     }
@@ -84,17 +336,32 @@ impl Context {
     }
 
     /// Get a list of devices that are already connected to the host.
+    ///
+    /// Only devices belonging to one of the product lines in `product_mask` are returned. An empty
+    /// mask applies no filter, and is equivalent to a mask containing only
+    /// [`Rs2ProductLine::Any`].
+    ///
+    /// This never fails: if no devices are connected, or the devices cannot be queried, an empty
+    /// list is returned.
     pub fn query_devices(&self, product_mask: HashSet<Rs2ProductLine>) -> Vec<Device> {
-        // TODO/TEST: Make sure that an empty mask (therefore giving no filter) gives
-        // us _all_ devices, not _no_ devices.
+        self.device_list(product_line_mask(product_mask)).collect()
+    }
 
-        let mask = if product_mask.is_empty() {
-            Rs2ProductLine::Any.to_i32().unwrap()
-        } else {
-            product_mask.iter().fold(0, |k, v| k | v.to_u32().unwrap()) as i32
-        };
+    /// Iterates over the devices that are already connected to the host.
+    ///
+    /// Unlike [`query_devices`](Self::query_devices), each device is only constructed once the
+    /// iterator reaches it. Constructing a device opens a handle to it, so when e.g. only the first
+    /// of many connected cameras is needed, this avoids opening the others. The set of devices is
+    /// fixed when this is called, so devices connected while iterating are not included.
+    ///
+    /// This never fails: devices that cannot be constructed, e.g. because they were disconnected
+    /// in the meantime, are skipped, and the iterator is empty if the devices cannot be queried.
+    pub fn devices(&self) -> DeviceListIter {
+        self.device_list(Rs2ProductLine::Any as i32)
+    }
 
-        let mut devices = Vec::new();
+    /// Queries the connected devices that belong to the product lines in the bitmask `mask`.
+    fn device_list(&self, mask: i32) -> DeviceListIter {
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             let device_list_ptr =
@@ -102,33 +369,53 @@ impl Context {
 
             if err.as_ref().is_some() {
                 sys::rs2_free_error(err);
-                return devices;
+                return DeviceListIter::empty();
             }
 
-            let device_list = NonNull::new(device_list_ptr).unwrap();
-
-            let len = sys::rs2_get_device_count(device_list.as_ptr(), &mut err);
-
-            if err.as_ref().is_some() {
-                sys::rs2_free_error(err);
-                sys::rs2_delete_device_list(device_list.as_ptr());
-                return devices;
-            }
+            DeviceListIter::new(DeviceList::new(NonNull::new(device_list_ptr).unwrap()))
+        }
+    }
 
-            for i in 0..len {
-                match Device::try_create(&device_list, i) {
-                    Ok(d) => {
-                        devices.push(d);
-                    }
-                    Err(_) => {
-                        continue;
-                    }
-                }
-            }
+    /// Get a list of connected devices that belong to the `product_line`.
+    ///
+    /// This is a shorthand for [`query_devices`](Self::query_devices) with a single product line,
+    /// e.g. to only pick up the depth cameras of a rig that also contains tracking cameras:
+    ///
+    /// ```no_run
+    /// use realsense_rust::{context::Context, kind::Rs2ProductLine};
+    ///
+    /// let context = Context::new().unwrap();
+    /// let depth_cameras = context.query_devices_by_product_line(Rs2ProductLine::Depth);
+    /// ```
+    ///
+    /// Like [`query_devices`](Self::query_devices), this never fails.
+    pub fn query_devices_by_product_line(&self, product_line: Rs2ProductLine) -> Vec<Device> {
+        self.query_devices(std::iter::once(product_line).collect())
+    }
 
-            sys::rs2_delete_device_list(device_list.as_ptr());
-        }
-        devices
+    /// Waits up to `timeout` for a device belonging to one of the product lines in `product_mask`
+    /// to be connected.
+    ///
+    /// If such a device is already connected, it is returned immediately. Otherwise the connected
+    /// devices are queried every 100 milliseconds until one appears, which is useful when starting
+    /// up before the camera has finished enumerating over USB. Only the list of connected devices
+    /// is queried, already filtered by product line, so no device is constructed until a matching
+    /// one is found. To wait without a timeout, use [`DeviceHub::wait_for_device`].
+    ///
+    /// The devices are queried once more when the timeout has elapsed, so a zero `timeout` checks
+    /// the connected devices exactly once. The call may return up to one query later than
+    /// `timeout`, but never sleeps past it.
+    ///
+    /// Returns `None` if no device is connected before the timeout.
+    pub fn wait_for_device(
+        &self,
+        product_mask: HashSet<Rs2ProductLine>,
+        timeout: Duration,
+    ) -> Option<Device> {
+        let mask = product_line_mask(product_mask);
+        poll_until(timeout, DEVICE_POLL_INTERVAL, || {
+            self.device_list(mask).next()
+        })
     }
 
     /// Create a new device and add it to the context.
@@ -183,6 +470,47 @@ impl Context {
         }
     }
 
+    /// Set a callback to be invoked whenever devices are connected to or disconnected from the host.
+    ///
+    /// The callback is invoked from a thread internal to librealsense2, which is why it must be
+    /// `Send`. It is owned by the context and is dropped along with it. Only one callback can be
+    /// registered at a time; a callback that is replaced is no longer invoked, but is kept alive
+    /// until the context is dropped.
+    ///
+    /// Any panic inside the callback is caught and discarded, since unwinding into librealsense2
+    /// is undefined behaviour.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CouldNotSetDevicesChangedCallbackError`] if the callback cannot be registered. In
+    /// this case any previously registered callback remains active.
+    ///
+    pub fn set_devices_changed_callback<F>(
+        &mut self,
+        callback: F,
+    ) -> Result<(), CouldNotSetDevicesChangedCallbackError>
+    where
+        F: FnMut(DevicesChangedEvent) + Send + 'static,
+    {
+        let mut callback: Box<DevicesChangedCallback> = Box::new(Box::new(callback));
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_set_devices_changed_callback(
+                self.context_ptr.as_ptr(),
+                Some(devices_changed_trampoline),
+                &mut *callback as *mut DevicesChangedCallback as *mut c_void,
+                &mut err,
+            );
+            check_rs2_error!(err, CouldNotSetDevicesChangedCallbackError)?;
+        }
+
+        if let Some(replaced) = self.devices_changed_callback.replace(callback) {
+            self.replaced_devices_changed_callbacks.push(replaced);
+        }
+        Ok(())
+    }
+
     /// Get the underlying low-level pointer to the context object.
     ///
     /// # Safety
@@ -195,3 +523,91 @@ impl Context {
         self.context_ptr
     }
 }
+
+/// Calls `poll` every `interval` until it returns a value, or until `timeout` has elapsed.
+///
+/// `poll` is always called at least once, and once more after the timeout has elapsed.
+fn poll_until<T, F>(timeout: Duration, interval: Duration, mut poll: F) -> Option<T>
+where
+    F: FnMut() -> Option<T>,
+{
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(value) = poll() {
+            return Some(value);
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return None;
+        }
+        thread::sleep(interval.min(deadline - now));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[test]
+    fn trampoline_invokes_devices_changed_callback() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let callback_count = Arc::clone(&count);
+
+        let mut callback: DevicesChangedCallback = Box::new(move |event| {
+            assert!(event.added_devices().is_empty());
+            assert!(event.removed_devices().is_empty());
+            callback_count.fetch_add(1, Ordering::SeqCst);
+        });
+
+        unsafe {
+            let user = &mut callback as *mut DevicesChangedCallback as *mut c_void;
+            devices_changed_trampoline(std::ptr::null_mut(), std::ptr::null_mut(), user);
+            devices_changed_trampoline(std::ptr::null_mut(), std::ptr::null_mut(), user);
+        }
+
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn empty_device_list_iter_yields_no_devices() {
+        let mut devices = DeviceListIter::empty();
+
+        assert_eq!(devices.size_hint(), (0, Some(0)));
+        assert!(devices.next().is_none());
+    }
+
+    #[test]
+    fn poll_until_returns_as_soon_as_a_value_is_available() {
+        let mut polls = 0;
+        let value = poll_until(Duration::from_secs(10), Duration::from_millis(1), || {
+            polls += 1;
+            if polls == 3 {
+                Some(polls)
+            } else {
+                None
+            }
+        });
+
+        assert_eq!(value, Some(3));
+    }
+
+    #[test]
+    fn poll_until_gives_up_after_the_timeout() {
+        let start = Instant::now();
+        let mut polls = 0;
+        let value: Option<()> =
+            poll_until(Duration::from_millis(50), Duration::from_millis(10), || {
+                polls += 1;
+                None
+            });
+
+        assert_eq!(value, None);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert!(polls > 1);
+    }
+}
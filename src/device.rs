@@ -5,10 +5,25 @@
 //! comprise that device (IR cameras, depth camera, color camera, IMU) are referred to as sensors.
 //! See [`sensors`](crate::sensor) for more info.
 
+mod advanced_mode_device;
+mod auto_calibrated_device;
+mod l515_settings;
+mod playback_device;
+mod record_device;
+
+pub use advanced_mode_device::{AdvancedModeDevice, AdvancedModeError, DepthControlGroup};
+pub use auto_calibrated_device::{
+    AutoCalibratedDevice, AutoCalibrationError, CalibrationHealth, CalibrationResult,
+    DEFAULT_CALIBRATION_TIMEOUT,
+};
+pub use l515_settings::{L515Settings, L515SettingsError};
+pub use playback_device::{PlaybackDevice, PlaybackDeviceError};
+pub use record_device::{RecordDevice, RecordDeviceError};
+
 use crate::{
     check_rs2_error,
-    kind::{Rs2CameraInfo, Rs2Exception},
-    sensor::Sensor,
+    kind::{Rs2CameraInfo, Rs2Exception, Rs2Extension},
+    sensor::{DepthSensor, PoseSensor, Sensor},
 };
 use anyhow::Result;
 use realsense_sys as sys;
@@ -28,8 +43,16 @@ pub enum DeviceConstructionError {
     /// Could not get device from device list
     #[error("Could not get device from device list. Type: {0}; Reason: {1}")]
     CouldNotGetDeviceFromDeviceList(Rs2Exception, String),
+    /// The device could not be cloned, because no sensors could be acquired from it.
+    #[error("Could not clone device, since it has no sensors.")]
+    DeviceHasNoSensors,
 }
 
+/// An error type describing failure to reset a device.
+#[derive(Error, Debug)]
+#[error("Could not reset the device. Type: {0}; Reason: {1}")]
+pub struct HardwareResetError(pub Rs2Exception, pub String);
+
 /// A type representing a RealSense device.
 ///
 /// A device in librealsense2 corresponds to a physical unit that connects to your computer
@@ -133,26 +156,77 @@ impl Device {
         }
     }
 
+    /// Gets the first sensor of the device which is a [`DepthSensor`].
+    ///
+    /// This is typically the stereo module of a D400 device, and is used to e.g. configure depth
+    /// options before streaming. Returns `None` if the device has no depth sensor, or if the list
+    /// of sensors cannot be read.
+    pub fn first_depth_sensor(&self) -> Option<DepthSensor> {
+        self.sensors()
+            .into_iter()
+            .find_map(|sensor| sensor.try_into_depth_sensor().ok())
+    }
+
+    /// Gets the first sensor of the device which is a [`PoseSensor`].
+    ///
+    /// This is the tracking module of a T200 series device, and is used to e.g. manage its
+    /// localization map. Returns `None` if the device cannot track its pose, or if the list of
+    /// sensors cannot be read.
+    pub fn first_pose_sensor(&self) -> Option<PoseSensor> {
+        self.sensors()
+            .into_iter()
+            .find_map(|sensor| sensor.try_into_pose_sensor().ok())
+    }
+
     /// Takes ownership of the device and forces a hardware reset on the device.
     ///
     /// Ownership of the device is taken as the underlying state can no longer be safely retained
-    /// after resetting the device.
+    /// after resetting the device. This is the only way to recover a device that has gotten into a
+    /// bad state (e.g. after a USB glitch) short of physically reconnecting it.
+    ///
+    /// The device will disconnect and then reconnect once the reset completes. To get a handle to
+    /// the device again, re-enumerate the connected devices via
+    /// [`Context::query_devices`](crate::context::Context::query_devices) once it has
+    /// reconnected (or wait for it with a [`DeviceHub`](crate::device_hub::DeviceHub)).
     ///
-    pub fn hardware_reset(self) {
+    /// # Errors
+    ///
+    /// Returns [`HardwareResetError`] if the reset could not be triggered, e.g. if the device does
+    /// not support being reset (as is the case for playback devices).
+    ///
+    pub fn hardware_reset(self) -> Result<(), HardwareResetError> {
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
-
-            // The only failure this can have is if device_ptr is null. This should not be the case
-            // since we're storing a `NonNull` type.
-            //
-            // It's a bit weird, but we don't need to actually check the error. Because if the
-            // device is null and this fails: you have an invalid device (so panic?) but if it
-            // succeeds, the device is no longer valid and we need to drop it. This is why this
-            // interface takes ownership of `self`.
             sys::rs2_hardware_reset(self.device_ptr.as_ptr(), &mut err);
+            check_rs2_error!(err, HardwareResetError)
         }
     }
 
+    /// Creates a new handle to the same underlying device.
+    ///
+    /// The clone refers to the exact same physical device as `self`, and both handles can be used
+    /// and dropped independently of one another, e.g. from different threads. librealsense2 does
+    /// not offer a way to copy a device handle directly, so the clone is created from the first
+    /// sensor of the device, which shares ownership of the device with every handle to it.
+    ///
+    /// Note that this does not duplicate the device itself: configuring the device through one
+    /// handle (e.g. setting sensor options) is visible through the other, and only one of them can
+    /// stream from a given sensor at a time.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DeviceConstructionError::DeviceHasNoSensors`] if no sensors can be acquired from
+    /// the device, e.g. because it has been disconnected.
+    ///
+    /// Returns [`DeviceConstructionError::CouldNotCreateDeviceFromSensor`] if the device cannot be
+    /// obtained from its sensor.
+    pub fn try_clone(&self) -> Result<Device, DeviceConstructionError> {
+        self.sensors()
+            .first()
+            .ok_or(DeviceConstructionError::DeviceHasNoSensors)?
+            .device()
+    }
+
     /// Gets the value associated with the provided camera info key from the device.
     ///
     /// Returns some information value associated with the camera info key if the `camera_info` is
@@ -182,6 +256,55 @@ impl Device {
         }
     }
 
+    /// Gets the name of the device, e.g. "Intel RealSense D435I".
+    ///
+    /// Returns `None` if the name is not available.
+    ///
+    pub fn name(&self) -> Option<String> {
+        self.info_string(Rs2CameraInfo::Name)
+    }
+
+    /// Gets the serial number of the device.
+    ///
+    /// Returns `None` if the serial number is not available.
+    ///
+    pub fn serial_number(&self) -> Option<String> {
+        self.info_string(Rs2CameraInfo::SerialNumber)
+    }
+
+    /// Gets the version of the firmware currently running on the device.
+    ///
+    /// Returns `None` if the firmware version is not available.
+    ///
+    pub fn firmware_version(&self) -> Option<String> {
+        self.info_string(Rs2CameraInfo::FirmwareVersion)
+    }
+
+    /// Gets the product line of the device, e.g. "D400".
+    ///
+    /// Returns `None` if the product line is not available.
+    ///
+    pub fn product_line(&self) -> Option<String> {
+        self.info_string(Rs2CameraInfo::ProductLine)
+    }
+
+    /// Gets the USB type descriptor of the device's connection, e.g. "3.2".
+    ///
+    /// Returns `None` if the USB type descriptor is not available (e.g. for playback devices).
+    ///
+    pub fn usb_type_descriptor(&self) -> Option<String> {
+        self.info_string(Rs2CameraInfo::UsbTypeDescriptor)
+    }
+
+    /// Gets the value associated with the provided camera info key as an owned string.
+    ///
+    /// Returns `None` if the `camera_info` is not supported or is not valid UTF-8.
+    fn info_string(&self, camera_info: Rs2CameraInfo) -> Option<String> {
+        self.info(camera_info)
+            .and_then(|s| s.to_str().ok())
+            .map(String::from)
+    }
+
     /// Predicate for checking if `camera_info` is supported for this device.
     ///
     /// Returns true iff the device has a value associated with the `camera_info` key.
@@ -205,6 +328,104 @@ impl Device {
         }
     }
 
+    /// Gets the device as a [`RecordDevice`], if it is recording to a file.
+    ///
+    /// This is the case for the device of a pipeline which has been configured to
+    /// [record to a file](crate::config::Config::enable_record_to_file), so that the recording
+    /// can be paused and resumed.
+    ///
+    /// Returns `None` if the device is not recording.
+    pub fn as_recorder(&self) -> Option<&RecordDevice> {
+        if self.is_extendable_to(Rs2Extension::Record) {
+            // SAFETY: `RecordDevice` is a transparent wrapper around `Device`.
+            Some(unsafe { &*(self as *const Device).cast::<RecordDevice>() })
+        } else {
+            None
+        }
+    }
+
+    /// Gets the device as a [`PlaybackDevice`], if it is playing back a file.
+    ///
+    /// This is the case for the device of a pipeline which has been configured to
+    /// [stream from a file](crate::config::Config::enable_device_from_file), so that the
+    /// playback can be controlled.
+    ///
+    /// Returns `None` if the device is not playing back a file.
+    pub fn as_playback(&self) -> Option<&PlaybackDevice> {
+        if self.is_extendable_to(Rs2Extension::Playback) {
+            // SAFETY: `PlaybackDevice` is a transparent wrapper around `Device`.
+            Some(unsafe { &*(self as *const Device).cast::<PlaybackDevice>() })
+        } else {
+            None
+        }
+    }
+
+    /// Gets the device as an [`AdvancedModeDevice`], if it supports advanced mode.
+    ///
+    /// This is the case for devices of the D400 series. Advanced mode does not need to be enabled
+    /// on the device; see [`AdvancedModeDevice::is_enabled`].
+    ///
+    /// Returns `None` if the device does not support advanced mode.
+    pub fn as_advanced_mode(&self) -> Option<&AdvancedModeDevice> {
+        if self.is_extendable_to(Rs2Extension::AdvancedMode) {
+            // SAFETY: `AdvancedModeDevice` is a transparent wrapper around `Device`.
+            Some(unsafe { &*(self as *const Device).cast::<AdvancedModeDevice>() })
+        } else {
+            None
+        }
+    }
+
+    /// Attempts to convert the device into an [`AdvancedModeDevice`].
+    ///
+    /// Succeeds iff the device supports advanced mode, like
+    /// [`as_advanced_mode`](Self::as_advanced_mode). Ownership is needed to
+    /// [toggle](AdvancedModeDevice::set_enabled) advanced mode, which resets the device.
+    ///
+    /// # Errors
+    ///
+    /// Returns the original device back if it does not support advanced mode.
+    pub fn try_into_advanced_mode(self) -> Result<AdvancedModeDevice, Device> {
+        if self.is_extendable_to(Rs2Extension::AdvancedMode) {
+            Ok(AdvancedModeDevice(self))
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Gets the device as an [`AutoCalibratedDevice`], if it supports on-chip calibration.
+    ///
+    /// This is the case for devices of the D400 series.
+    ///
+    /// Returns `None` if the device does not support on-chip calibration.
+    pub fn as_auto_calibrated(&self) -> Option<&AutoCalibratedDevice> {
+        if self.is_extendable_to(Rs2Extension::AutoCalibratedDevice) {
+            // SAFETY: `AutoCalibratedDevice` is a transparent wrapper around `Device`.
+            Some(unsafe { &*(self as *const Device).cast::<AutoCalibratedDevice>() })
+        } else {
+            None
+        }
+    }
+
+    /// Predicate for whether the device can be extended to the given extension type.
+    fn is_extendable_to(&self, extension: Rs2Extension) -> bool {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let is_extendable = sys::rs2_is_device_extendable_to(
+                self.device_ptr.as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (extension as i32).try_into().unwrap(),
+                &mut err,
+            );
+
+            if err.as_ref().is_none() {
+                is_extendable != 0
+            } else {
+                sys::rs2_free_error(err);
+                false
+            }
+        }
+    }
+
     /// Get the underlying low-level pointer to the context object
     ///
     /// # Safety
@@ -0,0 +1,311 @@
+//! Type for devices which support the D400 advanced mode.
+
+use super::Device;
+use crate::{check_rs2_error, kind::Rs2Exception};
+use realsense_sys as sys;
+use std::{
+    mem::MaybeUninit,
+    ops::Deref,
+    os::raw::{c_int, c_uint, c_void},
+    slice,
+};
+use thiserror::Error;
+
+/// Mode of `rs2_get_depth_control` that retrieves the current values.
+const DEPTH_CONTROL_CURRENT: c_int = 0;
+/// Mode of `rs2_get_depth_control` that retrieves the minimum values.
+const DEPTH_CONTROL_MIN: c_int = 1;
+/// Mode of `rs2_get_depth_control` that retrieves the maximum values.
+const DEPTH_CONTROL_MAX: c_int = 2;
+
+/// Enumeration of possible errors that can occur while using the advanced mode of a device.
+#[derive(Error, Debug)]
+pub enum AdvancedModeError {
+    /// Could not determine whether advanced mode is enabled.
+    #[error("Could not get advanced mode state. Type: {0}; Reason: {1}")]
+    CouldNotGetAdvancedModeState(Rs2Exception, String),
+    /// Could not enable or disable advanced mode.
+    #[error("Could not toggle advanced mode. Type: {0}; Reason: {1}")]
+    CouldNotToggleAdvancedMode(Rs2Exception, String),
+    /// Could not serialize the device configuration to JSON.
+    #[error("Could not serialize device configuration to JSON. Type: {0}; Reason: {1}")]
+    CouldNotSerializeJson(Rs2Exception, String),
+    /// Could not load a device configuration from JSON.
+    #[error("Could not load device configuration from JSON. Type: {0}; Reason: {1}")]
+    CouldNotLoadJson(Rs2Exception, String),
+    /// Could not get the depth control group of the device.
+    #[error("Could not get depth control group. Type: {0}; Reason: {1}")]
+    CouldNotGetDepthControl(Rs2Exception, String),
+    /// Could not set the depth control group of the device.
+    #[error("Could not set depth control group. Type: {0}; Reason: {1}")]
+    CouldNotSetDepthControl(Rs2Exception, String),
+}
+
+/// The depth control group of advanced mode, which tunes how the stereo matcher of the device
+/// accepts or rejects depth.
+///
+/// This is the `depth-control` section of the [JSON](AdvancedModeDevice::serialize_json)
+/// configuration, as individual values that can be tuned one at a time. Retrieved with
+/// [`AdvancedModeDevice::depth_control`] and applied with
+/// [`AdvancedModeDevice::set_depth_control`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DepthControlGroup {
+    /// Increment applied to the score of a pixel that matches.
+    pub plus_increment: u32,
+    /// Decrement applied to the score of a pixel that does not match.
+    pub minus_decrement: u32,
+    /// Threshold on the median of the matching costs, below which a match is rejected.
+    pub deep_sea_median_threshold: u32,
+    /// Minimum score for a match to be accepted.
+    pub score_threshold_a: u32,
+    /// Maximum score for a match to be accepted.
+    pub score_threshold_b: u32,
+    /// Minimum difference in intensity for a pixel to count towards the texture of its
+    /// neighborhood.
+    pub texture_difference_threshold: u32,
+    /// Minimum number of textured pixels in the neighborhood for a match to be accepted.
+    pub texture_count_threshold: u32,
+    /// Minimum difference between the best and second best match for a match to be accepted.
+    pub deep_sea_second_peak_threshold: u32,
+    /// Minimum number of neighboring pixels that agree with a match for it to be accepted.
+    pub deep_sea_neighbor_threshold: u32,
+    /// Maximum disparity between matching left-to-right and right-to-left for a match to be
+    /// accepted.
+    pub lr_agree_threshold: u32,
+}
+
+impl From<sys::STDepthControlGroup> for DepthControlGroup {
+    fn from(group: sys::STDepthControlGroup) -> Self {
+        Self {
+            plus_increment: group.plusIncrement,
+            minus_decrement: group.minusDecrement,
+            deep_sea_median_threshold: group.deepSeaMedianThreshold,
+            score_threshold_a: group.scoreThreshA,
+            score_threshold_b: group.scoreThreshB,
+            texture_difference_threshold: group.textureDifferenceThreshold,
+            texture_count_threshold: group.textureCountThreshold,
+            deep_sea_second_peak_threshold: group.deepSeaSecondPeakThreshold,
+            deep_sea_neighbor_threshold: group.deepSeaNeighborThreshold,
+            lr_agree_threshold: group.lrAgreeThreshold,
+        }
+    }
+}
+
+impl From<DepthControlGroup> for sys::STDepthControlGroup {
+    fn from(group: DepthControlGroup) -> Self {
+        Self {
+            plusIncrement: group.plus_increment,
+            minusDecrement: group.minus_decrement,
+            deepSeaMedianThreshold: group.deep_sea_median_threshold,
+            scoreThreshA: group.score_threshold_a,
+            scoreThreshB: group.score_threshold_b,
+            textureDifferenceThreshold: group.texture_difference_threshold,
+            textureCountThreshold: group.texture_count_threshold,
+            deepSeaSecondPeakThreshold: group.deep_sea_second_peak_threshold,
+            deepSeaNeighborThreshold: group.deep_sea_neighbor_threshold,
+            lrAgreeThreshold: group.lr_agree_threshold,
+        }
+    }
+}
+
+/// A device which supports the advanced mode of the D400 series.
+///
+/// Advanced mode exposes the full set of depth-quality parameters of the device. The whole set can
+/// be saved to and loaded from JSON, which is the format of the presets that are shared by Intel
+/// and produced by the RealSense Viewer.
+///
+/// Advanced mode devices are acquired with [`Device::as_advanced_mode`], or with
+/// [`Device::try_into_advanced_mode`] to [toggle](Self::set_enabled) advanced mode. This only
+/// requires that the device supports advanced mode, not that advanced mode is
+/// [enabled](Self::is_enabled).
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct AdvancedModeDevice(pub(super) Device);
+
+impl Deref for AdvancedModeDevice {
+    type Target = Device;
+
+    fn deref(&self) -> &Device {
+        &self.0
+    }
+}
+
+impl AdvancedModeDevice {
+    /// Predicate for whether advanced mode is currently enabled on the device.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdvancedModeError::CouldNotGetAdvancedModeState`] if the state cannot be
+    /// retrieved.
+    pub fn is_enabled(&self) -> Result<bool, AdvancedModeError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let mut enabled: c_int = 0;
+            sys::rs2_is_enabled(self.0.get_raw().as_ptr(), &mut enabled, &mut err);
+            check_rs2_error!(err, AdvancedModeError::CouldNotGetAdvancedModeState)?;
+
+            Ok(enabled != 0)
+        }
+    }
+
+    /// Takes ownership of the device and enables or disables advanced mode on it.
+    ///
+    /// Ownership of the device is taken as toggling advanced mode resets the device, after which
+    /// the underlying state can no longer be safely retained. Any sensors or pipelines acquired
+    /// from the device should be dropped as well. The device will disconnect and then reconnect
+    /// with advanced mode toggled. To get a handle to the device again, re-acquire it from the
+    /// context via [`Context::query_devices`](crate::context::Context::query_devices) once it has
+    /// reconnected.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdvancedModeError::CouldNotToggleAdvancedMode`] if advanced mode cannot be
+    /// toggled.
+    pub fn set_enabled(self, enabled: bool) -> Result<(), AdvancedModeError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_toggle_advanced_mode(self.0.get_raw().as_ptr(), enabled as c_int, &mut err);
+            check_rs2_error!(err, AdvancedModeError::CouldNotToggleAdvancedMode)
+        }
+    }
+
+    /// Serializes the current configuration of the device to JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdvancedModeError::CouldNotSerializeJson`] if the configuration cannot be
+    /// serialized, e.g. if advanced mode is not enabled.
+    pub fn serialize_json(&self) -> Result<String, AdvancedModeError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let buffer = sys::rs2_serialize_json(self.0.get_raw().as_ptr(), &mut err);
+            check_rs2_error!(err, AdvancedModeError::CouldNotSerializeJson)?;
+
+            let size = sys::rs2_get_raw_data_size(buffer, &mut err);
+            if let Err(e) = check_rs2_error!(err, AdvancedModeError::CouldNotSerializeJson) {
+                sys::rs2_delete_raw_data(buffer);
+                return Err(e);
+            }
+
+            let data = sys::rs2_get_raw_data(buffer, &mut err);
+            if let Err(e) = check_rs2_error!(err, AdvancedModeError::CouldNotSerializeJson) {
+                sys::rs2_delete_raw_data(buffer);
+                return Err(e);
+            }
+
+            let json =
+                String::from_utf8_lossy(slice::from_raw_parts(data, size as usize)).into_owned();
+            sys::rs2_delete_raw_data(buffer);
+            Ok(json)
+        }
+    }
+
+    /// Loads a configuration, such as one produced by [`serialize_json`](Self::serialize_json),
+    /// onto the device.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdvancedModeError::CouldNotLoadJson`] if the configuration cannot be loaded,
+    /// e.g. if advanced mode is not enabled or `json` is not a valid configuration.
+    pub fn load_json(&self, json: &str) -> Result<(), AdvancedModeError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_load_json(
+                self.0.get_raw().as_ptr(),
+                json.as_ptr().cast::<c_void>(),
+                json.len() as c_uint,
+                &mut err,
+            );
+            check_rs2_error!(err, AdvancedModeError::CouldNotLoadJson)
+        }
+    }
+
+    /// Gets the current depth control group of the device.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdvancedModeError::CouldNotGetDepthControl`] if the group cannot be retrieved,
+    /// e.g. if advanced mode is not enabled.
+    pub fn depth_control(&self) -> Result<DepthControlGroup, AdvancedModeError> {
+        self.get_depth_control(DEPTH_CONTROL_CURRENT)
+    }
+
+    /// Gets the minimum and maximum values that the device accepts for every field of the depth
+    /// control group, as `(min, max)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdvancedModeError::CouldNotGetDepthControl`] if the range cannot be retrieved,
+    /// e.g. if advanced mode is not enabled.
+    pub fn depth_control_range(
+        &self,
+    ) -> Result<(DepthControlGroup, DepthControlGroup), AdvancedModeError> {
+        Ok((
+            self.get_depth_control(DEPTH_CONTROL_MIN)?,
+            self.get_depth_control(DEPTH_CONTROL_MAX)?,
+        ))
+    }
+
+    /// Applies a depth control group to the device.
+    ///
+    /// To change a single value, modify the group returned by
+    /// [`depth_control`](Self::depth_control) and apply it again.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdvancedModeError::CouldNotSetDepthControl`] if the group cannot be applied,
+    /// e.g. if advanced mode is not enabled or a value is outside of its
+    /// [range](Self::depth_control_range).
+    pub fn set_depth_control(&self, group: &DepthControlGroup) -> Result<(), AdvancedModeError> {
+        let group = sys::STDepthControlGroup::from(*group);
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_set_depth_control(self.0.get_raw().as_ptr(), &group, &mut err);
+            check_rs2_error!(err, AdvancedModeError::CouldNotSetDepthControl)
+        }
+    }
+
+    /// Gets the current, minimum or maximum depth control group, depending on `mode`.
+    fn get_depth_control(&self, mode: c_int) -> Result<DepthControlGroup, AdvancedModeError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let mut group = MaybeUninit::<sys::STDepthControlGroup>::uninit();
+            sys::rs2_get_depth_control(
+                self.0.get_raw().as_ptr(),
+                group.as_mut_ptr(),
+                mode,
+                &mut err,
+            );
+            check_rs2_error!(err, AdvancedModeError::CouldNotGetDepthControl)?;
+
+            Ok(DepthControlGroup::from(group.assume_init()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_control_group_round_trips_through_sys() {
+        let group = DepthControlGroup {
+            plus_increment: 1,
+            minus_decrement: 2,
+            deep_sea_median_threshold: 3,
+            score_threshold_a: 4,
+            score_threshold_b: 5,
+            texture_difference_threshold: 6,
+            texture_count_threshold: 7,
+            deep_sea_second_peak_threshold: 8,
+            deep_sea_neighbor_threshold: 9,
+            lr_agree_threshold: 10,
+        };
+
+        let raw = sys::STDepthControlGroup::from(group);
+        assert_eq!(raw.scoreThreshB, 5);
+        assert_eq!(raw.lrAgreeThreshold, 10);
+        assert_eq!(DepthControlGroup::from(raw), group);
+    }
+}
@@ -0,0 +1,295 @@
+//! Type for devices which support on-chip self-calibration.
+
+use super::Device;
+use crate::{check_rs2_error, kind::Rs2Exception};
+use realsense_sys as sys;
+use std::{
+    convert::TryInto,
+    ops::Deref,
+    os::raw::{c_int, c_void},
+    ptr, slice,
+    time::Duration,
+};
+use thiserror::Error;
+
+/// The timeout applied to on-chip calibration if none is given, as recommended by librealsense2.
+pub const DEFAULT_CALIBRATION_TIMEOUT: Duration = Duration::from_millis(5000);
+
+/// Enumeration of possible errors that can occur while calibrating a device.
+#[derive(Error, Debug)]
+pub enum AutoCalibrationError {
+    /// On-chip calibration could not be run.
+    #[error("Could not run on-chip calibration. Type: {0}; Reason: {1}")]
+    CouldNotRunOnChipCalibration(Rs2Exception, String),
+    /// The calibration table could not be read from the device.
+    #[error("Could not get calibration table. Type: {0}; Reason: {1}")]
+    CouldNotGetCalibrationTable(Rs2Exception, String),
+    /// The calibration table could not be applied to the device.
+    #[error("Could not set calibration table. Type: {0}; Reason: {1}")]
+    CouldNotSetCalibrationTable(Rs2Exception, String),
+    /// The calibration could not be written to the flash memory of the device.
+    #[error("Could not write calibration. Type: {0}; Reason: {1}")]
+    CouldNotWriteCalibration(Rs2Exception, String),
+    /// The device could not be reset to its factory calibration.
+    #[error("Could not reset to factory calibration. Type: {0}; Reason: {1}")]
+    CouldNotResetToFactoryCalibration(Rs2Exception, String),
+}
+
+/// How far the calibration of a device is from the optimal one, as assessed by on-chip
+/// calibration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CalibrationHealth {
+    /// The calibration is good, i.e. the health is below 0.25.
+    Good,
+    /// The calibration can be improved, i.e. the health is below 0.75.
+    CanBeImproved,
+    /// The device requires calibration.
+    RequiresCalibration,
+}
+
+impl CalibrationHealth {
+    /// Classifies the health reported by on-chip calibration.
+    ///
+    /// Only the magnitude of the health matters; its sign reflects the direction of the error.
+    pub fn from_health(health: f32) -> Self {
+        let health = health.abs();
+        if health < 0.25 {
+            Self::Good
+        } else if health < 0.75 {
+            Self::CanBeImproved
+        } else {
+            Self::RequiresCalibration
+        }
+    }
+}
+
+/// The outcome of running on-chip calibration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationResult {
+    /// The new calibration table, which can be applied with
+    /// [`AutoCalibratedDevice::set_calibration_table`].
+    pub table: Vec<u8>,
+    /// How far the calibration of the device was from the optimal one when the calibration was
+    /// run, where lower is better.
+    ///
+    /// See [`health_status`](Self::health_status) for how to interpret it.
+    pub health: f32,
+}
+
+impl CalibrationResult {
+    /// Classifies the [health](Self::health) of the calibration.
+    pub fn health_status(&self) -> CalibrationHealth {
+        CalibrationHealth::from_health(self.health)
+    }
+}
+
+/// A device which supports on-chip self-calibration, such as a D400 series device.
+///
+/// On-chip calibration measures how far the calibration of the depth cameras has drifted, e.g.
+/// after the device was dropped, and computes a new calibration table. Calibration is a three-step
+/// process:
+///
+/// 1. [Run](Self::run_on_chip_calibration) the calibration while the device is streaming depth,
+///    pointed at a textured scene. This does not change the device.
+/// 2. [Apply](Self::set_calibration_table) the new table, which lasts until the device is reset,
+///    to check that it improves depth.
+/// 3. [Write](Self::write_calibration) the applied table to the flash memory of the device, so
+///    that it persists.
+///
+/// Auto-calibrated devices are acquired with [`Device::as_auto_calibrated`].
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct AutoCalibratedDevice(Device);
+
+impl Deref for AutoCalibratedDevice {
+    type Target = Device;
+
+    fn deref(&self) -> &Device {
+        &self.0
+    }
+}
+
+impl AutoCalibratedDevice {
+    /// Runs on-chip calibration, blocking until it completes, and gets the new calibration table
+    /// along with the health of the current calibration.
+    ///
+    /// `json_config` configures the calibration, e.g. `{"speed": 3}` for a slow but thorough
+    /// calibration. See the documentation of `rs2_run_on_chip_calibration` in librealsense2 for
+    /// the available parameters. If `json_config` is `None`, the default parameters are used.
+    ///
+    /// If `timeout` is `None`, the [default timeout](DEFAULT_CALIBRATION_TIMEOUT) is applied.
+    ///
+    /// The device has to be streaming depth while the calibration runs.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AutoCalibrationError::CouldNotRunOnChipCalibration`] if the calibration fails,
+    /// e.g. because the device is not streaming or the scene has too little texture.
+    pub fn run_on_chip_calibration(
+        &self,
+        json_config: Option<&str>,
+        timeout: Option<Duration>,
+    ) -> Result<CalibrationResult, AutoCalibrationError> {
+        let timeout_ms = timeout
+            .unwrap_or(DEFAULT_CALIBRATION_TIMEOUT)
+            .as_millis()
+            .try_into()
+            .unwrap_or(c_int::MAX);
+        let (json_ptr, json_len) = match json_config {
+            Some(json) => (json.as_ptr().cast::<c_void>(), json.len() as c_int),
+            None => (ptr::null(), 0),
+        };
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let mut health = 0.0_f32;
+            let buffer = sys::rs2_run_on_chip_calibration(
+                self.0.get_raw().as_ptr(),
+                json_ptr,
+                json_len,
+                &mut health,
+                None,
+                ptr::null_mut(),
+                timeout_ms,
+                &mut err,
+            );
+            check_rs2_error!(err, AutoCalibrationError::CouldNotRunOnChipCalibration)?;
+
+            let table = take_raw_data(buffer, AutoCalibrationError::CouldNotRunOnChipCalibration)?;
+            Ok(CalibrationResult { table, health })
+        }
+    }
+
+    /// Reads the calibration table that is currently in use by the device.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AutoCalibrationError::CouldNotGetCalibrationTable`] if the table cannot be read.
+    pub fn calibration_table(&self) -> Result<Vec<u8>, AutoCalibrationError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let buffer = sys::rs2_get_calibration_table(self.0.get_raw().as_ptr(), &mut err);
+            check_rs2_error!(err, AutoCalibrationError::CouldNotGetCalibrationTable)?;
+
+            take_raw_data(buffer, AutoCalibrationError::CouldNotGetCalibrationTable)
+        }
+    }
+
+    /// Applies a calibration table, such as the [table](CalibrationResult::table) computed by
+    /// on-chip calibration, to the device.
+    ///
+    /// The table is only applied until the device is reset. Use
+    /// [`write_calibration`](Self::write_calibration) to make it persist.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AutoCalibrationError::CouldNotSetCalibrationTable`] if the table cannot be
+    /// applied, e.g. because it is not a valid calibration table for the device.
+    pub fn set_calibration_table(&self, table: &[u8]) -> Result<(), AutoCalibrationError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_set_calibration_table(
+                self.0.get_raw().as_ptr(),
+                table.as_ptr().cast::<c_void>(),
+                table.len() as c_int,
+                &mut err,
+            );
+            check_rs2_error!(err, AutoCalibrationError::CouldNotSetCalibrationTable)
+        }
+    }
+
+    /// Writes the calibration table that is currently applied to the flash memory of the
+    /// device, so that it persists across resets.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AutoCalibrationError::CouldNotWriteCalibration`] if the calibration cannot be
+    /// written.
+    pub fn write_calibration(&self) -> Result<(), AutoCalibrationError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_write_calibration(self.0.get_raw().as_ptr(), &mut err);
+            check_rs2_error!(err, AutoCalibrationError::CouldNotWriteCalibration)
+        }
+    }
+
+    /// Restores the calibration that the device shipped with.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AutoCalibrationError::CouldNotResetToFactoryCalibration`] if the calibration
+    /// cannot be restored.
+    pub fn reset_to_factory_calibration(&self) -> Result<(), AutoCalibrationError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_reset_to_factory_calibration(self.0.get_raw().as_ptr(), &mut err);
+            check_rs2_error!(err, AutoCalibrationError::CouldNotResetToFactoryCalibration)
+        }
+    }
+}
+
+/// Copies the data out of a raw data buffer returned by librealsense2, and deletes the buffer.
+///
+/// # Safety
+///
+/// `buffer` must be a valid raw data buffer, which is not used after this call.
+unsafe fn take_raw_data(
+    buffer: *const sys::rs2_raw_data_buffer,
+    to_error: fn(Rs2Exception, String) -> AutoCalibrationError,
+) -> Result<Vec<u8>, AutoCalibrationError> {
+    let mut err = std::ptr::null_mut::<sys::rs2_error>();
+    let size = sys::rs2_get_raw_data_size(buffer, &mut err);
+    if let Err(e) = check_rs2_error!(err, to_error) {
+        sys::rs2_delete_raw_data(buffer);
+        return Err(e);
+    }
+
+    let data = sys::rs2_get_raw_data(buffer, &mut err);
+    if let Err(e) = check_rs2_error!(err, to_error) {
+        sys::rs2_delete_raw_data(buffer);
+        return Err(e);
+    }
+
+    let table = slice::from_raw_parts(data, size as usize).to_vec();
+    sys::rs2_delete_raw_data(buffer);
+    Ok(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn health_is_classified_by_magnitude() {
+        assert_eq!(CalibrationHealth::from_health(0.0), CalibrationHealth::Good);
+        assert_eq!(
+            CalibrationHealth::from_health(-0.2),
+            CalibrationHealth::Good
+        );
+        assert_eq!(
+            CalibrationHealth::from_health(0.25),
+            CalibrationHealth::CanBeImproved
+        );
+        assert_eq!(
+            CalibrationHealth::from_health(-0.5),
+            CalibrationHealth::CanBeImproved
+        );
+        assert_eq!(
+            CalibrationHealth::from_health(0.75),
+            CalibrationHealth::RequiresCalibration
+        );
+        assert_eq!(
+            CalibrationHealth::from_health(-1.3),
+            CalibrationHealth::RequiresCalibration
+        );
+    }
+
+    #[test]
+    fn calibration_result_reports_health_status() {
+        let result = CalibrationResult {
+            table: vec![0x14, 0x00, 0x20, 0x00],
+            health: 0.31,
+        };
+        assert_eq!(result.health_status(), CalibrationHealth::CanBeImproved);
+    }
+}
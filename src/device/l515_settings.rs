@@ -0,0 +1,154 @@
+//! Typed access to the depth options of L500 series LiDAR devices.
+
+use super::Device;
+use crate::{
+    kind::{OptionSetError, Rs2DigitalGain, Rs2Option},
+    sensor::DepthSensor,
+};
+use num_traits::FromPrimitive;
+use thiserror::Error;
+
+/// The product line that librealsense2 reports for L500 series devices, such as the L515.
+const L500_PRODUCT_LINE: &str = "L500";
+
+/// Enumeration of possible errors that can occur while acquiring the settings of an L515.
+#[derive(Error, Debug)]
+pub enum L515SettingsError {
+    /// The device is not an L500 series device.
+    #[error("Device of product line {0:?} is not an L500 series device.")]
+    NotAnL515(Option<String>),
+    /// The device has no depth sensor to apply the settings to.
+    #[error("Device has no depth sensor.")]
+    NoDepthSensor,
+}
+
+/// The LiDAR-specific depth options of an L500 series device, such as the L515.
+///
+/// The options of the L515 interact: e.g. a high [receiver gain](Self::set_receiver_gain)
+/// combined with [high digital gain](Rs2DigitalGain::High) saturates in bright ambient light, and
+/// the [confidence threshold](Self::set_confidence_threshold) decides which of the resulting
+/// pixels are kept. Tuning them through raw [`Rs2Option`] values is error-prone, since nothing
+/// ties an option to the devices that support it, so this groups them behind typed accessors.
+///
+/// Acquired with [`Device::l515_settings`], which checks the product line of the device.
+///
+/// Getters return `None` if the option cannot be read, e.g. because the firmware does not support
+/// it. Setters return the same errors as [`Sensor::set_option`](crate::sensor::Sensor::set_option).
+#[derive(Debug)]
+pub struct L515Settings {
+    /// The depth sensor of the device, which holds the options.
+    sensor: DepthSensor,
+}
+
+impl L515Settings {
+    /// Gets the digital gain of the depth sensor.
+    pub fn digital_gain(&self) -> Option<Rs2DigitalGain> {
+        self.sensor
+            .get_option(Rs2Option::DigitalGain)
+            .and_then(Rs2DigitalGain::from_f32)
+    }
+
+    /// Sets the digital gain of the depth sensor.
+    pub fn set_digital_gain(&mut self, gain: Rs2DigitalGain) -> Result<(), OptionSetError> {
+        self.sensor.set_option_enum(Rs2Option::DigitalGain, gain)
+    }
+
+    /// Gets the power of the laser.
+    pub fn laser_power(&self) -> Option<f32> {
+        self.sensor.get_option(Rs2Option::LaserPower)
+    }
+
+    /// Sets the power of the laser.
+    pub fn set_laser_power(&mut self, power: f32) -> Result<(), OptionSetError> {
+        self.sensor.set_option(Rs2Option::LaserPower, power)
+    }
+
+    /// Gets the minimal distance to the target below which depth is invalidated.
+    pub fn min_distance(&self) -> Option<f32> {
+        self.sensor.get_option(Rs2Option::MinDistance)
+    }
+
+    /// Sets the minimal distance to the target below which depth is invalidated.
+    pub fn set_min_distance(&mut self, distance: f32) -> Result<(), OptionSetError> {
+        self.sensor.set_option(Rs2Option::MinDistance, distance)
+    }
+
+    /// Gets the level of filtering of edges and background noise.
+    pub fn noise_filter_level(&self) -> Option<f32> {
+        self.sensor.get_option(Rs2Option::NoiseFiltering)
+    }
+
+    /// Sets the level of filtering of edges and background noise.
+    pub fn set_noise_filter_level(&mut self, level: f32) -> Result<(), OptionSetError> {
+        self.sensor.set_option(Rs2Option::NoiseFiltering, level)
+    }
+
+    /// Gets the confidence below which depth pixels are invalidated.
+    pub fn confidence_threshold(&self) -> Option<f32> {
+        self.sensor.get_option(Rs2Option::ConfidenceThreshold)
+    }
+
+    /// Sets the confidence below which depth pixels are invalidated.
+    pub fn set_confidence_threshold(&mut self, threshold: f32) -> Result<(), OptionSetError> {
+        self.sensor
+            .set_option(Rs2Option::ConfidenceThreshold, threshold)
+    }
+
+    /// Gets the gain of the receiver, i.e. its sensitivity to both projected and ambient light.
+    ///
+    /// This is the [`Rs2Option::AvalanchePhotoDiode`] option, which is the L515 counterpart of
+    /// [`Rs2Option::ReceiverSensitivity`].
+    pub fn receiver_gain(&self) -> Option<f32> {
+        self.sensor.get_option(Rs2Option::AvalanchePhotoDiode)
+    }
+
+    /// Sets the gain of the receiver, i.e. its sensitivity to both projected and ambient light.
+    pub fn set_receiver_gain(&mut self, gain: f32) -> Result<(), OptionSetError> {
+        self.sensor.set_option(Rs2Option::AvalanchePhotoDiode, gain)
+    }
+
+    /// Converts back into the underlying [`DepthSensor`], e.g. to access other options.
+    pub fn into_sensor(self) -> DepthSensor {
+        self.sensor
+    }
+}
+
+impl Device {
+    /// Gets the LiDAR-specific depth settings of the device, if it is an L500 series device.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`L515SettingsError::NotAnL515`] if the device does not belong to the L500 product
+    /// line.
+    ///
+    /// Returns [`L515SettingsError::NoDepthSensor`] if the depth sensor cannot be acquired.
+    pub fn l515_settings(&self) -> Result<L515Settings, L515SettingsError> {
+        let product_line = self.product_line();
+        if !is_l500_product_line(product_line.as_deref()) {
+            return Err(L515SettingsError::NotAnL515(product_line));
+        }
+
+        let sensor = self
+            .first_depth_sensor()
+            .ok_or(L515SettingsError::NoDepthSensor)?;
+        Ok(L515Settings { sensor })
+    }
+}
+
+/// Predicate for whether a device of the given product line is an L500 series device.
+fn is_l500_product_line(product_line: Option<&str>) -> bool {
+    product_line == Some(L500_PRODUCT_LINE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_l500_product_line_is_accepted() {
+        assert!(is_l500_product_line(Some("L500")));
+        assert!(!is_l500_product_line(Some("D400")));
+        assert!(!is_l500_product_line(Some("T200")));
+        assert!(!is_l500_product_line(None));
+    }
+}
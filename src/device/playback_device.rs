@@ -0,0 +1,260 @@
+//! Type for devices which play back streams previously recorded to a file.
+
+use super::Device;
+use crate::{base::from_path, check_rs2_error, kind::Rs2Exception};
+use anyhow::Result;
+use realsense_sys as sys;
+use std::{convert::TryFrom, ffi::CStr, ops::Deref, path::Path, ptr::NonNull, time::Duration};
+use thiserror::Error;
+
+/// Converts a playback position to the nanoseconds librealsense2 seeks to.
+///
+/// Positions beyond what fits in an `i64`, about 292 years, are clamped to `i64::MAX`.
+fn position_nanos(position: Duration) -> i64 {
+    i64::try_from(position.as_nanos()).unwrap_or(i64::MAX)
+}
+
+/// Enumeration of possible errors that can occur while controlling playback from a file.
+#[derive(Error, Debug)]
+pub enum PlaybackDeviceError {
+    /// Could not create a playback device from the file.
+    #[error("Could not create playback device. Type: {0}; Reason: {1}")]
+    CouldNotCreatePlaybackDevice(Rs2Exception, String),
+    /// Could not pause the playback.
+    #[error("Could not pause playback. Type: {0}; Reason: {1}")]
+    CouldNotPausePlayback(Rs2Exception, String),
+    /// Could not resume the playback.
+    #[error("Could not resume playback. Type: {0}; Reason: {1}")]
+    CouldNotResumePlayback(Rs2Exception, String),
+    /// Could not seek to the requested position in the file.
+    #[error("Could not seek playback. Type: {0}; Reason: {1}")]
+    CouldNotSeek(Rs2Exception, String),
+    /// Could not get the current position of the playback.
+    #[error("Could not get playback position. Type: {0}; Reason: {1}")]
+    CouldNotGetPosition(Rs2Exception, String),
+    /// Could not get the total duration of the file.
+    #[error("Could not get playback duration. Type: {0}; Reason: {1}")]
+    CouldNotGetDuration(Rs2Exception, String),
+    /// Could not set the speed of the playback.
+    #[error("Could not set playback speed. Type: {0}; Reason: {1}")]
+    CouldNotSetPlaybackSpeed(Rs2Exception, String),
+    /// Could not set whether the playback happens in real time.
+    #[error("Could not set real time playback. Type: {0}; Reason: {1}")]
+    CouldNotSetRealTime(Rs2Exception, String),
+    /// Could not determine whether the playback happens in real time.
+    #[error("Could not determine if playback is real time. Type: {0}; Reason: {1}")]
+    CouldNotGetRealTime(Rs2Exception, String),
+}
+
+/// A device which plays back the streams recorded to a `.bag` file.
+///
+/// Playback devices are acquired one of two ways:
+///
+/// 1. By opening a file directly with [`PlaybackDevice::new`].
+/// 2. By streaming from a [file](crate::config::Config::enable_device_from_file) with a pipeline.
+///    The [pipeline's device](crate::pipeline::PipelineProfile::device) is then a playback
+///    device, which can be accessed with [`Device::as_playback`].
+///
+/// # Real time playback
+///
+/// By default, frames are played back at the rate they were recorded, and frames are dropped if
+/// they are not consumed quickly enough. Disabling [real time](Self::set_real_time) playback
+/// instead delivers every frame in the file, waiting for each to be consumed. This is what you
+/// want for deterministic processing, e.g. in tests.
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct PlaybackDevice(Device);
+
+impl Deref for PlaybackDevice {
+    type Target = Device;
+
+    fn deref(&self) -> &Device {
+        &self.0
+    }
+}
+
+impl PlaybackDevice {
+    /// Creates a playback device which plays back the recording in `file`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NulError`](std::ffi::NulError) if the provided file path cannot be cleanly
+    /// represented as a [`CString`](std::ffi::CString).
+    ///
+    /// Returns [`PlaybackDeviceError::CouldNotCreatePlaybackDevice`] if the playback device cannot
+    /// be created, e.g. if the file does not exist or is not a valid recording.
+    pub fn new<P>(file: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = from_path(file)?;
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let device_ptr = sys::rs2_create_playback_device(path.as_ptr(), &mut err);
+            check_rs2_error!(err, PlaybackDeviceError::CouldNotCreatePlaybackDevice)?;
+
+            Ok(Self(Device::from(NonNull::new(device_ptr).unwrap())))
+        }
+    }
+
+    /// Pauses the playback.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PlaybackDeviceError::CouldNotPausePlayback`] if the playback cannot be paused.
+    pub fn pause(&self) -> Result<(), PlaybackDeviceError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_playback_device_pause(self.0.get_raw().as_ptr(), &mut err);
+            check_rs2_error!(err, PlaybackDeviceError::CouldNotPausePlayback)
+        }
+    }
+
+    /// Resumes a [paused](Self::pause) playback.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PlaybackDeviceError::CouldNotResumePlayback`] if the playback cannot be resumed.
+    pub fn resume(&self) -> Result<(), PlaybackDeviceError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_playback_device_resume(self.0.get_raw().as_ptr(), &mut err);
+            check_rs2_error!(err, PlaybackDeviceError::CouldNotResumePlayback)
+        }
+    }
+
+    /// Moves the playback to `position`, relative to the start of the file.
+    ///
+    /// Positions too large to be represented in nanoseconds as an `i64` are clamped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PlaybackDeviceError::CouldNotSeek`] if the playback cannot be moved, e.g. if
+    /// `position` is past the end of the file.
+    pub fn seek(&self, position: Duration) -> Result<(), PlaybackDeviceError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_playback_seek(
+                self.0.get_raw().as_ptr(),
+                position_nanos(position),
+                &mut err,
+            );
+            check_rs2_error!(err, PlaybackDeviceError::CouldNotSeek)
+        }
+    }
+
+    /// Gets the current position of the playback, relative to the start of the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PlaybackDeviceError::CouldNotGetPosition`] if the position cannot be retrieved.
+    pub fn current_position(&self) -> Result<Duration, PlaybackDeviceError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let position = sys::rs2_playback_get_position(self.0.get_raw().as_ptr(), &mut err);
+            check_rs2_error!(err, PlaybackDeviceError::CouldNotGetPosition)?;
+
+            Ok(Duration::from_nanos(position))
+        }
+    }
+
+    /// Gets the total duration of the recording.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PlaybackDeviceError::CouldNotGetDuration`] if the duration cannot be retrieved.
+    pub fn duration(&self) -> Result<Duration, PlaybackDeviceError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let duration = sys::rs2_playback_get_duration(self.0.get_raw().as_ptr(), &mut err);
+            check_rs2_error!(err, PlaybackDeviceError::CouldNotGetDuration)?;
+
+            Ok(Duration::from_nanos(duration))
+        }
+    }
+
+    /// Sets the speed of the playback, relative to the speed it was recorded at.
+    ///
+    /// e.g. a speed of `2.0` plays back twice as fast as the recording, and `0.5` half as fast.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PlaybackDeviceError::CouldNotSetPlaybackSpeed`] if the speed cannot be set.
+    pub fn set_playback_speed(&self, speed: f32) -> Result<(), PlaybackDeviceError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_playback_device_set_playback_speed(self.0.get_raw().as_ptr(), speed, &mut err);
+            check_rs2_error!(err, PlaybackDeviceError::CouldNotSetPlaybackSpeed)
+        }
+    }
+
+    /// Sets whether the playback happens in real time.
+    ///
+    /// See the [type level documentation](PlaybackDevice#real-time-playback) for what this
+    /// changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PlaybackDeviceError::CouldNotSetRealTime`] if the mode cannot be set.
+    pub fn set_real_time(&self, real_time: bool) -> Result<(), PlaybackDeviceError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_playback_device_set_real_time(
+                self.0.get_raw().as_ptr(),
+                real_time as i32,
+                &mut err,
+            );
+            check_rs2_error!(err, PlaybackDeviceError::CouldNotSetRealTime)
+        }
+    }
+
+    /// Predicate for whether the playback happens in real time.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PlaybackDeviceError::CouldNotGetRealTime`] if the mode cannot be determined.
+    pub fn is_real_time(&self) -> Result<bool, PlaybackDeviceError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let real_time =
+                sys::rs2_playback_device_is_real_time(self.0.get_raw().as_ptr(), &mut err);
+            check_rs2_error!(err, PlaybackDeviceError::CouldNotGetRealTime)?;
+
+            Ok(real_time != 0)
+        }
+    }
+
+    /// Gets the path of the file being played back.
+    ///
+    /// Returns `None` if the path cannot be retrieved.
+    pub fn file_path(&self) -> Option<String> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let path = sys::rs2_playback_device_get_file_path(self.0.get_raw().as_ptr(), &mut err);
+
+            if err.as_ref().is_none() {
+                path.as_ref()
+                    .map(|p| CStr::from_ptr(p).to_string_lossy().into_owned())
+            } else {
+                sys::rs2_free_error(err);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn position_is_converted_to_nanoseconds() {
+        assert_eq!(position_nanos(Duration::from_millis(1500)), 1_500_000_000);
+    }
+
+    #[test]
+    fn position_beyond_i64_is_clamped() {
+        assert_eq!(position_nanos(Duration::MAX), i64::MAX);
+        assert_eq!(position_nanos(Duration::from_nanos(u64::MAX)), i64::MAX);
+    }
+}
@@ -0,0 +1,122 @@
+//! Type for devices which record everything they stream to a file.
+
+use super::Device;
+use crate::{base::from_path, check_rs2_error, kind::Rs2Exception};
+use anyhow::Result;
+use realsense_sys as sys;
+use std::{ffi::CStr, ops::Deref, path::Path, ptr::NonNull};
+use thiserror::Error;
+
+/// Enumeration of possible errors that can occur while recording from a device.
+#[derive(Error, Debug)]
+pub enum RecordDeviceError {
+    /// Could not create a record device wrapping the device.
+    #[error("Could not create record device. Type: {0}; Reason: {1}")]
+    CouldNotCreateRecordDevice(Rs2Exception, String),
+    /// Could not pause the recording.
+    #[error("Could not pause recording. Type: {0}; Reason: {1}")]
+    CouldNotPauseRecording(Rs2Exception, String),
+    /// Could not resume the recording.
+    #[error("Could not resume recording. Type: {0}; Reason: {1}")]
+    CouldNotResumeRecording(Rs2Exception, String),
+}
+
+/// A device which records all of the data streamed from it to a `.bag` file.
+///
+/// Record devices are acquired one of two ways:
+///
+/// 1. By wrapping a device directly with [`RecordDevice::new`]. Streaming from the sensors of the
+///    record device streams from the wrapped device, and records those streams.
+/// 2. By enabling [recording](crate::config::Config::enable_record_to_file) on a pipeline
+///    configuration. The [pipeline's device](crate::pipeline::PipelineProfile::device) is then a
+///    record device, which can be accessed with [`Device::as_recorder`].
+///
+/// The recording can be replayed by streaming from the file with
+/// [`Config::enable_device_from_file`](crate::config::Config::enable_device_from_file).
+///
+/// The file is finalized and flushed once the record device is dropped. For pipelines, this
+/// happens once the pipeline is stopped.
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct RecordDevice(Device);
+
+impl Deref for RecordDevice {
+    type Target = Device;
+
+    fn deref(&self) -> &Device {
+        &self.0
+    }
+}
+
+impl RecordDevice {
+    /// Creates a record device that records everything streamed from `device` to `file`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NulError`](std::ffi::NulError) if the provided file path cannot be cleanly
+    /// represented as a [`CString`](std::ffi::CString).
+    ///
+    /// Returns [`RecordDeviceError::CouldNotCreateRecordDevice`] if the record device cannot be
+    /// created, e.g. if the file cannot be written to.
+    pub fn new<P>(device: &Device, file: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = from_path(file)?;
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let device_ptr =
+                sys::rs2_create_record_device(device.get_raw().as_ptr(), path.as_ptr(), &mut err);
+            check_rs2_error!(err, RecordDeviceError::CouldNotCreateRecordDevice)?;
+
+            Ok(Self(Device::from(NonNull::new(device_ptr).unwrap())))
+        }
+    }
+
+    /// Pauses the recording.
+    ///
+    /// While paused, the device keeps streaming but nothing is written to the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RecordDeviceError::CouldNotPauseRecording`] if the recording cannot be paused.
+    pub fn pause(&self) -> Result<(), RecordDeviceError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_record_device_pause(self.0.get_raw().as_ptr(), &mut err);
+            check_rs2_error!(err, RecordDeviceError::CouldNotPauseRecording)
+        }
+    }
+
+    /// Resumes a [paused](Self::pause) recording.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RecordDeviceError::CouldNotResumeRecording`] if the recording cannot be resumed.
+    pub fn resume(&self) -> Result<(), RecordDeviceError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_record_device_resume(self.0.get_raw().as_ptr(), &mut err);
+            check_rs2_error!(err, RecordDeviceError::CouldNotResumeRecording)
+        }
+    }
+
+    /// Gets the name of the file being recorded to.
+    ///
+    /// Returns `None` if the file name cannot be retrieved.
+    pub fn filename(&self) -> Option<String> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let filename = sys::rs2_record_device_filename(self.0.get_raw().as_ptr(), &mut err);
+
+            if err.as_ref().is_none() {
+                filename
+                    .as_ref()
+                    .map(|f| CStr::from_ptr(f).to_string_lossy().into_owned())
+            } else {
+                sys::rs2_free_error(err);
+                None
+            }
+        }
+    }
+}
@@ -47,7 +47,8 @@ impl DeviceHub {
     ///
     /// If any device is connected, this method will return that device. It will cycle through
     /// devices if multiple are connected. Otherwise, it blocks the calling thread until a device
-    /// is connected.
+    /// is connected, however long that takes. To give up after a timeout instead, use
+    /// [`Context::wait_for_device`](crate::context::Context::wait_for_device).
     ///
     /// # Errors
     ///
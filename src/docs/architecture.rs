@@ -87,6 +87,20 @@
 //! Please [submit a bug report](https://gitlab.com/tangram-vision-oss/realsense-rust/-/issues) if you've found some
 //! scenario in which an object you retained is holding onto invalid or otherwise deleted pointers.
 //!
+//! #### Contexts, devices and pipelines
+//!
+//! A [`Context`](crate::context::Context) is the root of every other object in the API, so it is natural to ask whether
+//! it has to outlive the devices, sensors and pipelines created from it. It does not. In librealsense2, the context is held
+//! by a `shared_ptr`, and every device, sensor and pipeline created from it holds its own reference to it. Dropping a
+//! `Context` on the Rust side only releases its own reference, so the underlying context stays alive until the last
+//! object created from it is dropped, e.g. while a pipeline is still streaming. This is why pipelines and devices neither
+//! borrow the context nor hold an `Arc` to it, and why they can be sent to other threads independently of it.
+//!
+//! The one thing a `Context` owns on the Rust side is the callback registered through
+//! [`Context::set_devices_changed_callback`](crate::context::Context::set_devices_changed_callback), since
+//! librealsense2 only holds a pointer to it. Dropping the context detaches the callback before freeing it, so device
+//! events that occur while a pipeline keeps the underlying context alive are discarded.
+//!
 //! ### Make invalid states unrepresentable
 //!
 //! The main way we aim to do this is by understanding the lifetimes of the low-level pointers that the realsense-sys
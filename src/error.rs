@@ -1,5 +1,10 @@
 //! Defines utilities for dealing with errors across the crate
 
+use crate::kind::Rs2Exception;
+use num_traits::FromPrimitive;
+use realsense_sys as sys;
+use std::{ffi::CStr, os::raw::c_char};
+
 /// Helper macro for checking errors that are returned from the low-level C-API.
 ///
 /// # Why a macro?
@@ -87,6 +92,8 @@
 /// 2. Performs a null check on that error
 /// 3. Converts that null check to either an `Ok(())` or constructs your custom error type if the
 ///    pointer is non-null.
+/// 4. Frees the error, after copying its exception type and message into the custom error type,
+///    so that no error allocated by librealsense2 is leaked.
 ///
 /// Having this expand to a block-expression has some benefits. The chief one is that when you use
 /// this macro the expression returns, which means that if you fail to check the result the
@@ -137,28 +144,89 @@ macro_rules! check_rs2_error {
     ($rs2_error:expr, $result:expr) => {
         // We make this alias here to type check $rs2_error.
         {
-            use num_traits::FromPrimitive;
-            use std::convert::TryInto;
-            use $crate::kind::Rs2Exception;
             let err: *mut sys::rs2_error = $rs2_error;
             if err.as_ref().is_some() {
-                let realsense_exception_type = sys::rs2_get_librealsense_exception_type(err);
-                let realsense_exception_type_i32 = realsense_exception_type.try_into().unwrap();
-
-                let res = $result(
-                    Rs2Exception::from_i32(realsense_exception_type_i32).unwrap_or_else(|| {
-                        panic!("Unknown Rs2Exception: {}", realsense_exception_type_i32)
-                    }),
-                    std::ffi::CStr::from_ptr(sys::rs2_get_error_message(err))
-                        .to_str()
-                        .unwrap()
-                        .to_string(),
-                );
-                sys::rs2_free_error(err);
-                Err(res)
+                let (exception, message) = $crate::error::take_rs2_error(err);
+                Err($result(exception, message))
             } else {
                 Ok(())
             }
         }
     };
 }
+
+/// Takes ownership of an error returned by the C-API, and frees it once its exception type and
+/// message have been copied out.
+///
+/// Every error returned by librealsense2 is allocated on the C++ side, so it has to be freed with
+/// `rs2_free_error` on every error path, or it leaks. Copying the message into an owned `String`
+/// first means that the error can be freed right away, rather than its lifetime being tied to the
+/// Rust error that it is converted to.
+///
+/// An exception type that the bindings do not know about is reported as
+/// [`Rs2Exception::Unknown`], and a message that is not valid UTF-8 is converted lossily, so that a
+/// failing call never panics while its error is being reported.
+///
+/// # Safety
+///
+/// `err` must be a non-null error returned by librealsense2, which has not been freed yet. It is
+/// freed by this call, so it must not be used afterwards.
+pub(crate) unsafe fn take_rs2_error(err: *mut sys::rs2_error) -> (Rs2Exception, String) {
+    let exception = exception_from_type(sys::rs2_get_librealsense_exception_type(err) as i32);
+    let message = message_from_ptr(sys::rs2_get_error_message(err));
+    sys::rs2_free_error(err);
+
+    (exception, message)
+}
+
+/// Converts the exception type of an error into an [`Rs2Exception`].
+fn exception_from_type(exception_type: i32) -> Rs2Exception {
+    Rs2Exception::from_i32(exception_type).unwrap_or(Rs2Exception::Unknown)
+}
+
+/// Copies the message of an error into an owned `String`.
+///
+/// # Safety
+///
+/// `message` must either be null, or point to a nul-terminated string.
+unsafe fn message_from_ptr(message: *const c_char) -> String {
+    if message.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(message).to_string_lossy().into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    #[test]
+    fn unknown_exception_types_are_reported_as_unknown() {
+        assert_eq!(
+            exception_from_type(sys::rs2_exception_type_RS2_EXCEPTION_TYPE_INVALID_VALUE as i32),
+            Rs2Exception::InvalidValue
+        );
+        assert_eq!(exception_from_type(-1), Rs2Exception::Unknown);
+        assert_eq!(exception_from_type(i32::MAX), Rs2Exception::Unknown);
+    }
+
+    #[test]
+    fn messages_are_copied_into_owned_strings() {
+        let message = CString::new("failed to set option").unwrap();
+        let copied = unsafe { message_from_ptr(message.as_ptr()) };
+        drop(message);
+
+        assert_eq!(copied, "failed to set option");
+        assert_eq!(unsafe { message_from_ptr(std::ptr::null()) }, "");
+    }
+
+    #[test]
+    fn invalid_utf8_messages_are_converted_lossily() {
+        let message = CString::new(vec![b'b', b'a', b'd', 0xff]).unwrap();
+        let copied = unsafe { message_from_ptr(message.as_ptr()) };
+
+        assert_eq!(copied, "bad\u{fffd}");
+    }
+}
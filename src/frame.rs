@@ -1,20 +1,76 @@
 //! Defines the frame type including sensor data.
+//!
+//! # Thread safety
+//!
+//! Every frame type is [`Send`], so frames can be handed to another thread for processing, and
+//! [`Sync`], so a single frame can be read from multiple threads at once, e.g. by wrapping it in an
+//! [`Arc`](std::sync::Arc) and processing parts of it in parallel.
+//!
+//! Each frame value owns one reference to the underlying librealsense2 frame, which is released
+//! exactly once when the value is dropped. librealsense2 counts these references atomically, so
+//! frames extracted from the same [`CompositeFrame`] may be dropped on different threads. Frames
+//! cannot be cloned; share them with an `Arc` instead.
+//!
+//! The data of a frame is never modified through a shared reference. The only way to mutate frame
+//! data is [`ImageFrame::data_mut`], which requires exclusive access to the frame value, and only
+//! succeeds for frames that no other frame value can refer to.
+//!
+//! # Logging
+//!
+//! Every frame type except [`CompositeFrame`] implements [`Display`](std::fmt::Display) with a
+//! one-line summary of its stream, frame number and timestamp, such as
+//! `Depth frame #42 (stream 0, Z16 848x480, 814080 bytes) at 1602345123.456 ms (GlobalTime)`.
+//! Formatting a frame only uses values cached when the frame was constructed, so it never calls
+//! into librealsense2 and cannot fail.
 
+mod aligned_depth;
+mod bytes;
 mod composite;
 mod image;
+mod keep_last;
 mod motion;
 mod pixel;
 mod points;
 mod pose;
 mod prelude;
+mod sequence;
+mod time_aligner;
 
 pub use self::image::{
     ColorFrame, ConfidenceFrame, DepthFrame, DisparityFrame, FisheyeFrame, ImageFrame,
     InfraredFrame,
 };
-pub use self::motion::{AccelFrame, GyroFrame, MotionFrame};
+pub use self::motion::{Accel, AccelFrame, Gyro, GyroFrame, MotionFrame};
 pub use self::points::PointsFrame;
-pub use composite::CompositeFrame;
+pub use aligned_depth::AlignedDepth;
+pub use bytes::FrameBytes;
+pub use composite::{CompositeFrame, CompositeFrameIter, MissingFrameError};
+pub use keep_last::KeepLast;
 pub use pixel::PixelKind;
-pub use pose::{Confidence, PoseFrame};
-pub use prelude::{FrameCategory, FrameConstructionError, FrameEx};
+pub use pose::{Confidence, PoseData, PoseFrame};
+pub use prelude::{
+    DepthError, DisparityError, FormatError, FrameCategory, FrameConstructionError, FrameDataError,
+    FrameEx, FrameSizeMismatch, MetadataError, MetadataIter, SharedFrameError,
+};
+pub use sequence::{FrameSequenceMonitor, SequenceStatus};
+
+pub(crate) use prelude::frame_of_category;
+pub use time_aligner::{MotionSample, TimeAligner};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn frames_are_send_and_sync() {
+        assert_send_sync::<CompositeFrame>();
+        assert_send_sync::<DepthFrame>();
+        assert_send_sync::<ColorFrame>();
+        assert_send_sync::<AccelFrame>();
+        assert_send_sync::<GyroFrame>();
+        assert_send_sync::<PointsFrame>();
+        assert_send_sync::<PoseFrame>();
+    }
+}
@@ -0,0 +1,275 @@
+//! Type for depth that has been reprojected onto the viewport of another stream on the CPU.
+
+use crate::base::{Rs2Extrinsics, Rs2Intrinsics};
+use std::ops::RangeInclusive;
+
+/// Depth that has been aligned to the viewport of another stream, e.g. color.
+///
+/// Created with [`DepthFrame::align_to`](super::DepthFrame::align_to). Unlike the frames output
+/// by the [`Align`](crate::processing_blocks::Align) processing block, this is owned by Rust
+/// rather than by librealsense2, so it does not hold on to a frame of the device and can be kept
+/// for as long as needed.
+///
+/// The depth has the resolution of the stream that it was aligned to, and is stored in row-major
+/// order as raw values in [`units`](Self::units), like the data of a [`Rs2Format::Z16`] frame. A
+/// value of zero means that no depth pixel maps to that pixel.
+///
+/// [`Rs2Format::Z16`]: crate::kind::Rs2Format::Z16
+#[derive(Debug)]
+pub struct AlignedDepth {
+    /// The raw depth of every pixel, in row-major order.
+    depth: Vec<u16>,
+    /// The number of meters per unit of raw depth.
+    units: f32,
+    /// The intrinsics of the stream that the depth was aligned to.
+    intrinsics: Rs2Intrinsics,
+}
+
+impl AlignedDepth {
+    /// Constructs aligned depth from `depth`, which holds one value for every pixel described
+    /// by `intrinsics`.
+    pub(crate) fn new(depth: Vec<u16>, units: f32, intrinsics: Rs2Intrinsics) -> Self {
+        debug_assert_eq!(depth.len(), intrinsics.width() * intrinsics.height());
+        Self {
+            depth,
+            units,
+            intrinsics,
+        }
+    }
+
+    /// Gets the width of the aligned depth in pixels.
+    pub fn width(&self) -> usize {
+        self.intrinsics.width()
+    }
+
+    /// Gets the height of the aligned depth in pixels.
+    pub fn height(&self) -> usize {
+        self.intrinsics.height()
+    }
+
+    /// Gets the number of meters per unit of raw depth.
+    ///
+    /// These are the [units](super::DepthFrame::units) of the frame that was aligned.
+    pub fn units(&self) -> f32 {
+        self.units
+    }
+
+    /// Gets the intrinsics of the stream that the depth was aligned to.
+    pub fn intrinsics(&self) -> &Rs2Intrinsics {
+        &self.intrinsics
+    }
+
+    /// Gets the raw depth of every pixel, in row-major order.
+    pub fn as_slice(&self) -> &[u16] {
+        &self.depth
+    }
+
+    /// Gets the raw depth of the pixel at `(col, row)`.
+    ///
+    /// Returns `None` if the pixel is outside of the aligned depth.
+    pub fn get(&self, col: usize, row: usize) -> Option<u16> {
+        if col >= self.width() || row >= self.height() {
+            return None;
+        }
+        self.depth.get(row * self.width() + col).copied()
+    }
+
+    /// Gets the distance in meters to the pixel at `(col, row)`.
+    ///
+    /// A distance of zero means that the depth of the pixel is unknown. Returns `None` if the
+    /// pixel is outside of the aligned depth.
+    pub fn distance(&self, col: usize, row: usize) -> Option<f32> {
+        self.get(col, row).map(|depth| depth as f32 * self.units)
+    }
+
+    /// Takes the raw depth of every pixel, in row-major order.
+    pub fn into_vec(self) -> Vec<u16> {
+        self.depth
+    }
+}
+
+/// Reprojects `depth`, which holds one raw value for every pixel described by `from`, onto the
+/// pixels described by `to`.
+///
+/// Every pixel with a valid depth is deprojected to 3D, transformed by `extrinsics` and projected
+/// again, like the `align_z_to_other` kernel of librealsense2. To avoid holes when the target has
+/// a higher resolution, the two opposite corners of each depth pixel are projected, and every
+/// target pixel whose center lies between them gets the depth. If no center lies between them,
+/// i.e. the target has a lower resolution, the depth goes to the nearest target pixel instead.
+/// Where several depth pixels land on the same target pixel, the closest depth wins, since it
+/// occludes the others.
+pub(crate) fn align_depth(
+    depth: &[u16],
+    units: f32,
+    from: &Rs2Intrinsics,
+    extrinsics: &Rs2Extrinsics,
+    to: &Rs2Intrinsics,
+) -> Vec<u16> {
+    let (from_width, to_width, to_height) = (from.width(), to.width(), to.height());
+    let mut aligned = vec![0_u16; to_width * to_height];
+    if from_width == 0 {
+        return aligned;
+    }
+
+    let project_corner = |x: f32, y: f32, meters: f32| {
+        to.project(extrinsics.transform_point(from.deproject([x, y], meters)))
+    };
+
+    for (index, &value) in depth.iter().enumerate() {
+        if value == 0 {
+            continue;
+        }
+        let (col, row) = ((index % from_width) as f32, (index / from_width) as f32);
+        let meters = value as f32 * units;
+
+        let [u0, v0] = project_corner(col - 0.5, row - 0.5, meters);
+        let [u1, v1] = project_corner(col + 0.5, row + 0.5, meters);
+        let columns = match covered_pixels(u0, u1, to_width) {
+            Some(columns) => columns,
+            None => continue,
+        };
+        let rows = match covered_pixels(v0, v1, to_height) {
+            Some(rows) => rows,
+            None => continue,
+        };
+
+        for y in rows {
+            for x in columns.clone() {
+                let target = &mut aligned[y * to_width + x];
+                if *target == 0 || value < *target {
+                    *target = value;
+                }
+            }
+        }
+    }
+    aligned
+}
+
+/// Gets the pixels along one axis, of an image `len` pixels long, whose centers lie in
+/// `[start, end)`, or the pixel nearest to the middle of that range if there are none.
+///
+/// Returns `None` if the pixels are outside of the image, or the range is not finite.
+fn covered_pixels(start: f32, end: f32, len: usize) -> Option<RangeInclusive<usize>> {
+    if !(start.is_finite() && end.is_finite()) {
+        return None;
+    }
+    let (mut first, mut last) = (start.ceil(), end.ceil() - 1.0);
+    if last < first {
+        first = ((start + end) / 2.0).round();
+        last = first;
+    }
+    if last < 0.0 || first >= len as f32 {
+        return None;
+    }
+    Some(first.max(0.0) as usize..=(last as usize).min(len - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kind::Rs2DistortionModel;
+    use realsense_sys as sys;
+
+    fn intrinsics(width: i32, height: i32, focal: f32) -> Rs2Intrinsics {
+        Rs2Intrinsics(sys::rs2_intrinsics {
+            width,
+            height,
+            ppx: (width as f32 - 1.0) / 2.0,
+            ppy: (height as f32 - 1.0) / 2.0,
+            fx: focal,
+            fy: focal,
+            model: Rs2DistortionModel::None as sys::rs2_distortion,
+            coeffs: [0.0; 5],
+        })
+    }
+
+    fn identity() -> Rs2Extrinsics {
+        Rs2Extrinsics(sys::rs2_extrinsics {
+            rotation: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+            translation: [0.0; 3],
+        })
+    }
+
+    // Depth units of 1/1024 m keep the reprojection in the tests exact.
+    const UNITS: f32 = 1.0 / 1024.0;
+
+    #[test]
+    fn aligning_to_the_same_viewport_preserves_depth() {
+        let depth: Vec<u16> = (0..8 * 6)
+            .map(|i| if i % 5 == 0 { 0 } else { 1000 + i })
+            .collect();
+
+        let aligned = align_depth(
+            &depth,
+            UNITS,
+            &intrinsics(8, 6, 8.0),
+            &identity(),
+            &intrinsics(8, 6, 8.0),
+        );
+
+        assert_eq!(aligned, depth);
+    }
+
+    #[test]
+    fn aligning_to_a_higher_resolution_leaves_no_holes() {
+        let depth: Vec<u16> = (0..4 * 4).map(|i| 1000 + i).collect();
+
+        let aligned = align_depth(
+            &depth,
+            UNITS,
+            &intrinsics(4, 4, 4.0),
+            &identity(),
+            &intrinsics(8, 8, 8.0),
+        );
+
+        for (index, &value) in aligned.iter().enumerate() {
+            let (col, row) = (index % 8, index / 8);
+            assert_eq!(value, depth[row / 2 * 4 + col / 2]);
+        }
+    }
+
+    #[test]
+    fn aligning_to_a_lower_resolution_keeps_the_closest_depth() {
+        let depth = vec![3000_u16, 2000, 1000, 4000];
+
+        let aligned = align_depth(
+            &depth,
+            UNITS,
+            &intrinsics(2, 2, 2.0),
+            &identity(),
+            &intrinsics(1, 1, 1.0),
+        );
+
+        assert_eq!(aligned, vec![1000]);
+    }
+
+    #[test]
+    fn translation_shifts_near_depth_further_than_far_depth() {
+        // Shifting by 0.5 m moves the pixel at 0.5 m by one pixel, and those at 2 m by a quarter
+        // pixel, so the near pixel leaves a hole and occludes its far neighbor.
+        let depth = vec![512_u16, 2048, 2048];
+        let extrinsics = Rs2Extrinsics(sys::rs2_extrinsics {
+            rotation: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+            translation: [0.5, 0.0, 0.0],
+        });
+
+        let aligned = align_depth(
+            &depth,
+            UNITS,
+            &intrinsics(3, 1, 1.0),
+            &extrinsics,
+            &intrinsics(3, 1, 1.0),
+        );
+
+        assert_eq!(aligned, vec![0, 512, 2048]);
+    }
+
+    #[test]
+    fn pixels_outside_of_the_target_are_dropped() {
+        assert_eq!(covered_pixels(-3.5, -1.5, 4), None);
+        assert_eq!(covered_pixels(4.5, 5.5, 4), None);
+        assert_eq!(covered_pixels(f32::NAN, 1.5, 4), None);
+        assert_eq!(covered_pixels(-1.5, 1.5, 4), Some(0..=1));
+        assert_eq!(covered_pixels(2.5, 6.5, 4), Some(3..=3));
+    }
+}
@@ -0,0 +1,82 @@
+//! Type for sharing the data of a frame without copying it.
+
+use realsense_sys as sys;
+use std::{fmt, ops::Deref, ptr::NonNull, slice};
+
+/// The raw data of a frame, which keeps the frame alive for as long as the data is in use.
+///
+/// This owns the reference to the librealsense2 frame that the data belongs to, and releases it
+/// when dropped. It dereferences to the data as a byte slice, including any padding at the end of
+/// each row, so the layout has to be interpreted according to the format, width and
+/// [stride](super::ImageFrame::stride) of the original frame.
+///
+/// Created with [`ImageFrame::into_arc_slice`](super::ImageFrame::into_arc_slice), which wraps it
+/// in an [`Arc`](std::sync::Arc) so that the data can be handed to several consumers, e.g. an
+/// encoder running on another thread, without copying it out of the frame.
+///
+/// # Frame pools
+///
+/// Like any other frame value, holding on to the data prevents librealsense2 from reusing the
+/// frame. Keeping many of them alive can therefore cause the stream to drop frames.
+pub struct FrameBytes {
+    /// The frame that the data belongs to, which is released on drop.
+    frame_ptr: NonNull<sys::rs2_frame>,
+    /// The data of the frame.
+    data: NonNull<u8>,
+    /// The size of the data in bytes.
+    len: usize,
+}
+
+impl FrameBytes {
+    /// Takes ownership of `frame_ptr` to share its data.
+    ///
+    /// # Safety
+    ///
+    /// The caller must own a reference to `frame_ptr`, and `data` must point to `len` bytes of
+    /// data owned by that frame.
+    pub(crate) unsafe fn new(
+        frame_ptr: NonNull<sys::rs2_frame>,
+        data: NonNull<u8>,
+        len: usize,
+    ) -> Self {
+        Self {
+            frame_ptr,
+            data,
+            len,
+        }
+    }
+}
+
+impl Deref for FrameBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.data.as_ptr(), self.len) }
+    }
+}
+
+impl AsRef<[u8]> for FrameBytes {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl fmt::Debug for FrameBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrameBytes")
+            .field("frame_ptr", &self.frame_ptr)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl Drop for FrameBytes {
+    fn drop(&mut self) {
+        unsafe {
+            sys::rs2_release_frame(self.frame_ptr.as_ptr());
+        }
+    }
+}
+
+unsafe impl Send for FrameBytes {}
+unsafe impl Sync for FrameBytes {}
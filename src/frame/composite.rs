@@ -6,13 +6,30 @@
 //!
 //! This is typically what is delivered from the pipeline.
 
-use super::prelude::FrameCategory;
-use crate::kind::Rs2StreamKind;
+use super::{
+    image::{ColorFrame, DepthFrame},
+    prelude::{frame_of_category, FrameCategory, FrameEx},
+};
+use crate::{
+    base::{Rs2Extrinsics, Rs2Intrinsics},
+    kind::Rs2StreamKind,
+};
+use anyhow::Result;
 use realsense_sys as sys;
 use std::{
-    convert::{TryFrom, TryInto},
-    ptr::NonNull,
+    convert::TryFrom, iter::FusedIterator, marker::PhantomData, mem::ManuallyDrop, ptr::NonNull,
 };
+use thiserror::Error;
+
+/// How far the intrinsics and extrinsics of two streams may differ for them to be aligned.
+///
+/// Aligned streams share their intrinsics and extrinsics exactly, so this only absorbs rounding.
+const ALIGNMENT_TOLERANCE: f32 = 1e-5;
+
+/// Occurs when a composite frame does not hold a frame of a stream that an operation needs.
+#[derive(Error, Debug)]
+#[error("Composite frame does not hold a frame of stream {0}.")]
+pub struct MissingFrameError(pub Rs2StreamKind);
 
 /// Holds the raw data pointer from an RS2 Composite frame type.
 #[derive(Debug)]
@@ -30,6 +47,9 @@ impl Drop for CompositeFrame {
     }
 }
 
+unsafe impl Send for CompositeFrame {}
+unsafe impl Sync for CompositeFrame {}
+
 impl From<NonNull<sys::rs2_frame>> for CompositeFrame {
     fn from(frame_ptr: NonNull<sys::rs2_frame>) -> Self {
         Self { ptr: frame_ptr }
@@ -73,47 +93,240 @@ impl CompositeFrame {
     where
         F: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory,
     {
-        let mut frames = Vec::new();
-        for i in 0..self.count() {
-            unsafe {
-                let mut err = std::ptr::null_mut::<sys::rs2_error>();
-                let frame_ptr =
-                    sys::rs2_extract_frame(self.ptr.as_ptr(), i as std::os::raw::c_int, &mut err);
-
-                if err.as_ref().is_some() {
-                    sys::rs2_free_error(err);
-                    continue;
-                }
-
-                let nonnull_frame_ptr = NonNull::new(frame_ptr).unwrap();
-
-                let is_extendable_to = sys::rs2_is_frame_extendable_to(
-                    nonnull_frame_ptr.as_ptr(),
-                    #[allow(clippy::useless_conversion)]
-                    (F::extension() as i32).try_into().unwrap(),
-                    &mut err,
-                );
-
-                if err.as_ref().is_none() {
-                    if is_extendable_to != 0 {
-                        if let Ok(f) = F::try_from(nonnull_frame_ptr) {
-                            let kind_for_frame = F::kind();
-
-                            if kind_for_frame == Rs2StreamKind::Any || f.has_correct_kind() {
-                                frames.push(f);
-                            }
-                            // This continue is to skip releasing the frame at the end of the loop.
-                            // If the call to try_from above is successful and we can push, then
-                            // the frame is owned by the type `E` and we should not release it.
-                            continue;
-                        }
-                    }
-                } else {
-                    sys::rs2_free_error(err);
-                }
-                sys::rs2_release_frame(nonnull_frame_ptr.as_ptr());
+        self.iter().collect()
+    }
+
+    /// Gets the first frame in the Composite frame collection of a given type, if any.
+    ///
+    /// This is the most convenient way to get at a stream that has a single frame in each
+    /// composite frame, e.g. the depth or color stream, without collecting every frame of the type
+    /// into a [`Vec`] like [`frames_of_type`](Self::frames_of_type) does.
+    ///
+    /// # Generic Arguments
+    ///
+    /// `F` must implement [`FrameCategory`](super::prelude::FrameCategory), see
+    /// [`frames_of_type`](Self::frames_of_type).
+    pub fn first_of_type<F>(&self) -> Option<F>
+    where
+        F: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory,
+    {
+        self.iter().next()
+    }
+
+    /// Iterates over all frames in the Composite frame collection of a given type.
+    ///
+    /// This is the lazy equivalent of [`frames_of_type`](Self::frames_of_type). The composite
+    /// frame is only borrowed, so it can be iterated over any number of times. Every frame yielded
+    /// holds its own reference to the underlying frame data, so frames can outlive the iterator
+    /// and be sent to other threads for processing.
+    ///
+    /// # Generic Arguments
+    ///
+    /// `F` must implement [`FrameCategory`](super::prelude::FrameCategory), see
+    /// [`frames_of_type`](Self::frames_of_type).
+    pub fn iter<F>(&self) -> CompositeFrameIter<'_, F>
+    where
+        F: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory,
+    {
+        CompositeFrameIter {
+            composite: self,
+            index: 0,
+            end: self.count(),
+            _phantom: PhantomData {},
+        }
+    }
+
+    /// Predicate for whether the depth and color frames of the composite frame are aligned.
+    ///
+    /// Aligned frames are co-registered: they have the same resolution and intrinsics, and the
+    /// extrinsics between them are the identity, so the same pixel in both frames sees the same
+    /// point. This is the case after the composite frame has been passed through an
+    /// [`Align`](crate::processing_blocks::Align) block, and is worth checking before overlaying
+    /// depth on color.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MissingFrameError`] if the composite frame does not hold both a depth and a
+    /// color frame.
+    ///
+    /// Returns [`DataError`](crate::stream_profile::DataError) if the intrinsics or extrinsics of
+    /// the streams cannot be retrieved.
+    pub fn are_aligned(&self) -> Result<bool> {
+        let depth = self
+            .first_of_type::<DepthFrame>()
+            .ok_or(MissingFrameError(Rs2StreamKind::Depth))?;
+        let color = self
+            .first_of_type::<ColorFrame>()
+            .ok_or(MissingFrameError(Rs2StreamKind::Color))?;
+
+        let depth_profile = depth.stream_profile();
+        let color_profile = color.stream_profile();
+        Ok(are_co_registered(
+            &depth_profile.intrinsics()?,
+            &color_profile.intrinsics()?,
+            &depth_profile.extrinsics(color_profile)?,
+        ))
+    }
+
+    /// Converts the composite frame into its raw pointer, transferring ownership of the frame to
+    /// the caller.
+    ///
+    /// # Safety
+    ///
+    /// The caller is responsible for releasing the returned frame, e.g. by passing it on to a
+    /// function of librealsense2 that takes ownership of it.
+    pub(crate) unsafe fn into_raw(self) -> NonNull<sys::rs2_frame> {
+        ManuallyDrop::new(self).ptr
+    }
+
+    /// Gets the frame at `index` in the Composite frame collection, if it is of type `F`.
+    fn frame_at<F>(&self, index: usize) -> Option<F>
+    where
+        F: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory,
+    {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let frame_ptr =
+                sys::rs2_extract_frame(self.ptr.as_ptr(), index as std::os::raw::c_int, &mut err);
+
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+                return None;
+            }
+
+            // librealsense2 adds a reference to the extracted frame, which is owned from here on.
+            // The frame therefore stays valid after the composite frame is released.
+            frame_of_category(NonNull::new(frame_ptr).unwrap())
+        }
+    }
+}
+
+/// Iterator over the frames of a given type in a [`CompositeFrame`].
+///
+/// Constructed with [`CompositeFrame::iter`]. Frames can be taken from both ends, e.g. to iterate
+/// in reverse with [`rev`](Iterator::rev), and every frame is yielded at most once. Since frames of
+/// other types are skipped, the number of remaining frames is only known once they have been
+/// checked, so the [size hint](Iterator::size_hint) only gives an upper bound.
+#[derive(Debug)]
+pub struct CompositeFrameIter<'a, F> {
+    /// The composite frame being iterated over.
+    composite: &'a CompositeFrame,
+    /// The index of the next frame to check from the front.
+    index: usize,
+    /// One past the index of the next frame to check from the back.
+    end: usize,
+    /// Holds the type of the frames being iterated over.
+    _phantom: PhantomData<F>,
+}
+
+impl<'a, F> Iterator for CompositeFrameIter<'a, F>
+where
+    F: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory,
+{
+    type Item = F;
+
+    fn next(&mut self) -> Option<F> {
+        while self.index < self.end {
+            let index = self.index;
+            self.index += 1;
+
+            if let Some(frame) = self.composite.frame_at(index) {
+                return Some(frame);
             }
         }
-        frames
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end - self.index))
+    }
+}
+
+impl<'a, F> DoubleEndedIterator for CompositeFrameIter<'a, F>
+where
+    F: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory,
+{
+    fn next_back(&mut self) -> Option<F> {
+        while self.index < self.end {
+            self.end -= 1;
+
+            if let Some(frame) = self.composite.frame_at(self.end) {
+                return Some(frame);
+            }
+        }
+        None
+    }
+}
+
+impl<'a, F> FusedIterator for CompositeFrameIter<'a, F> where
+    F: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory
+{
+}
+
+/// Predicate for whether two streams with intrinsics `from` and `to`, and extrinsics `extrinsics`
+/// between them, are co-registered.
+fn are_co_registered(from: &Rs2Intrinsics, to: &Rs2Intrinsics, extrinsics: &Rs2Extrinsics) -> bool {
+    let close = |a: f32, b: f32| (a - b).abs() <= ALIGNMENT_TOLERANCE;
+
+    from.width() == to.width()
+        && from.height() == to.height()
+        && close(from.fx(), to.fx())
+        && close(from.fy(), to.fy())
+        && close(from.ppx(), to.ppx())
+        && close(from.ppy(), to.ppy())
+        && extrinsics.is_identity(ALIGNMENT_TOLERANCE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intrinsics(width: i32, height: i32, fx: f32) -> Rs2Intrinsics {
+        Rs2Intrinsics(sys::rs2_intrinsics {
+            width,
+            height,
+            ppx: width as f32 / 2.0,
+            ppy: height as f32 / 2.0,
+            fx,
+            fy: fx,
+            model: sys::rs2_distortion_RS2_DISTORTION_NONE,
+            coeffs: [0.0; 5],
+        })
+    }
+
+    fn extrinsics(translation: [f32; 3]) -> Rs2Extrinsics {
+        Rs2Extrinsics(sys::rs2_extrinsics {
+            rotation: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+            translation,
+        })
+    }
+
+    #[test]
+    fn identical_streams_are_co_registered() {
+        assert!(are_co_registered(
+            &intrinsics(640, 480, 615.0),
+            &intrinsics(640, 480, 615.0),
+            &extrinsics([0.0; 3]),
+        ));
+    }
+
+    #[test]
+    fn unaligned_depth_and_color_are_not_co_registered() {
+        // Typical D400 depth and color streams: different resolutions and focal lengths, with the
+        // color imager 15mm to the side of the depth origin.
+        let depth = intrinsics(848, 480, 425.0);
+        let color = intrinsics(640, 480, 615.0);
+
+        assert!(!are_co_registered(
+            &depth,
+            &color,
+            &extrinsics([0.015, 0.0, 0.0])
+        ));
+        assert!(!are_co_registered(
+            &color,
+            &color,
+            &extrinsics([0.015, 0.0, 0.0])
+        ));
+        assert!(!are_co_registered(&depth, &color, &extrinsics([0.0; 3])));
     }
 }
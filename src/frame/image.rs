@@ -9,25 +9,39 @@
 //! Each frame type can hold data in multiple formats. The data type presented
 //! depends on the settings and flags used at runtime on the RealSense device.
 
+use super::aligned_depth::{align_depth, AlignedDepth};
+use super::bytes::FrameBytes;
 use super::pixel::{get_pixel, PixelKind};
 use super::prelude::{
-    CouldNotGetFrameSensorError, DepthError, DisparityError, FrameCategory, FrameConstructionError,
-    FrameEx, BITS_PER_BYTE,
+    write_summary, CouldNotGetFrameSensorError, DepthError, DisparityError, FormatError,
+    FrameCategory, FrameConstructionError, FrameDataError, FrameEx, FrameSizeMismatch,
+    MetadataError, SharedFrameError, BITS_PER_BYTE,
 };
 use crate::{
+    base::Rs2Intrinsics,
     check_rs2_error,
-    kind::{Rs2Extension, Rs2FrameMetadata, Rs2Option, Rs2StreamKind, Rs2TimestampDomain},
+    kind::{
+        Rs2Extension, Rs2Format, Rs2FrameMetadata, Rs2Option, Rs2StreamKind, Rs2TimestampDomain,
+    },
     sensor::Sensor,
     stream_profile::StreamProfile,
 };
 use anyhow::Result;
+#[cfg(feature = "image")]
+use image::{DynamicImage, ImageBuffer};
+#[cfg(feature = "ndarray")]
+use ndarray::Array2;
 use num_traits::FromPrimitive;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 use realsense_sys as sys;
 use std::{
     convert::{TryFrom, TryInto},
+    fmt,
     marker::PhantomData,
     os::raw::c_int,
     ptr::{self, NonNull},
+    sync::Arc,
 };
 
 /// A unit struct defining a Depth frame.
@@ -80,6 +94,8 @@ pub struct ImageFrame<Kind> {
     /// A boolean used during `Drop` calls. This allows for proper handling of the pointer
     /// during ownership transfer.
     should_drop: bool,
+    /// Whether this value holds the only reference to the frame, so that its data can be written.
+    is_exclusive: bool,
     /// Holds the type metadata of this frame.
     _phantom: PhantomData<Kind>,
 }
@@ -173,7 +189,24 @@ impl<'a, K> IntoIterator for &'a ImageFrame<K> {
     }
 }
 
+impl<K> fmt::Display for ImageFrame<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_summary(
+            f,
+            self,
+            format_args!(
+                "{:?} {}x{}, {} bytes",
+                self.frame_stream_profile.format(),
+                self.width,
+                self.height,
+                self.data_size_in_bytes
+            ),
+        )
+    }
+}
+
 unsafe impl<K> Send for ImageFrame<K> {}
+unsafe impl<K> Sync for ImageFrame<K> {}
 
 impl<K> TryFrom<NonNull<sys::rs2_frame>> for ImageFrame<K> {
     type Error = anyhow::Error;
@@ -253,6 +286,7 @@ impl<K> TryFrom<NonNull<sys::rs2_frame>> for ImageFrame<K> {
                 data_size_in_bytes: size as usize,
                 data: nonnull_data_ptr,
                 should_drop: true,
+                is_exclusive: false,
                 _phantom: PhantomData::<K> {},
             })
         }
@@ -370,27 +404,34 @@ impl<T> FrameEx for ImageFrame<T> {
         self.frame_number
     }
 
-    fn metadata(&self, metadata_kind: Rs2FrameMetadata) -> Option<std::os::raw::c_longlong> {
-        if !self.supports_metadata(metadata_kind) {
-            return None;
-        }
-
+    fn try_metadata(
+        &self,
+        metadata_kind: Rs2FrameMetadata,
+    ) -> Result<Option<std::os::raw::c_longlong>, MetadataError> {
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
 
-            let val = sys::rs2_get_frame_metadata(
+            let supports_metadata = sys::rs2_supports_frame_metadata(
                 self.frame_ptr.as_ptr(),
                 #[allow(clippy::useless_conversion)]
                 (metadata_kind as i32).try_into().unwrap(),
                 &mut err,
             );
+            check_rs2_error!(err, MetadataError)?;
 
-            if err.as_ref().is_none() {
-                Some(val)
-            } else {
-                sys::rs2_free_error(err);
-                None
+            if supports_metadata == 0 {
+                return Ok(None);
             }
+
+            let val = sys::rs2_get_frame_metadata(
+                self.frame_ptr.as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (metadata_kind as i32).try_into().unwrap(),
+                &mut err,
+            );
+            check_rs2_error!(err, MetadataError)?;
+
+            Ok(Some(val))
         }
     }
 
@@ -428,9 +469,24 @@ impl DepthFrame {
     ///
     /// It is fairly expensive to use this in practice as it will copy the underlying pixel into a
     /// f32 value that gives you the direct distance. In practice getting
-    /// `DepthFrame::depth_units` and then applying that to the raw data with [`ImageFrame::get`]
+    /// [`DepthFrame::units`] and then applying that to the raw data with [`ImageFrame::get`]
     /// is a much more efficient way to handle this.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DepthError::PixelOutOfBounds`] if `(col, row)` is outside of the frame.
+    ///
+    /// Returns [`DepthError::CouldNotGetDistance`] if the distance cannot be retrieved.
     pub fn distance(&self, col: usize, row: usize) -> Result<f32, DepthError> {
+        if col >= self.width || row >= self.height {
+            return Err(DepthError::PixelOutOfBounds {
+                col,
+                row,
+                width: self.width,
+                height: self.height,
+            });
+        }
+
         unsafe {
             let mut err = ptr::null_mut::<sys::rs2_error>();
             let distance = sys::rs2_depth_frame_get_distance(
@@ -444,7 +500,30 @@ impl DepthFrame {
         }
     }
 
+    /// Get the metric units in which the depth of this frame is reported.
+    ///
+    /// This is the number of meters per unit of raw depth, as it was when the frame was captured.
+    /// Unlike [`depth_units`](Self::depth_units), it does not depend on the current state of the
+    /// sensor, so it stays correct if the [`Rs2Option::DepthUnits`] option changes while frames
+    /// are in flight, and for frames read from a recording or output by a processing block.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DepthError::CouldNotGetDepthUnits`] if the depth units cannot be retrieved.
+    pub fn units(&self) -> Result<f32, DepthError> {
+        unsafe {
+            let mut err = ptr::null_mut::<sys::rs2_error>();
+            let units = sys::rs2_depth_frame_get_units(self.frame_ptr.as_ptr(), &mut err);
+            check_rs2_error!(err, DepthError::CouldNotGetDepthUnits)?;
+            Ok(units)
+        }
+    }
+
     /// Get the metric units currently used for reporting depth information.
+    ///
+    /// This reads the [`Rs2Option::DepthUnits`] option of the sensor that produced the frame, so
+    /// it reflects the current setting rather than the setting the frame was captured with. Prefer
+    /// [`units`](Self::units) to scale the depth of this frame.
     pub fn depth_units(&self) -> Result<f32> {
         let sensor = self.sensor()?;
         let depth_units = sensor.get_option(Rs2Option::DepthUnits).ok_or_else(|| {
@@ -452,6 +531,185 @@ impl DepthFrame {
         })?;
         Ok(depth_units)
     }
+
+    /// Deprojects every pixel with a valid depth to a 3D point in meters, on the CPU.
+    ///
+    /// This is a pure-Rust alternative to the [`PointCloud`](crate::processing_blocks::PointCloud)
+    /// processing block, using [`Rs2Intrinsics::deproject`] for every pixel. Unlike the processing
+    /// block, pixels without a valid depth (i.e. a depth of zero) are skipped rather than being
+    /// deprojected to the origin, so the result holds one point per valid pixel, in row-major
+    /// order.
+    ///
+    /// `intrinsics` should be the intrinsics of the depth stream, e.g. from the
+    /// [stream profile](FrameEx::stream_profile) of this frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameDataError::UnsupportedFormat`] if the frame does not hold
+    /// [`Rs2Format::Z16`] data.
+    ///
+    /// Returns [`FrameDataError::SizeMismatch`] if the frame does not hold enough data for its
+    /// resolution.
+    ///
+    /// Returns [`DepthError::CouldNotGetDepthUnits`] if the [depth units](Self::units) cannot be
+    /// retrieved.
+    pub fn deproject_to_points(&self, intrinsics: &Rs2Intrinsics) -> Result<Vec<[f32; 3]>> {
+        let data = self.checked_data(&[Rs2Format::Z16])?;
+        let depth_units = self.units()?;
+
+        let mut points = Vec::new();
+        for (row, row_data) in data.chunks(self.stride).take(self.height).enumerate() {
+            points.extend(deproject_row(
+                row,
+                z16_row(row_data, self.width),
+                depth_units,
+                intrinsics,
+            ));
+        }
+        Ok(points)
+    }
+
+    /// Deprojects every pixel with a valid depth to a 3D point in meters, on multiple threads.
+    ///
+    /// This gives the same result as [`deproject_to_points`](Self::deproject_to_points), but
+    /// splits the rows of the frame across the threads of the global `rayon` thread pool, which
+    /// speeds it up considerably for high resolutions.
+    ///
+    /// This requires the `rayon` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`deproject_to_points`](Self::deproject_to_points).
+    #[cfg(feature = "rayon")]
+    pub fn par_deproject_to_points(&self, intrinsics: &Rs2Intrinsics) -> Result<Vec<[f32; 3]>> {
+        let data = self.checked_data(&[Rs2Format::Z16])?;
+        let depth_units = self.units()?;
+
+        Ok(par_deproject_rows(
+            data,
+            self.width,
+            self.height,
+            self.stride,
+            depth_units,
+            intrinsics,
+        ))
+    }
+
+    /// Aligns the depth of this frame to the viewport of another stream, on the CPU.
+    ///
+    /// This is a pure-Rust alternative to the [`Align`](crate::processing_blocks::Align)
+    /// processing block, for aligning a single depth frame to e.g. the color stream without a
+    /// frameset. Every pixel with a valid depth is deprojected with the intrinsics of this frame,
+    /// transformed by the extrinsics from this frame to `to_profile`, and projected with the
+    /// intrinsics of `to_profile`. The corners of each depth pixel are projected, so that the
+    /// depth fills every target pixel it covers, and the closest depth wins where pixels overlap.
+    /// Target pixels that no depth pixel maps to have a depth of zero.
+    ///
+    /// The result has the resolution of `to_profile`, and holds the raw depth in the
+    /// [units](Self::units) of this frame. Like the processing block, this is a nearest-neighbor
+    /// mapping, so depth is never interpolated.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameDataError::UnsupportedFormat`] if the frame does not hold
+    /// [`Rs2Format::Z16`] data.
+    ///
+    /// Returns [`FrameDataError::SizeMismatch`] if the frame does not hold enough data for its
+    /// resolution.
+    ///
+    /// Returns [`DepthError::CouldNotGetDepthUnits`] if the [depth units](Self::units) cannot be
+    /// retrieved.
+    ///
+    /// Returns [`DataError`](crate::stream_profile::DataError) if the intrinsics of either stream
+    /// or the extrinsics between them cannot be retrieved, e.g. because `to_profile` is not a
+    /// video stream.
+    pub fn align_to(&self, to_profile: &StreamProfile) -> Result<AlignedDepth> {
+        let depth: Vec<u16> = self.raw_depths()?.collect();
+        let depth_units = self.units()?;
+        let from_intrinsics = self.frame_stream_profile.intrinsics()?;
+        let to_intrinsics = to_profile.intrinsics()?;
+        let extrinsics = self.frame_stream_profile.extrinsics(to_profile)?;
+
+        let aligned = align_depth(
+            &depth,
+            depth_units,
+            &from_intrinsics,
+            &extrinsics,
+            &to_intrinsics,
+        );
+        Ok(AlignedDepth::new(aligned, depth_units, to_intrinsics))
+    }
+
+    /// Counts the pixels with a valid, i.e. non-zero, depth in each of `bins` equally sized ranges
+    /// of raw depth.
+    ///
+    /// The bins evenly split the whole range of raw depth values, `1..=65535`, like the histogram
+    /// that librealsense2 uses for histogram equalization in its colorizer. Bin `i` thus holds the
+    /// pixels whose raw depth `d` satisfies `(d - 1) * bins / 65535 == i`. Pixels with unknown
+    /// depth are not counted, so the bins sum to the number of valid pixels. This is useful for
+    /// e.g. auto-ranging a colorizer, or telling whether a scene is mostly within range.
+    ///
+    /// Returns an empty histogram if `bins` is zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameDataError::UnsupportedFormat`] if the frame does not hold
+    /// [`Rs2Format::Z16`] data.
+    ///
+    /// Returns [`FrameDataError::SizeMismatch`] if the frame does not hold enough data for its
+    /// resolution.
+    pub fn histogram(&self, bins: usize) -> Result<Vec<u32>, FrameDataError> {
+        Ok(depth_histogram(self.raw_depths()?, bins))
+    }
+
+    /// Gets the fraction of pixels in the frame with a valid, i.e. non-zero, depth.
+    ///
+    /// A low fraction means that much of the scene is out of range, or lacks texture for stereo
+    /// matching. Returns zero for an empty frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameDataError::UnsupportedFormat`] if the frame does not hold
+    /// [`Rs2Format::Z16`] data.
+    ///
+    /// Returns [`FrameDataError::SizeMismatch`] if the frame does not hold enough data for its
+    /// resolution.
+    pub fn valid_pixel_fraction(&self) -> Result<f32, FrameDataError> {
+        let valid = self.raw_depths()?.filter(|&depth| depth != 0).count();
+        let total = self.width * self.height;
+        Ok(if total == 0 {
+            0.0
+        } else {
+            valid as f32 / total as f32
+        })
+    }
+
+    /// Iterates over the raw depth values of the frame in row-major order, skipping any padding
+    /// at the end of each row.
+    fn raw_depths(&self) -> Result<impl Iterator<Item = u16> + '_, FrameDataError> {
+        let data = self.checked_data(&[Rs2Format::Z16])?;
+        Ok(z16_rows(data, self.width, self.height, self.stride))
+    }
+
+    /// Copies the raw depth values of the frame into a `height` x `width` array.
+    ///
+    /// The values are in [depth units](Self::depth_units), not in meters. Any padding at the end
+    /// of each row of the frame is skipped.
+    ///
+    /// This requires the `ndarray` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameDataError::UnsupportedFormat`] if the frame does not hold
+    /// [`Rs2Format::Z16`] data.
+    ///
+    /// Returns [`FrameDataError::SizeMismatch`] if the frame does not hold enough data for its
+    /// resolution.
+    #[cfg(feature = "ndarray")]
+    pub fn to_array2(&self) -> Result<Array2<u16>, FrameDataError> {
+        let depths = self.raw_depths()?.collect();
+        Ok(Array2::from_shape_vec((self.height, self.width), depths).unwrap())
+    }
 }
 
 impl DisparityFrame {
@@ -466,7 +724,22 @@ impl DisparityFrame {
     /// It is often much more efficient to directly stream the
     /// [`Rs2Format::Distance`](crate::kind::Rs2Format::Distance) format if you want the distance
     /// directly, and access the frame data with [`ImageFrame::get`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DepthError::PixelOutOfBounds`] if `(col, row)` is outside of the frame.
+    ///
+    /// Returns [`DepthError::CouldNotGetDistance`] if the distance cannot be retrieved.
     pub fn distance(&self, col: usize, row: usize) -> Result<f32, DepthError> {
+        if col >= self.width || row >= self.height {
+            return Err(DepthError::PixelOutOfBounds {
+                col,
+                row,
+                width: self.width,
+                height: self.height,
+            });
+        }
+
         unsafe {
             let mut err = ptr::null_mut::<sys::rs2_error>();
             let distance = sys::rs2_depth_frame_get_distance(
@@ -480,7 +753,30 @@ impl DisparityFrame {
         }
     }
 
+    /// Get the metric units in which the depth of this frame is reported.
+    ///
+    /// This is the number of meters per unit of raw depth, as it was when the frame was captured.
+    /// Unlike [`depth_units`](Self::depth_units), it does not depend on the current state of the
+    /// sensor, so it stays correct if the [`Rs2Option::DepthUnits`] option changes while frames
+    /// are in flight, and for frames read from a recording or output by a processing block.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DepthError::CouldNotGetDepthUnits`] if the depth units cannot be retrieved.
+    pub fn units(&self) -> Result<f32, DepthError> {
+        unsafe {
+            let mut err = ptr::null_mut::<sys::rs2_error>();
+            let units = sys::rs2_depth_frame_get_units(self.frame_ptr.as_ptr(), &mut err);
+            check_rs2_error!(err, DepthError::CouldNotGetDepthUnits)?;
+            Ok(units)
+        }
+    }
+
     /// Get the metric units currently used for reporting depth information.
+    ///
+    /// This reads the [`Rs2Option::DepthUnits`] option of the sensor that produced the frame, so
+    /// it reflects the current setting rather than the setting the frame was captured with. Prefer
+    /// [`units`](Self::units) to scale the depth of this frame.
     pub fn depth_units(&self) -> Result<f32> {
         let sensor = self.sensor()?;
         let depth_units = sensor.get_option(Rs2Option::DepthUnits).ok_or_else(|| {
@@ -558,6 +854,63 @@ impl<K> ImageFrame<K> {
         self.data.as_ref()
     }
 
+    /// Get a mutable reference to the raw data held by this Video frame, e.g. to filter the frame
+    /// in place.
+    ///
+    /// The slice covers the whole frame, including any padding at the end of each row. As with
+    /// [`get_data`](Self::get_data), the data has to be interpreted according to the format of the
+    /// frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SharedFrameError`] unless this value is known to hold the only reference to the
+    /// frame. librealsense2 reference counts frames without exposing the count, so this is only
+    /// the case for frames [allocated](crate::processing_blocks::FramePool::allocate_video_frame)
+    /// from the pool of a processing block. Frames from a pipeline or a [`CompositeFrame`] may be
+    /// read through another reference at any time, e.g. by the composite frame on another thread.
+    ///
+    /// [`CompositeFrame`]: crate::frame::CompositeFrame
+    pub fn data_mut(&mut self) -> Result<&mut [u8], SharedFrameError> {
+        if !self.is_exclusive {
+            return Err(SharedFrameError);
+        }
+        unsafe {
+            Ok(std::slice::from_raw_parts_mut(
+                self.data.as_ptr().cast::<u8>(),
+                self.data_size_in_bytes,
+            ))
+        }
+    }
+
+    /// Marks the frame as the only reference to its data, so that it can be written through
+    /// [`data_mut`](Self::data_mut).
+    ///
+    /// # Safety
+    ///
+    /// No other reference to the frame may exist, in librealsense2 or in this crate.
+    pub(crate) unsafe fn assume_exclusive(mut self) -> Self {
+        self.is_exclusive = true;
+        self
+    }
+
+    /// Converts the frame into its raw data, which can be shared without copying it.
+    ///
+    /// The returned [`FrameBytes`] takes over the reference to the frame, so the data remains
+    /// valid until the last clone of the `Arc` is dropped, regardless of the original frame value.
+    /// This is much cheaper than copying the data of every frame into an owned buffer, e.g. to
+    /// send it to a recording thread over a channel.
+    ///
+    /// As with [`get_data`](Self::get_data), the data covers the whole frame, including any
+    /// padding at the end of each row, and has to be interpreted according to the format of the
+    /// frame.
+    pub fn into_arc_slice(self) -> Arc<FrameBytes> {
+        let (data, len) = (self.data.cast::<u8>(), self.data_size_in_bytes);
+        unsafe {
+            let frame_ptr = self.get_owned_raw();
+            Arc::new(FrameBytes::new(frame_ptr, data, len))
+        }
+    }
+
     /// Get the width of this Video frame in pixels
     pub fn width(&self) -> usize {
         self.width
@@ -568,6 +921,29 @@ impl<K> ImageFrame<K> {
         self.height
     }
 
+    /// Predicate for whether the frame belongs to a color stream.
+    pub fn is_color(&self) -> bool {
+        self.frame_stream_profile.kind() == Rs2StreamKind::Color
+    }
+
+    /// Predicate for whether the frame belongs to an infrared stream.
+    pub fn is_infrared(&self) -> bool {
+        self.frame_stream_profile.kind() == Rs2StreamKind::Infrared
+    }
+
+    /// Gets the index of the infrared stream that the frame belongs to.
+    ///
+    /// Devices with several infrared imagers number their streams starting from 1, e.g. on the
+    /// D400 series the left imager is 1 and the right imager is 2. This tells apart the infrared
+    /// frames of a composite frame. Returns `None` if the frame is not an infrared frame.
+    pub fn infrared_index(&self) -> Option<usize> {
+        if self.is_infrared() {
+            Some(self.frame_stream_profile.index())
+        } else {
+            None
+        }
+    }
+
     /// Given a row and column index, Get a pixel value from this frame.
     pub fn get(&self, col: usize, row: usize) -> Option<PixelKind<'_>> {
         if col >= self.width || row >= self.height {
@@ -576,6 +952,421 @@ impl<K> ImageFrame<K> {
             Some(self.get_unchecked(col, row))
         }
     }
+
+    /// Get the data of a frame holding 32-bit floating point values as a slice.
+    ///
+    /// The following formats are supported:
+    ///
+    /// * [`Rs2Format::Distance`], e.g. the output of a
+    ///   [`UnitsTransform`](crate::processing_blocks::UnitsTransform), with one value per pixel.
+    /// * [`Rs2Format::Disparity32`], with one value per pixel.
+    /// * [`Rs2Format::Xyz32F`], with three values per pixel.
+    ///
+    /// The slice covers the whole frame, including any padding at the end of each row. Rows start
+    /// every [`stride`](Self::stride) bytes, so the value of the pixel at `(col, row)` in a
+    /// single-valued format is at index `row * stride / 4 + col`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameDataError::UnsupportedFormat`] if the format of the frame is not one of the
+    /// above.
+    ///
+    /// Returns [`FrameDataError::SizeMismatch`] if the frame does not hold enough data for its
+    /// resolution.
+    pub fn as_slice_f32(&self) -> Result<&[f32], FrameDataError> {
+        let data = self.checked_data(&[
+            Rs2Format::Distance,
+            Rs2Format::Disparity32,
+            Rs2Format::Xyz32F,
+        ])?;
+        let len = data.len() / std::mem::size_of::<f32>();
+        // librealsense2 allocates frame data on the heap, so it is suitably aligned for any
+        // primitive type.
+        debug_assert_eq!(data.as_ptr() as usize % std::mem::align_of::<f32>(), 0);
+        Ok(unsafe { std::slice::from_raw_parts(data.as_ptr().cast::<f32>(), len) })
+    }
+
+    /// Copies the frame into an image from the `image` crate, e.g. to save it to a file.
+    ///
+    /// The following formats are supported:
+    ///
+    /// * [`Rs2Format::Rgb8`] and [`Rs2Format::Bgr8`] are converted to
+    ///   [`DynamicImage::ImageRgb8`], swapping the channels of BGR8 frames.
+    /// * [`Rs2Format::Y8`] is converted to [`DynamicImage::ImageLuma8`].
+    /// * [`Rs2Format::Y16`] is converted to [`DynamicImage::ImageLuma16`].
+    ///
+    /// This requires the `image` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameDataError::UnsupportedFormat`] if the format of the frame is not one of the
+    /// above. Other formats, such as YUYV, need to be converted by hand.
+    ///
+    /// Returns [`FrameDataError::SizeMismatch`] if the frame does not hold enough data for its
+    /// resolution.
+    #[cfg(feature = "image")]
+    pub fn to_image(&self) -> Result<DynamicImage, FrameDataError> {
+        let format = self.frame_stream_profile.format();
+        let width = self.width as u32;
+        let height = self.height as u32;
+
+        self.checked_data(&[
+            Rs2Format::Rgb8,
+            Rs2Format::Bgr8,
+            Rs2Format::Y8,
+            Rs2Format::Y16,
+        ])?;
+
+        match format {
+            Rs2Format::Rgb8 => {
+                let buffer = self.packed_rows().collect();
+                Ok(DynamicImage::ImageRgb8(
+                    ImageBuffer::from_raw(width, height, buffer).unwrap(),
+                ))
+            }
+            Rs2Format::Bgr8 => {
+                let mut buffer: Vec<u8> = self.packed_rows().collect();
+                for pixel in buffer.chunks_exact_mut(3) {
+                    pixel.swap(0, 2);
+                }
+                Ok(DynamicImage::ImageRgb8(
+                    ImageBuffer::from_raw(width, height, buffer).unwrap(),
+                ))
+            }
+            Rs2Format::Y8 => {
+                let buffer = self.packed_rows().collect();
+                Ok(DynamicImage::ImageLuma8(
+                    ImageBuffer::from_raw(width, height, buffer).unwrap(),
+                ))
+            }
+            Rs2Format::Y16 => {
+                let bytes: Vec<u8> = self.packed_rows().collect();
+                let buffer = bytes
+                    .chunks_exact(2)
+                    .map(|b| u16::from_ne_bytes([b[0], b[1]]))
+                    .collect();
+                Ok(DynamicImage::ImageLuma16(
+                    ImageBuffer::from_raw(width, height, buffer).unwrap(),
+                ))
+            }
+            _ => unreachable!("The format of the frame was checked above."),
+        }
+    }
+
+    /// Unpacks the bit-packed samples of a [`Rs2Format::Raw10`] frame, e.g. raw Bayer data.
+    ///
+    /// RAW10 data packs four 10-bit samples into every five bytes, so it cannot be read one pixel
+    /// at a time like other formats. This expands it to one sample per pixel, in row-major order,
+    /// with the value in the 10 least significant bits. Any padding at the end of each row is
+    /// skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameDataError::UnsupportedFormat`] if the frame does not hold
+    /// [`Rs2Format::Raw10`] data.
+    ///
+    /// Returns [`FrameDataError::SizeMismatch`] if the frame does not hold enough data for its
+    /// resolution.
+    pub fn unpack_raw10(&self) -> Result<Vec<u16>, FrameDataError> {
+        let data = self.checked_data(&[Rs2Format::Raw10])?;
+        Ok(unpack_raw10_rows(
+            data,
+            self.width,
+            self.height,
+            self.stride,
+        ))
+    }
+
+    /// Copies the samples of a [`Rs2Format::Raw16`] frame, e.g. raw Bayer data, one per pixel.
+    ///
+    /// The samples are in row-major order, and any padding at the end of each row is skipped. This
+    /// gives the same layout as [`unpack_raw10`](Self::unpack_raw10), so raw frames can be
+    /// processed the same way regardless of their bit depth.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameDataError::UnsupportedFormat`] if the frame does not hold
+    /// [`Rs2Format::Raw16`] data.
+    ///
+    /// Returns [`FrameDataError::SizeMismatch`] if the frame does not hold enough data for its
+    /// resolution.
+    pub fn unpack_raw16(&self) -> Result<Vec<u16>, FrameDataError> {
+        let data = self.checked_data(&[Rs2Format::Raw16])?;
+        Ok(unpack_raw16_rows(
+            data,
+            self.width,
+            self.height,
+            self.stride,
+        ))
+    }
+
+    /// Checks that the frame holds enough data for its resolution and the format of its stream.
+    ///
+    /// The typed accessors of the frame, such as [`as_slice_f32`](Self::as_slice_f32), interpret
+    /// the data according to the stream profile, and perform this check before doing so. It can
+    /// also be called directly to catch misconfigured pipelines early, e.g. on the first frame.
+    ///
+    /// Frames of formats without a fixed size per pixel, such as compressed formats, always pass.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameSizeMismatch`] if the frame holds less data than its profile requires.
+    pub fn validate_data_size(&self) -> Result<(), FrameSizeMismatch> {
+        validate_data_size(
+            self.frame_stream_profile.format(),
+            self.width,
+            self.height,
+            self.stride,
+            self.data_size_in_bytes,
+        )
+    }
+
+    /// Gets the data of the frame, after checking that it holds one of `formats` and enough data
+    /// for its resolution.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameDataError::UnsupportedFormat`] if the format of the frame is not one of
+    /// `formats`.
+    ///
+    /// Returns [`FrameDataError::SizeMismatch`] if the frame does not hold enough data for its
+    /// resolution.
+    fn checked_data(&self, formats: &[Rs2Format]) -> Result<&[u8], FrameDataError> {
+        let format = self.frame_stream_profile.format();
+        if !formats.contains(&format) {
+            return Err(FrameDataError::UnsupportedFormat(FormatError(format)));
+        }
+        self.validate_data_size()
+            .map_err(FrameDataError::SizeMismatch)?;
+
+        Ok(unsafe {
+            std::slice::from_raw_parts(self.data.as_ptr().cast::<u8>(), self.data_size_in_bytes)
+        })
+    }
+
+    /// Computes a fast, non-cryptographic hash of the data of the frame.
+    ///
+    /// Frames with the same pixels have the same hash, so this can be used to e.g. skip recording
+    /// duplicate frames, or to detect a stalled stream that keeps delivering the same frame. Any
+    /// padding at the end of each row is skipped, since it does not hold pixel data. The hash is
+    /// computed with XXH3, and is not stable across formats or resolutions.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameSizeMismatch`] if the frame does not hold enough data for its resolution.
+    #[cfg(feature = "content-hash")]
+    pub fn content_hash(&self) -> Result<u64, FrameSizeMismatch> {
+        self.validate_data_size()?;
+
+        let data = unsafe {
+            std::slice::from_raw_parts(self.data.as_ptr().cast::<u8>(), self.data_size_in_bytes)
+        };
+        Ok(hash_rows(
+            data,
+            row_size(self.frame_stream_profile.format(), self.width),
+            self.height,
+            self.stride,
+        ))
+    }
+
+    /// Get the underlying low-level pointer to the frame.
+    ///
+    /// # Safety
+    ///
+    /// This method is not intended to be called or used outside of the crate itself. Be warned, it
+    /// is _undefined behaviour_ to release this pointer in any context. If you do, you risk a
+    /// double-free or use-after-free error when the frame itself is dropped.
+    pub(crate) unsafe fn get_raw(&self) -> NonNull<sys::rs2_frame> {
+        self.frame_ptr
+    }
+
+    /// Iterates over the bytes of the frame data, skipping any padding at the end of each row.
+    #[cfg(feature = "image")]
+    fn packed_rows(&self) -> impl Iterator<Item = u8> + '_ {
+        let row_size = self.width * self.bits_per_pixel / BITS_PER_BYTE as usize;
+        let data = unsafe {
+            std::slice::from_raw_parts(self.data.as_ptr().cast::<u8>(), self.data_size_in_bytes)
+        };
+
+        data.chunks(self.stride)
+            .take(self.height)
+            .flat_map(move |row| row[..row_size].iter().copied())
+    }
+}
+
+/// Deprojects the pixels with a valid depth in a row of raw depths, from left to right.
+fn deproject_row<'a>(
+    row: usize,
+    depths: impl Iterator<Item = u16> + 'a,
+    depth_units: f32,
+    intrinsics: &'a Rs2Intrinsics,
+) -> impl Iterator<Item = [f32; 3]> + 'a {
+    depths.enumerate().filter_map(move |(col, depth)| {
+        if depth == 0 {
+            return None;
+        }
+        let pixel = [col as f32, row as f32];
+        Some(intrinsics.deproject(pixel, depth as f32 * depth_units))
+    })
+}
+
+/// Deprojects the pixels with a valid depth in `height` rows of Z16 data, each `width` pixels
+/// wide and `stride` bytes apart, splitting the rows across threads.
+#[cfg(feature = "rayon")]
+fn par_deproject_rows(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    stride: usize,
+    depth_units: f32,
+    intrinsics: &Rs2Intrinsics,
+) -> Vec<[f32; 3]> {
+    data.par_chunks(stride)
+        .take(height)
+        .enumerate()
+        .flat_map_iter(|(row, row_data)| {
+            deproject_row(row, z16_row(row_data, width), depth_units, intrinsics)
+        })
+        .collect()
+}
+
+/// The number of pixels packed into each group of [`RAW10_GROUP_SIZE`] bytes of RAW10 data.
+const RAW10_PIXELS_PER_GROUP: usize = 4;
+
+/// The number of bytes in each group of packed RAW10 pixels.
+///
+/// The first four bytes hold the 8 most significant bits of each pixel, and the last byte holds
+/// the 2 least significant bits of all four pixels, starting with the first pixel in the lowest
+/// bits. This is the RAW10 layout of the MIPI CSI-2 specification.
+const RAW10_GROUP_SIZE: usize = 5;
+
+/// Gets the number of bytes taken up by a row of `width` pixels of `format`, excluding padding.
+///
+/// Returns `None` for formats without a fixed size per pixel, such as compressed formats.
+fn row_size(format: Rs2Format, width: usize) -> Option<usize> {
+    match format {
+        Rs2Format::Raw10 => {
+            // A partial group at the end of a row still takes up a whole group.
+            let groups = match width {
+                0 => 0,
+                _ => (width - 1) / RAW10_PIXELS_PER_GROUP + 1,
+            };
+            Some(groups * RAW10_GROUP_SIZE)
+        }
+        _ => format
+            .bytes_per_pixel()
+            .map(|bytes_per_pixel| width * bytes_per_pixel),
+    }
+}
+
+/// Iterates over `height` rows of Z16 data, each `width` pixels wide and `stride` bytes apart,
+/// yielding the raw depth of each pixel.
+fn z16_rows(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    stride: usize,
+) -> impl Iterator<Item = u16> + '_ {
+    data.chunks(stride)
+        .take(height)
+        .flat_map(move |row| z16_row(row, width))
+}
+
+/// Iterates over the raw depth of the first `width` pixels of a row of Z16 data.
+fn z16_row(row: &[u8], width: usize) -> impl Iterator<Item = u16> + '_ {
+    row[..width * 2]
+        .chunks_exact(2)
+        .map(|b| u16::from_ne_bytes([b[0], b[1]]))
+}
+
+/// Counts the non-zero values of `depths` in each of `bins` ranges evenly splitting `1..=65535`.
+fn depth_histogram(depths: impl Iterator<Item = u16>, bins: usize) -> Vec<u32> {
+    let mut histogram = vec![0; bins];
+    if bins == 0 {
+        return histogram;
+    }
+    for depth in depths.filter(|&depth| depth != 0) {
+        histogram[(depth as usize - 1) * bins / u16::MAX as usize] += 1;
+    }
+    histogram
+}
+
+/// Unpacks `height` rows of RAW10 data, each `width` pixels wide and `stride` bytes apart, into
+/// one 10-bit sample per pixel.
+fn unpack_raw10_rows(data: &[u8], width: usize, height: usize, stride: usize) -> Vec<u16> {
+    let mut samples = Vec::with_capacity(width * height);
+    for row in data.chunks(stride).take(height) {
+        let row_samples = row.chunks_exact(RAW10_GROUP_SIZE).flat_map(|group| {
+            let low_bits = group[RAW10_PIXELS_PER_GROUP];
+            group[..RAW10_PIXELS_PER_GROUP]
+                .iter()
+                .enumerate()
+                .map(move |(i, &high_bits)| {
+                    (u16::from(high_bits) << 2) | u16::from((low_bits >> (2 * i)) & 0b11)
+                })
+        });
+        samples.extend(row_samples.take(width));
+    }
+    samples
+}
+
+/// Copies `height` rows of RAW16 data, each `width` pixels wide and `stride` bytes apart, into
+/// one sample per pixel.
+fn unpack_raw16_rows(data: &[u8], width: usize, height: usize, stride: usize) -> Vec<u16> {
+    data.chunks(stride)
+        .take(height)
+        .flat_map(|row| row[..width * 2].chunks_exact(2))
+        .map(|sample| u16::from_ne_bytes([sample[0], sample[1]]))
+        .collect()
+}
+
+/// Hashes the first `row_size` bytes of each of the first `height` rows of `data`, which start
+/// `stride` bytes apart, or all of `data` if the rows have no fixed size.
+#[cfg(feature = "content-hash")]
+fn hash_rows(data: &[u8], row_size: Option<usize>, height: usize, stride: usize) -> u64 {
+    let row_size = match row_size {
+        Some(row_size) => row_size,
+        None => return xxhash_rust::xxh3::xxh3_64(data),
+    };
+
+    let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+    for row in data.chunks(stride).take(height) {
+        hasher.update(&row[..row_size]);
+    }
+    hasher.digest()
+}
+
+/// Checks that `data_size` bytes, with rows `stride` bytes apart, hold `width` x `height` pixels
+/// of `format`.
+fn validate_data_size(
+    format: Rs2Format,
+    width: usize,
+    height: usize,
+    stride: usize,
+    data_size: usize,
+) -> Result<(), FrameSizeMismatch> {
+    let row_size = match row_size(format, width) {
+        Some(row_size) => row_size,
+        None => return Ok(()),
+    };
+
+    let expected = if height == 0 {
+        0
+    } else {
+        stride.max(row_size) * (height - 1) + row_size
+    };
+
+    if stride < row_size || data_size < expected {
+        Err(FrameSizeMismatch {
+            format,
+            width,
+            height,
+            expected,
+            actual: data_size,
+        })
+    } else {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -591,4 +1382,175 @@ mod tests {
         assert_eq!(FisheyeFrame::kind(), Rs2StreamKind::Fisheye);
         assert_eq!(ConfidenceFrame::kind(), Rs2StreamKind::Confidence);
     }
+
+    #[test]
+    fn depth_histogram_sums_to_valid_pixels() {
+        let (width, height, stride) = (4, 3, 4 * 2 + 4);
+        let depths: [u16; 12] = [0, 1, 500, 1000, 0, 0, 30000, 32768, 32769, 65535, 2000, 0];
+
+        // Rows padded to the stride with bytes that would count as depth if they were read.
+        let mut data = vec![0xff_u8; stride * height];
+        for (i, depth) in depths.iter().enumerate() {
+            let offset = i / width * stride + i % width * 2;
+            data[offset..offset + 2].copy_from_slice(&depth.to_ne_bytes());
+        }
+
+        let histogram = depth_histogram(z16_rows(&data, width, height, stride), 2);
+        assert_eq!(histogram, vec![6, 2]);
+        assert_eq!(histogram.iter().sum::<u32>(), 8);
+
+        let histogram = depth_histogram(z16_rows(&data, width, height, stride), 1024);
+        assert_eq!(histogram.iter().sum::<u32>(), 8);
+        assert_eq!(histogram[0], 1);
+        assert_eq!(histogram[1023], 1);
+
+        assert!(depth_histogram(z16_rows(&data, width, height, stride), 0).is_empty());
+    }
+
+    #[cfg(feature = "content-hash")]
+    #[test]
+    fn identical_data_hashes_equal_and_different_data_differs() {
+        let (row_size, height, stride) = (6, 3, 8);
+        let data: Vec<u8> = (0..stride * height).map(|i| i as u8).collect();
+
+        let mut identical = data.clone();
+        assert_eq!(
+            hash_rows(&data, Some(row_size), height, stride),
+            hash_rows(&identical, Some(row_size), height, stride)
+        );
+
+        // Padding at the end of a row does not hold pixel data, so it does not change the hash.
+        identical[stride - 1] = 0xff;
+        assert_eq!(
+            hash_rows(&data, Some(row_size), height, stride),
+            hash_rows(&identical, Some(row_size), height, stride)
+        );
+
+        let mut different = data.clone();
+        different[stride + 2] ^= 1;
+        assert_ne!(
+            hash_rows(&data, Some(row_size), height, stride),
+            hash_rows(&different, Some(row_size), height, stride)
+        );
+        assert_ne!(
+            hash_rows(&data, None, height, stride),
+            hash_rows(&different, None, height, stride)
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_deprojection_matches_serial_deprojection() {
+        let intrinsics = Rs2Intrinsics(sys::rs2_intrinsics {
+            width: 64,
+            height: 48,
+            ppx: 31.5,
+            ppy: 23.75,
+            fx: 60.0,
+            fy: 60.5,
+            model: crate::kind::Rs2DistortionModel::BrownConradyInverse as sys::rs2_distortion,
+            coeffs: [0.12, -0.25, 0.001, -0.0005, 0.08],
+        });
+        let (width, height, stride) = (64, 48, 64 * 2 + 16);
+
+        // Rows padded to the stride, with a hole of invalid depth every few pixels.
+        let mut data = vec![0_u8; stride * height];
+        for row in 0..height {
+            for col in 0..width {
+                let depth = if (row + col) % 7 == 0 {
+                    0
+                } else {
+                    (500 + row * width + col) as u16
+                };
+                let offset = row * stride + col * 2;
+                data[offset..offset + 2].copy_from_slice(&depth.to_ne_bytes());
+            }
+        }
+
+        let serial: Vec<[f32; 3]> = data
+            .chunks(stride)
+            .take(height)
+            .enumerate()
+            .flat_map(|(row, row_data)| {
+                deproject_row(row, z16_row(row_data, width), 0.001, &intrinsics)
+            })
+            .collect();
+        let parallel = par_deproject_rows(&data, width, height, stride, 0.001, &intrinsics);
+
+        assert!(!serial.is_empty());
+        assert!(serial.len() < width * height);
+        assert_eq!(parallel, serial);
+    }
+
+    #[test]
+    fn data_matching_profile_is_valid() {
+        assert!(validate_data_size(Rs2Format::Rgb8, 640, 480, 640 * 3, 640 * 480 * 3).is_ok());
+        assert!(validate_data_size(Rs2Format::Z16, 640, 480, 640 * 2, 640 * 480 * 2).is_ok());
+    }
+
+    #[test]
+    fn padded_rows_are_valid() {
+        assert!(validate_data_size(Rs2Format::Y8, 641, 3, 656, 656 * 3).is_ok());
+    }
+
+    #[test]
+    fn packed_data_for_rgb8_profile_is_a_mismatch() {
+        // YUYV data, at two bytes per pixel, delivered for an RGB8 stream.
+        let mismatch =
+            validate_data_size(Rs2Format::Rgb8, 640, 480, 640 * 2, 640 * 480 * 2).unwrap_err();
+
+        assert_eq!(mismatch.format, Rs2Format::Rgb8);
+        assert_eq!(mismatch.expected, 640 * 480 * 3);
+        assert_eq!(mismatch.actual, 640 * 480 * 2);
+    }
+
+    #[test]
+    fn truncated_data_is_a_mismatch() {
+        assert!(validate_data_size(Rs2Format::Z16, 640, 480, 640 * 2, 640 * 479 * 2).is_err());
+    }
+
+    #[test]
+    fn raw10_rows_hold_four_pixels_per_five_bytes() {
+        assert_eq!(row_size(Rs2Format::Raw10, 640), Some(800));
+        assert_eq!(row_size(Rs2Format::Raw10, 6), Some(10));
+        assert!(validate_data_size(Rs2Format::Raw10, 640, 480, 800, 800 * 480).is_ok());
+        assert!(validate_data_size(Rs2Format::Raw10, 640, 480, 640, 640 * 480).is_err());
+    }
+
+    #[test]
+    fn unpacks_raw10_samples() {
+        // Two rows of six pixels, i.e. two groups each, padded to a stride of 12 bytes.
+        #[rustfmt::skip]
+        let data = [
+            0x00, 0xff, 0x80, 0x01, 0b11_10_01_00, 0x12, 0x34, 0x00, 0x00, 0b0000_01_11, 0xaa, 0xaa,
+            0xff, 0xff, 0xff, 0xff, 0b11_11_11_11, 0x00, 0x00, 0x00, 0x00, 0b0000_00_00, 0xaa, 0xaa,
+        ];
+
+        assert_eq!(
+            unpack_raw10_rows(&data, 6, 2, 12),
+            vec![
+                0x000, 0x3fd, 0x202, 0x007, 0x04b, 0x0d1, //
+                0x3ff, 0x3ff, 0x3ff, 0x3ff, 0x000, 0x000,
+            ]
+        );
+    }
+
+    #[test]
+    fn unpacks_raw16_samples_skipping_padding() {
+        let mut data = Vec::new();
+        for row in [[1u16, 2, 3], [0x1234, 0xffff, 0]] {
+            data.extend(row.iter().flat_map(|sample| sample.to_ne_bytes()));
+            data.extend([0xaa, 0xaa]);
+        }
+
+        assert_eq!(
+            unpack_raw16_rows(&data, 3, 2, 8),
+            vec![1, 2, 3, 0x1234, 0xffff, 0]
+        );
+    }
+
+    #[test]
+    fn formats_without_fixed_pixel_size_are_always_valid() {
+        assert!(validate_data_size(Rs2Format::Mjpeg, 640, 480, 0, 1024).is_ok());
+    }
 }
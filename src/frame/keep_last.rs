@@ -0,0 +1,78 @@
+//! Type for retaining the most recent frames of a stream.
+
+use super::prelude::FrameEx;
+
+/// A buffer that retains the `capacity` most recently pushed frames.
+///
+/// Once the buffer is full, pushing a frame drops the oldest frame in the buffer, which releases
+/// it back to librealsense2. This is useful for temporal analysis, such as motion detection or
+/// averaging over the last few depth frames.
+///
+/// # Frame pools
+///
+/// librealsense2 allocates the frames of each stream from a small pool, and a frame can only be
+/// reused once it has been released. Retaining many frames of a stream can therefore cause the
+/// stream to drop frames, so the capacity should be kept small.
+#[derive(Debug)]
+pub struct KeepLast<F> {
+    /// The retained frames, ordered from oldest to newest.
+    frames: Vec<F>,
+    /// The maximum number of frames that are retained.
+    capacity: usize,
+}
+
+impl<F> KeepLast<F>
+where
+    F: FrameEx,
+{
+    /// Constructs an empty buffer which retains at most `capacity` frames.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            frames: Vec::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Pushes a frame into the buffer, dropping the oldest frame if the buffer is full.
+    ///
+    /// If the buffer has a capacity of zero, `frame` is dropped immediately.
+    pub fn push(&mut self, frame: F) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.frames.len() == self.capacity {
+            self.frames.remove(0);
+        }
+        self.frames.push(frame);
+    }
+
+    /// Gets the retained frames, ordered from oldest to newest.
+    pub fn frames(&self) -> &[F] {
+        &self.frames
+    }
+
+    /// Gets the most recently pushed frame, if any.
+    pub fn latest(&self) -> Option<&F> {
+        self.frames.last()
+    }
+
+    /// Gets the maximum number of frames that are retained.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Gets the number of frames currently retained.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Predicate for whether no frames are currently retained.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Drops all retained frames.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+}
@@ -5,7 +5,10 @@
 //!
 //! See the docs for [MotionFrame::motion] for more.
 
-use super::prelude::{CouldNotGetFrameSensorError, FrameCategory, FrameConstructionError, FrameEx};
+use super::prelude::{
+    write_summary, CouldNotGetFrameSensorError, FrameCategory, FrameConstructionError, FrameEx,
+    MetadataError,
+};
 use crate::{
     check_rs2_error,
     kind::{Rs2Extension, Rs2FrameMetadata, Rs2StreamKind, Rs2TimestampDomain},
@@ -17,6 +20,7 @@ use num_traits::FromPrimitive;
 use realsense_sys as sys;
 use std::{
     convert::{TryFrom, TryInto},
+    fmt,
     marker::PhantomData,
     ptr::{self, NonNull},
 };
@@ -100,7 +104,18 @@ impl<K> Drop for MotionFrame<K> {
     }
 }
 
+impl<K> fmt::Display for MotionFrame<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_summary(
+            f,
+            self,
+            format_args!("{:?}", self.frame_stream_profile.format()),
+        )
+    }
+}
+
 unsafe impl<K> Send for MotionFrame<K> {}
+unsafe impl<K> Sync for MotionFrame<K> {}
 
 impl<K> TryFrom<NonNull<sys::rs2_frame>> for MotionFrame<K> {
     type Error = anyhow::Error;
@@ -194,26 +209,34 @@ impl<K> FrameEx for MotionFrame<K> {
         self.frame_number
     }
 
-    fn metadata(&self, metadata_kind: Rs2FrameMetadata) -> Option<std::os::raw::c_longlong> {
-        if !self.supports_metadata(metadata_kind) {
-            return None;
-        }
-
+    fn try_metadata(
+        &self,
+        metadata_kind: Rs2FrameMetadata,
+    ) -> Result<Option<std::os::raw::c_longlong>, MetadataError> {
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
 
-            let val = sys::rs2_get_frame_metadata(
+            let supports_metadata = sys::rs2_supports_frame_metadata(
                 self.frame_ptr.as_ptr(),
                 #[allow(clippy::useless_conversion)]
                 (metadata_kind as i32).try_into().unwrap(),
                 &mut err,
             );
-            if err.as_ref().is_none() {
-                Some(val)
-            } else {
-                sys::rs2_free_error(err);
-                None
+            check_rs2_error!(err, MetadataError)?;
+
+            if supports_metadata == 0 {
+                return Ok(None);
             }
+
+            let val = sys::rs2_get_frame_metadata(
+                self.frame_ptr.as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (metadata_kind as i32).try_into().unwrap(),
+                &mut err,
+            );
+            check_rs2_error!(err, MetadataError)?;
+
+            Ok(Some(val))
         }
     }
 
@@ -244,6 +267,13 @@ impl<K> FrameEx for MotionFrame<K> {
     }
 }
 
+impl<K> MotionFrame<K> {
+    /// Gets the motion vector of the frame, regardless of the kind of motion.
+    pub(crate) fn motion(&self) -> &[f32; 3] {
+        &self.motion
+    }
+}
+
 impl AccelFrame {
     /// Returns a 3-item array representing the sensor motion recorded in the Accel frame.
     ///
@@ -2,18 +2,28 @@
 //!
 //! A Points frame is a RealSense point cloud storage class.
 
-use super::prelude::{CouldNotGetFrameSensorError, FrameCategory, FrameConstructionError, FrameEx};
+use super::{
+    image::ImageFrame,
+    prelude::{
+        write_summary, CouldNotGetFrameSensorError, FrameCategory, FrameConstructionError, FrameEx,
+        MetadataError,
+    },
+};
 use crate::{
+    base::Rs2Resolution,
     check_rs2_error,
     kind::{Rs2Extension, Rs2FrameMetadata, Rs2StreamKind, Rs2TimestampDomain},
     sensor::Sensor,
     stream_profile::StreamProfile,
 };
 use anyhow::Result;
+#[cfg(feature = "ndarray")]
+use ndarray::Array2;
 use num_traits::FromPrimitive;
 use realsense_sys as sys;
 use std::{
     convert::{TryFrom, TryInto},
+    fmt,
     ptr::{self, NonNull},
     slice,
 };
@@ -87,27 +97,34 @@ impl FrameEx for PointsFrame {
         self.frame_number
     }
 
-    fn metadata(&self, metadata_kind: Rs2FrameMetadata) -> Option<std::os::raw::c_longlong> {
-        if !self.supports_metadata(metadata_kind) {
-            return None;
-        }
-
+    fn try_metadata(
+        &self,
+        metadata_kind: Rs2FrameMetadata,
+    ) -> Result<Option<std::os::raw::c_longlong>, MetadataError> {
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
 
-            let val = sys::rs2_get_frame_metadata(
+            let supports_metadata = sys::rs2_supports_frame_metadata(
                 self.frame_ptr.as_ptr(),
                 #[allow(clippy::useless_conversion)]
                 (metadata_kind as i32).try_into().unwrap(),
                 &mut err,
             );
+            check_rs2_error!(err, MetadataError)?;
 
-            if err.as_ref().is_none() {
-                Some(val)
-            } else {
-                sys::rs2_free_error(err);
-                None
+            if supports_metadata == 0 {
+                return Ok(None);
             }
+
+            let val = sys::rs2_get_frame_metadata(
+                self.frame_ptr.as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (metadata_kind as i32).try_into().unwrap(),
+                &mut err,
+            );
+            check_rs2_error!(err, MetadataError)?;
+
+            Ok(Some(val))
         }
     }
 
@@ -151,7 +168,22 @@ impl Drop for PointsFrame {
     }
 }
 
+impl fmt::Display for PointsFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_summary(
+            f,
+            self,
+            format_args!(
+                "{:?}, {} points",
+                self.frame_stream_profile.format(),
+                self.num_points
+            ),
+        )
+    }
+}
+
 unsafe impl Send for PointsFrame {}
+unsafe impl Sync for PointsFrame {}
 
 impl std::convert::TryFrom<NonNull<sys::rs2_frame>> for PointsFrame {
     type Error = anyhow::Error;
@@ -247,16 +279,132 @@ impl PointsFrame {
         }
     }
 
+    /// Maps the texture coordinates of the point cloud to pixels of the `texture` frame.
+    ///
+    /// This yields the `(col, row)` of the pixel that each point maps to, in the same order as the
+    /// [vertices](Self::vertices), e.g. to sample the color of every point with
+    /// [`ImageFrame::get`]. `texture` should be the frame that the point cloud was
+    /// [mapped to](crate::processing_blocks::PointCloud::map_to), or another frame of the same
+    /// stream.
+    ///
+    /// Points outside of the field of view of the texture, including points without a valid depth,
+    /// have texture coordinates outside of `[0, 1]`. These are clamped to the nearest pixel on the
+    /// edge of the texture, so every pixel returned is within its bounds.
+    pub fn texture_pixels<K>(&self, texture: &ImageFrame<K>) -> Vec<(usize, usize)> {
+        let (width, height) = (texture.width(), texture.height());
+        self.texture_coordinates()
+            .iter()
+            .map(|uv| texture_pixel(*uv, width, height))
+            .collect()
+    }
+
     /// Gets number of points in the point cloud.
     pub fn points_count(&self) -> usize {
         self.num_points
     }
+
+    /// Gets the resolution of the grid that the points are organized in, if any.
+    ///
+    /// A point cloud calculated from a depth frame by the
+    /// [`PointCloud`](crate::processing_blocks::PointCloud) processing block holds one point per
+    /// depth pixel, in row-major order, so its vertices form a `height` x `width` grid with the
+    /// resolution of the depth stream. This is needed by algorithms that rely on the neighbours of
+    /// each point, such as normal estimation.
+    ///
+    /// Returns `None` if the point cloud is unorganized, i.e. its stream has no resolution or the
+    /// number of points does not match it.
+    pub fn resolution(&self) -> Option<Rs2Resolution> {
+        organized_resolution(self.frame_stream_profile.resolution().ok(), self.num_points)
+    }
+
+    /// Gets the width of the grid that the points are organized in, if any.
+    ///
+    /// See [`resolution`](Self::resolution) for when a point cloud is organized.
+    pub fn width(&self) -> Option<usize> {
+        self.resolution().map(|resolution| resolution.width)
+    }
+
+    /// Gets the height of the grid that the points are organized in, if any.
+    ///
+    /// See [`resolution`](Self::resolution) for when a point cloud is organized.
+    pub fn height(&self) -> Option<usize> {
+        self.resolution().map(|resolution| resolution.height)
+    }
+
+    /// Copies the vertices of the point cloud into an `N` x 3 array of X, Y, Z coordinates.
+    ///
+    /// This requires the `ndarray` feature.
+    #[cfg(feature = "ndarray")]
+    pub fn to_array2(&self) -> Array2<f32> {
+        let coordinates = self.vertices().iter().flat_map(|v| v.xyz).collect();
+        Array2::from_shape_vec((self.num_points, 3), coordinates).unwrap()
+    }
+}
+
+/// Maps the texture coordinate `uv` to the nearest pixel of a `width` x `height` texture, clamped
+/// to the edges of the texture.
+fn texture_pixel(uv: [f32; 2], width: usize, height: usize) -> (usize, usize) {
+    let to_pixel = |coordinate: f32, size: usize| {
+        // Casting a float to an integer saturates, so negative and NaN coordinates map to 0.
+        ((coordinate * size as f32 + 0.5) as usize).min(size.saturating_sub(1))
+    };
+    (to_pixel(uv[0], width), to_pixel(uv[1], height))
+}
+
+/// Gets the resolution of the grid that `num_points` points are organized in.
+///
+/// The points are only organized if they fill the grid of the stream `resolution` exactly.
+fn organized_resolution(
+    resolution: Option<Rs2Resolution>,
+    num_points: usize,
+) -> Option<Rs2Resolution> {
+    resolution.filter(|resolution| resolution.area() == num_points && num_points > 0)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn points_filling_the_stream_resolution_are_organized() {
+        let resolution = Rs2Resolution {
+            width: 640,
+            height: 480,
+        };
+        let organized = organized_resolution(Some(resolution), 640 * 480).unwrap();
+        assert_eq!(organized, resolution);
+        assert_eq!(organized.width * organized.height, 640 * 480);
+    }
+
+    #[test]
+    fn points_not_filling_the_stream_resolution_are_unorganized() {
+        let resolution = Rs2Resolution {
+            width: 640,
+            height: 480,
+        };
+        assert_eq!(organized_resolution(Some(resolution), 1000), None);
+        assert_eq!(organized_resolution(Some(resolution), 0), None);
+        assert_eq!(organized_resolution(None, 640 * 480), None);
+    }
+
+    #[test]
+    fn texture_coordinates_map_to_the_nearest_pixel() {
+        assert_eq!(texture_pixel([0.0, 0.0], 640, 480), (0, 0));
+        assert_eq!(texture_pixel([0.5, 0.5], 640, 480), (320, 240));
+        assert_eq!(
+            texture_pixel([10.2 / 640.0, 20.7 / 480.0], 640, 480),
+            (10, 21)
+        );
+    }
+
+    #[test]
+    fn texture_coordinates_outside_of_the_texture_are_clamped() {
+        assert_eq!(texture_pixel([1.0, 1.0], 640, 480), (639, 479));
+        assert_eq!(texture_pixel([-0.3, 2.5], 640, 480), (0, 479));
+        assert_eq!(texture_pixel([f32::NAN, f32::INFINITY], 640, 480), (0, 479));
+        assert_eq!(texture_pixel([0.5, 0.5], 0, 0), (0, 0));
+    }
+
     #[test]
     fn frame_has_correct_kind() {
         assert_eq!(PointsFrame::kind(), Rs2StreamKind::Any);
@@ -4,7 +4,10 @@
 //! at a point in time. See the member and function declarations for how these values are stored
 //! and retrieved.
 
-use super::prelude::{CouldNotGetFrameSensorError, FrameCategory, FrameConstructionError, FrameEx};
+use super::prelude::{
+    write_summary, CouldNotGetFrameSensorError, FrameCategory, FrameConstructionError, FrameEx,
+    MetadataError,
+};
 use crate::{
     check_rs2_error,
     kind::{Rs2Extension, Rs2FrameMetadata, Rs2StreamKind, Rs2TimestampDomain},
@@ -14,8 +17,11 @@ use crate::{
 use anyhow::Result;
 use num_traits::FromPrimitive;
 use realsense_sys as sys;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::{
     convert::{TryFrom, TryInto},
+    fmt,
     mem::MaybeUninit,
     ptr::{self, NonNull},
 };
@@ -41,6 +47,8 @@ pub struct PoseFrame {
 }
 
 /// Used by the tracker and mapper to estimate the certainty in this pose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Confidence {
     /// The tracker/mapper has failed. This information is probably not reliable.
     Failed,
@@ -52,7 +60,46 @@ pub enum Confidence {
     High,
 }
 
+/// A copy of the pose held by a [`PoseFrame`].
+///
+/// Unlike the frame, this does not hold on to any librealsense2 resources, so it can be kept
+/// around indefinitely, e.g. to record the trajectory of a device.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PoseData {
+    /// X, Y, Z values of translation, in meters (relative to initial position)
+    pub translation: [f32; 3],
+    /// X, Y, Z values of velocity, in meters/sec
+    pub velocity: [f32; 3],
+    /// X, Y, Z values of acceleration, in meters/sec^2
+    pub acceleration: [f32; 3],
+    /// Qi, Qj, Qk, Qr components of rotation as represented in quaternion rotation (relative to initial position)
+    pub rotation: [f32; 4],
+    /// X, Y, Z values of angular velocity, in radians/sec
+    pub angular_velocity: [f32; 3],
+    /// X, Y, Z values of angular acceleration, in radians/sec^2
+    pub angular_acceleration: [f32; 3],
+    /// Pose confidence of the tracker
+    pub tracker_confidence: Confidence,
+    /// Pose confidence of the mapper
+    pub mapper_confidence: Confidence,
+}
+
 impl PoseFrame {
+    /// Copies all of the pose data out of the frame.
+    pub fn pose_data(&self) -> PoseData {
+        PoseData {
+            translation: self.translation(),
+            velocity: self.velocity(),
+            acceleration: self.acceleration(),
+            rotation: self.rotation(),
+            angular_velocity: self.angular_velocity(),
+            angular_acceleration: self.angular_acceleration(),
+            tracker_confidence: self.tracker_confidence(),
+            mapper_confidence: self.mapper_confidence(),
+        }
+    }
+
     /// X, Y, Z values of translation, in meters (relative to initial position)
     pub fn translation(&self) -> [f32; 3] {
         let sys::rs2_vector { x, y, z } = self.data.translation;
@@ -123,7 +170,18 @@ impl Drop for PoseFrame {
     }
 }
 
+impl fmt::Display for PoseFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_summary(
+            f,
+            self,
+            format_args!("{:?}", self.frame_stream_profile.format()),
+        )
+    }
+}
+
 unsafe impl Send for PoseFrame {}
+unsafe impl Sync for PoseFrame {}
 
 impl FrameCategory for PoseFrame {
     fn extension() -> Rs2Extension {
@@ -223,27 +281,34 @@ impl FrameEx for PoseFrame {
         self.frame_number
     }
 
-    fn metadata(&self, metadata_kind: Rs2FrameMetadata) -> Option<std::os::raw::c_longlong> {
-        if !self.supports_metadata(metadata_kind) {
-            return None;
-        }
-
+    fn try_metadata(
+        &self,
+        metadata_kind: Rs2FrameMetadata,
+    ) -> Result<Option<std::os::raw::c_longlong>, MetadataError> {
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
 
-            let val = sys::rs2_get_frame_metadata(
+            let supports_metadata = sys::rs2_supports_frame_metadata(
                 self.frame_ptr.as_ptr(),
                 #[allow(clippy::useless_conversion)]
                 (metadata_kind as i32).try_into().unwrap(),
                 &mut err,
             );
+            check_rs2_error!(err, MetadataError)?;
 
-            if err.as_ref().is_none() {
-                Some(val)
-            } else {
-                sys::rs2_free_error(err);
-                None
+            if supports_metadata == 0 {
+                return Ok(None);
             }
+
+            let val = sys::rs2_get_frame_metadata(
+                self.frame_ptr.as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (metadata_kind as i32).try_into().unwrap(),
+                &mut err,
+            );
+            check_rs2_error!(err, MetadataError)?;
+
+            Ok(Some(val))
         }
     }
 
@@ -282,4 +347,23 @@ mod tests {
     fn frame_has_correct_kind() {
         assert_eq!(PoseFrame::kind(), Rs2StreamKind::Pose);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn pose_data_round_trips_through_serde() {
+        let pose = PoseData {
+            translation: [0.25, -1.0, 3.5],
+            velocity: [0.0, 0.125, 0.0],
+            acceleration: [9.75, 0.0, -0.5],
+            rotation: [0.0, 0.0, 0.5, 0.75],
+            angular_velocity: [0.5, 0.0, 0.0],
+            angular_acceleration: [0.0, -0.25, 0.0],
+            tracker_confidence: Confidence::High,
+            mapper_confidence: Confidence::Low,
+        };
+
+        let json = serde_json::to_string(&pose).unwrap();
+        assert!(json.contains("\"tracker_confidence\":\"High\""));
+        assert_eq!(serde_json::from_str::<PoseData>(&json).unwrap(), pose);
+    }
 }
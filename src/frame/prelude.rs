@@ -5,13 +5,21 @@
 //! with the wildcard describing the specialization that goes with that type.
 
 use crate::{
-    kind::{Rs2Exception, Rs2Extension, Rs2FrameMetadata, Rs2StreamKind, Rs2TimestampDomain},
+    kind::{
+        Rs2Exception, Rs2Extension, Rs2Format, Rs2FrameMetadata, Rs2StreamKind, Rs2TimestampDomain,
+    },
     sensor::Sensor,
     stream_profile::StreamProfile,
 };
 use anyhow::Result;
+use num_traits::FromPrimitive;
 use realsense_sys as sys;
-use std::ptr::NonNull;
+use std::{
+    convert::{TryFrom, TryInto},
+    fmt,
+    ptr::NonNull,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 use thiserror::Error;
 
 /// How many bits are in a byte? Who can truly say.
@@ -65,6 +73,18 @@ pub enum DepthError {
     /// Cannot derive the depth units used.
     #[error("Could not get depth units. Type: {0}; Reason: {1}")]
     CouldNotGetDepthUnits(Rs2Exception, String),
+    /// The requested pixel is outside of the frame.
+    #[error("Pixel ({col}, {row}) is outside of the {width}x{height} frame.")]
+    PixelOutOfBounds {
+        /// The column of the requested pixel.
+        col: usize,
+        /// The row of the requested pixel.
+        row: usize,
+        /// The width of the frame.
+        width: usize,
+        /// The height of the frame.
+        height: usize,
+    },
 }
 
 /// Occurs when a baseline cannot be derived from a Disparity frame.
@@ -72,6 +92,65 @@ pub enum DepthError {
 #[error("Could not get baseline. Type: {0}; Reason: {1}")]
 pub struct DisparityError(pub Rs2Exception, pub String);
 
+/// Occurs when a frame cannot be converted to another representation because of its format.
+///
+/// This is returned e.g. when converting frames to images from the `image` crate, or to arrays
+/// from the `ndarray` crate.
+#[derive(Error, Debug)]
+#[error("Frames with format {0:?} cannot be converted.")]
+pub struct FormatError(pub Rs2Format);
+
+/// Occurs when the metadata of a frame cannot be read.
+///
+/// This is not returned for metadata that the frame does not support, which is instead reported as
+/// `Ok(None)` by [`FrameEx::try_metadata`].
+#[derive(Error, Debug)]
+#[error("Could not get frame metadata. Type: {0}; Reason: {1}")]
+pub struct MetadataError(pub Rs2Exception, pub String);
+
+/// Occurs when the data of a frame is too small for the resolution and format of its stream.
+///
+/// This happens when the stream profile of a frame does not describe its data, e.g. when frames
+/// of a packed format are delivered for a stream that was configured as [`Rs2Format::Rgb8`].
+/// Interpreting the data according to the profile would then read the wrong pixels.
+#[derive(Error, Debug)]
+#[error(
+    "Frame holds {actual} bytes, but {width}x{height} pixels of format {format:?} need {expected}."
+)]
+pub struct FrameSizeMismatch {
+    /// The format of the stream profile of the frame.
+    pub format: Rs2Format,
+    /// The width of the frame, in pixels.
+    pub width: usize,
+    /// The height of the frame, in pixels.
+    pub height: usize,
+    /// The number of bytes needed for the pixels of the frame.
+    pub expected: usize,
+    /// The number of bytes held by the frame.
+    pub actual: usize,
+}
+
+/// Occurs when the data of an image frame cannot be read through one of its typed accessors.
+#[derive(Error, Debug)]
+pub enum FrameDataError {
+    /// The frame does not hold data of a format that the accessor supports.
+    #[error("{0}")]
+    UnsupportedFormat(FormatError),
+    /// The frame holds too little data for its resolution and format.
+    #[error("{0}")]
+    SizeMismatch(FrameSizeMismatch),
+}
+
+/// Occurs when the data of a frame cannot be written, because the frame may be shared.
+///
+/// librealsense2 reference counts frames, but does not expose the count. Only frames that the
+/// crate knows to be owned exclusively can be written, i.e. frames
+/// [allocated](crate::processing_blocks::FramePool::allocate_video_frame) from the pool of a
+/// processing block that have not been output yet.
+#[derive(Error, Debug)]
+#[error("Frame data cannot be written, because the frame may be shared.")]
+pub struct SharedFrameError;
+
 /// Cannot get the frame sensor.
 #[derive(Error, Debug)]
 #[error("Could not get frame sensor. Type: {0}; Reason: {1}")]
@@ -85,6 +164,20 @@ pub trait FrameEx {
     /// Get the sensor associated with the frame.
     fn sensor(&self) -> Result<Sensor>;
 
+    /// Get every extension supported by the sensor that produced the frame.
+    ///
+    /// This tells e.g. whether the frame came from a [`DepthSensor`](Rs2Extension::DepthSensor),
+    /// [`ColorSensor`](Rs2Extension::ColorSensor) or [`MotionSensor`](Rs2Extension::MotionSensor),
+    /// which is useful to route frames by their source. See [`Sensor::extensions`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sensor of the frame cannot be retrieved, e.g. because the frame was
+    /// not produced by a sensor.
+    fn sensor_extensions(&self) -> Result<Vec<Rs2Extension>> {
+        Ok(self.sensor()?.extensions())
+    }
+
     /// Get the frame number.
     fn frame_number(&self) -> u64;
 
@@ -92,16 +185,146 @@ pub trait FrameEx {
     fn timestamp(&self) -> f64;
 
     /// Get the RealSense timestamp domain for the current timestamp.
+    ///
+    /// Frames from sensors with [global time](crate::sensor::Sensor::enable_global_time) enabled
+    /// report [`Rs2TimestampDomain::GlobalTime`].
     fn timestamp_domain(&self) -> Rs2TimestampDomain;
 
+    /// Get the frame timestamp as a [`Duration`].
+    ///
+    /// The [timestamp](Self::timestamp) is reported in milliseconds since the start of its
+    /// [domain](Self::timestamp_domain). Negative timestamps are clamped to zero.
+    fn timestamp_duration(&self) -> Duration {
+        Duration::from_secs_f64(self.timestamp().max(0.0) / 1000.0)
+    }
+
+    /// Get the frame timestamp as a point in wall-clock time.
+    ///
+    /// Returns `None` if the timestamp is measured by the device's [hardware
+    /// clock](Rs2TimestampDomain::HardwareClock), which has no relation to the host's clock.
+    fn system_time(&self) -> Option<SystemTime> {
+        if self.timestamp_domain().is_hardware() {
+            None
+        } else {
+            UNIX_EPOCH.checked_add(self.timestamp_duration())
+        }
+    }
+
     /// Get frame metadata.
     ///
-    /// Returns `None` if the `metadata_kind` is not supported by the frame type.
-    fn metadata(&self, metadata_kind: Rs2FrameMetadata) -> Option<std::os::raw::c_longlong>;
+    /// Returns `None` if the `metadata_kind` is not supported by the frame type, or if it cannot
+    /// be read. Use [`try_metadata`](Self::try_metadata) to tell these cases apart.
+    fn metadata(&self, metadata_kind: Rs2FrameMetadata) -> Option<std::os::raw::c_longlong> {
+        self.try_metadata(metadata_kind).ok().flatten()
+    }
+
+    /// Get frame metadata, distinguishing unsupported metadata from failures.
+    ///
+    /// Returns `Ok(None)` if the `metadata_kind` is not supported by the frame, which is normal
+    /// for metadata such as [`ActualExposure`](Rs2FrameMetadata::ActualExposure) that only some
+    /// devices and firmwares provide.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MetadataError`] if the metadata cannot be read.
+    fn try_metadata(
+        &self,
+        metadata_kind: Rs2FrameMetadata,
+    ) -> Result<Option<std::os::raw::c_longlong>, MetadataError>;
 
     /// Test whether the metadata arguemnt is supported by the frame.
     fn supports_metadata(&self, metadata_kind: Rs2FrameMetadata) -> bool;
 
+    /// Iterate over all metadata supported by the frame, as `(key, value)` pairs.
+    ///
+    /// The metadata is read lazily as the iterator advances, so nothing is allocated, and
+    /// unsupported keys are skipped.
+    fn metadata_iter(&self) -> MetadataIter<'_, Self> {
+        MetadataIter {
+            frame: self,
+            next_key: 0,
+        }
+    }
+
+    /// Get the [`FrameCounter`](Rs2FrameMetadata::FrameCounter) metadata of the frame, i.e. the
+    /// sequential index of the frame in its stream, as counted by the device.
+    ///
+    /// Returns `Ok(None)` if the frame does not support the metadata.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MetadataError`] if the metadata cannot be read.
+    fn frame_counter(&self) -> Result<Option<u64>, MetadataError> {
+        unsigned_metadata(self, Rs2FrameMetadata::FrameCounter)
+    }
+
+    /// Get the [`FrameTimestamp`](Rs2FrameMetadata::FrameTimestamp) metadata of the frame, i.e.
+    /// the time at which the device captured the frame, in microseconds of the device clock.
+    ///
+    /// Returns `Ok(None)` if the frame does not support the metadata.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MetadataError`] if the metadata cannot be read.
+    fn frame_timestamp(&self) -> Result<Option<u64>, MetadataError> {
+        unsigned_metadata(self, Rs2FrameMetadata::FrameTimestamp)
+    }
+
+    /// Get the [`BackendTimestamp`](Rs2FrameMetadata::BackendTimestamp) metadata of the frame,
+    /// i.e. the time at which the USB or other backend of the host received the frame, in
+    /// milliseconds of the host clock.
+    ///
+    /// Returns `Ok(None)` if the frame does not support the metadata.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MetadataError`] if the metadata cannot be read.
+    fn backend_timestamp(&self) -> Result<Option<u64>, MetadataError> {
+        unsigned_metadata(self, Rs2FrameMetadata::BackendTimestamp)
+    }
+
+    /// Get the [`TimeOfArrival`](Rs2FrameMetadata::TimeOfArrival) metadata of the frame, i.e. the
+    /// time at which librealsense2 received the frame, in milliseconds of the host clock.
+    ///
+    /// Compared to the [backend timestamp](Self::backend_timestamp), this includes the time spent
+    /// by librealsense2 to e.g. unpack the frame.
+    ///
+    /// Returns `Ok(None)` if the frame does not support the metadata.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MetadataError`] if the metadata cannot be read.
+    fn time_of_arrival(&self) -> Result<Option<u64>, MetadataError> {
+        unsigned_metadata(self, Rs2FrameMetadata::TimeOfArrival)
+    }
+
+    /// Get the [`ActualExposure`](Rs2FrameMetadata::ActualExposure) metadata of the frame, i.e.
+    /// the exposure that the frame was captured with, in the units of
+    /// [`Rs2Option::Exposure`](crate::kind::Rs2Option::Exposure) for the sensor.
+    ///
+    /// With auto-exposure enabled, this is the exposure chosen by the device for this frame.
+    ///
+    /// Returns `Ok(None)` if the frame does not support the metadata.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MetadataError`] if the metadata cannot be read.
+    fn actual_exposure(&self) -> Result<Option<u64>, MetadataError> {
+        unsigned_metadata(self, Rs2FrameMetadata::ActualExposure)
+    }
+
+    /// Get the [`GainLevel`](Rs2FrameMetadata::GainLevel) metadata of the frame, i.e. the
+    /// sensor-specific gain that the frame was captured with.
+    ///
+    /// Returns `Ok(None)` if the frame does not support the metadata.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MetadataError`] if the metadata cannot be read.
+    fn gain_level(&self) -> Result<Option<u64>, MetadataError> {
+        unsigned_metadata(self, Rs2FrameMetadata::GainLevel)
+    }
+
     /// Get (and own) the underlying frame pointer for this frame.
     ///
     /// This is primarily useful for passing this frame forward to a processing block or blocks
@@ -115,6 +338,53 @@ pub trait FrameEx {
     unsafe fn get_owned_raw(self) -> NonNull<sys::rs2_frame>;
 }
 
+/// Gets metadata which is never negative, such as counters and timestamps, as an unsigned value.
+///
+/// A negative value cannot be meaningful for such metadata, so it is reported as unsupported.
+fn unsigned_metadata<F>(
+    frame: &F,
+    metadata_kind: Rs2FrameMetadata,
+) -> Result<Option<u64>, MetadataError>
+where
+    F: FrameEx + ?Sized,
+{
+    Ok(frame
+        .try_metadata(metadata_kind)?
+        .and_then(|value| u64::try_from(value).ok()))
+}
+
+/// Iterator over the metadata supported by a frame.
+///
+/// This is returned by [`FrameEx::metadata_iter`], and borrows the frame it reads from.
+#[derive(Debug)]
+pub struct MetadataIter<'a, F: ?Sized> {
+    /// The frame that metadata is read from.
+    frame: &'a F,
+    /// The ordinal of the next metadata key to read.
+    next_key: i32,
+}
+
+impl<'a, F> Iterator for MetadataIter<'a, F>
+where
+    F: FrameEx + ?Sized,
+{
+    type Item = (Rs2FrameMetadata, std::os::raw::c_longlong);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next_key < sys::rs2_frame_metadata_value_RS2_FRAME_METADATA_COUNT as i32 {
+            let key = Rs2FrameMetadata::from_i32(self.next_key);
+            self.next_key += 1;
+
+            if let Some(key) = key {
+                if let Some(value) = self.frame.metadata(key) {
+                    return Some((key, value));
+                }
+            }
+        }
+        None
+    }
+}
+
 /// A trait for specifying which runtime stream kinds can be held within a frame type
 ///
 /// This trait changes some of the semantics for how to think about librealsense2 frames. The
@@ -138,3 +408,224 @@ pub trait FrameCategory {
     /// Predicate for checking if the RS2 frame's stream has the same kind as the frame category.
     fn has_correct_kind(&self) -> bool;
 }
+
+/// Converts an owned raw frame to `F`, if it belongs to the frame category of `F`.
+///
+/// Frames of another extension or stream kind, e.g. infrared frames when `F` is
+/// [`DepthFrame`](crate::frame::DepthFrame), and frames that cannot be converted are released.
+///
+/// # Safety
+///
+/// The caller must own a reference to the frame, which is taken over by this function.
+pub(crate) unsafe fn frame_of_category<F>(frame_ptr: NonNull<sys::rs2_frame>) -> Option<F>
+where
+    F: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory,
+{
+    let mut err = std::ptr::null_mut::<sys::rs2_error>();
+    let is_extendable_to = sys::rs2_is_frame_extendable_to(
+        frame_ptr.as_ptr(),
+        #[allow(clippy::useless_conversion)]
+        (F::extension() as i32).try_into().unwrap(),
+        &mut err,
+    );
+
+    if err.as_ref().is_none() {
+        if is_extendable_to != 0 {
+            if let Ok(f) = F::try_from(frame_ptr) {
+                // If the call to try_from above is successful, then the frame is owned by the
+                // type `F` and we should not release it. It is released when `f` is dropped.
+                if F::kind() == Rs2StreamKind::Any || f.has_correct_kind() {
+                    return Some(f);
+                }
+                return None;
+            }
+        }
+    } else {
+        sys::rs2_free_error(err);
+    }
+    sys::rs2_release_frame(frame_ptr.as_ptr());
+    None
+}
+
+/// Writes the one-line summary of `frame` that the `Display` implementations of frames use, with
+/// type-specific `details` such as the resolution.
+///
+/// Only values that are cached when the frame is constructed are used, so formatting a frame
+/// never calls into librealsense2 and cannot fail.
+pub(crate) fn write_summary<F>(
+    f: &mut fmt::Formatter<'_>,
+    frame: &F,
+    details: fmt::Arguments<'_>,
+) -> fmt::Result
+where
+    F: FrameEx,
+{
+    let profile = frame.stream_profile();
+    write!(
+        f,
+        "{} frame #{} (stream {}, {}) at {:.3} ms ({:?})",
+        profile.kind(),
+        frame.frame_number(),
+        profile.index(),
+        details,
+        frame.timestamp(),
+        frame.timestamp_domain(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// A frame without any data, for testing the provided methods of [`FrameEx`].
+    #[derive(Default)]
+    struct MockFrame {
+        timestamp: f64,
+        timestamp_domain: Option<Rs2TimestampDomain>,
+        metadata: HashMap<Rs2FrameMetadata, std::os::raw::c_longlong>,
+    }
+
+    impl FrameEx for MockFrame {
+        fn stream_profile(&self) -> &StreamProfile {
+            unimplemented!()
+        }
+
+        fn sensor(&self) -> Result<Sensor> {
+            unimplemented!()
+        }
+
+        fn frame_number(&self) -> u64 {
+            0
+        }
+
+        fn timestamp(&self) -> f64 {
+            self.timestamp
+        }
+
+        fn timestamp_domain(&self) -> Rs2TimestampDomain {
+            self.timestamp_domain
+                .unwrap_or(Rs2TimestampDomain::HardwareClock)
+        }
+
+        fn try_metadata(
+            &self,
+            metadata_kind: Rs2FrameMetadata,
+        ) -> Result<Option<std::os::raw::c_longlong>, MetadataError> {
+            Ok(self.metadata.get(&metadata_kind).copied())
+        }
+
+        fn supports_metadata(&self, metadata_kind: Rs2FrameMetadata) -> bool {
+            self.metadata.contains_key(&metadata_kind)
+        }
+
+        unsafe fn get_owned_raw(self) -> NonNull<sys::rs2_frame> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn timestamp_duration_is_in_milliseconds() {
+        let frame = MockFrame {
+            timestamp: 1234.5,
+            ..Default::default()
+        };
+        assert_eq!(frame.timestamp_duration(), Duration::from_micros(1_234_500));
+    }
+
+    #[test]
+    fn hardware_clock_frames_have_no_system_time() {
+        let frame = MockFrame {
+            timestamp: 1234.5,
+            ..Default::default()
+        };
+        assert_eq!(frame.system_time(), None);
+    }
+
+    #[test]
+    fn system_time_frames_are_relative_to_unix_epoch() {
+        let frame = MockFrame {
+            timestamp: 1_600_000_000_000.0,
+            timestamp_domain: Some(Rs2TimestampDomain::SystemTime),
+            ..Default::default()
+        };
+        assert_eq!(
+            frame.system_time(),
+            Some(UNIX_EPOCH + Duration::from_secs(1_600_000_000))
+        );
+    }
+
+    #[test]
+    fn metadata_iter_yields_only_supported_metadata() {
+        let mut metadata = HashMap::new();
+        metadata.insert(Rs2FrameMetadata::FrameCounter, 42);
+        metadata.insert(Rs2FrameMetadata::ActualFps, 30);
+        let frame = MockFrame {
+            metadata,
+            ..Default::default()
+        };
+
+        let collected: HashMap<_, _> = frame.metadata_iter().collect();
+        assert_eq!(collected, frame.metadata);
+    }
+
+    #[test]
+    fn typed_metadata_accessors_read_their_metadata() {
+        let mut metadata = HashMap::new();
+        metadata.insert(Rs2FrameMetadata::FrameCounter, 42);
+        metadata.insert(Rs2FrameMetadata::FrameTimestamp, 1_000_000);
+        metadata.insert(Rs2FrameMetadata::BackendTimestamp, 1_600_000_000_000);
+        metadata.insert(Rs2FrameMetadata::TimeOfArrival, 1_600_000_000_003);
+        metadata.insert(Rs2FrameMetadata::ActualExposure, 8500);
+        let frame = MockFrame {
+            metadata,
+            ..Default::default()
+        };
+
+        assert_eq!(frame.frame_counter().unwrap(), Some(42));
+        assert_eq!(frame.frame_timestamp().unwrap(), Some(1_000_000));
+        assert_eq!(frame.backend_timestamp().unwrap(), Some(1_600_000_000_000));
+        assert_eq!(frame.time_of_arrival().unwrap(), Some(1_600_000_000_003));
+        assert_eq!(frame.actual_exposure().unwrap(), Some(8500));
+        assert_eq!(frame.gain_level().unwrap(), None);
+    }
+
+    #[test]
+    fn negative_typed_metadata_is_unsupported() {
+        let mut metadata = HashMap::new();
+        metadata.insert(Rs2FrameMetadata::GainLevel, -1);
+        let frame = MockFrame {
+            metadata,
+            ..Default::default()
+        };
+
+        assert_eq!(frame.gain_level().unwrap(), None);
+    }
+
+    #[test]
+    fn unsupported_metadata_is_not_an_error() {
+        let mut metadata = HashMap::new();
+        metadata.insert(Rs2FrameMetadata::FrameCounter, 42);
+        let frame = MockFrame {
+            metadata,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            frame.try_metadata(Rs2FrameMetadata::FrameCounter).unwrap(),
+            Some(42)
+        );
+        assert_eq!(
+            frame
+                .try_metadata(Rs2FrameMetadata::ActualExposure)
+                .unwrap(),
+            None
+        );
+        assert_eq!(frame.metadata(Rs2FrameMetadata::ActualExposure), None);
+    }
+
+    #[test]
+    fn metadata_iter_of_frame_without_metadata_is_empty() {
+        assert_eq!(MockFrame::default().metadata_iter().count(), 0);
+    }
+}
@@ -0,0 +1,151 @@
+//! Type for detecting dropped and out-of-order frames of a stream.
+
+use super::prelude::FrameEx;
+
+/// The outcome of observing a frame with a [`FrameSequenceMonitor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SequenceStatus {
+    /// The frame directly follows the previously observed frame, or is the first frame observed.
+    Ok,
+    /// The given number of frames were dropped between the previously observed frame and this one.
+    Dropped(u64),
+    /// The frame does not come after the previously observed frame, i.e. it was delivered out of
+    /// order or more than once.
+    OutOfOrder,
+}
+
+/// Tracks the frame numbers of a stream to detect dropped and out-of-order frames.
+///
+/// librealsense2 numbers the frames of each stream consecutively, so a gap between the
+/// [frame numbers](FrameEx::frame_number) of two successive frames means that the frames in
+/// between were lost, e.g. because the host did not keep up, or the frame pool was exhausted.
+///
+/// A monitor should only observe the frames of a single stream, since frame numbers of different
+/// streams are unrelated.
+///
+/// ```no_run
+/// use realsense_rust::frame::{DepthFrame, FrameSequenceMonitor, SequenceStatus};
+///
+/// # fn on_frame(frame: &DepthFrame, monitor: &mut FrameSequenceMonitor) {
+/// if let SequenceStatus::Dropped(n) = monitor.observe(frame) {
+///     eprintln!("Dropped {} depth frames", n);
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FrameSequenceMonitor {
+    /// The number of the most recent frame that was observed in order.
+    last_frame_number: Option<u64>,
+    /// The total number of frames dropped since the monitor was constructed or reset.
+    dropped_frames: u64,
+    /// The total number of frames observed out of order since the monitor was constructed or
+    /// reset.
+    out_of_order_frames: u64,
+}
+
+impl FrameSequenceMonitor {
+    /// Constructs a monitor which has not observed any frames yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Observes the next frame of the stream, and reports whether any frames were dropped before
+    /// it.
+    pub fn observe<F>(&mut self, frame: &F) -> SequenceStatus
+    where
+        F: FrameEx,
+    {
+        self.observe_frame_number(frame.frame_number())
+    }
+
+    /// Observes the number of the next frame of the stream.
+    ///
+    /// This is the same as [`observe`](Self::observe), for when only the frame number is at hand,
+    /// e.g. after the frame has been handed off to another thread.
+    ///
+    /// A frame that is observed out of order does not count towards the dropped frames, nor does
+    /// it affect which frame is expected next. If a frame arrives late, the frames it was waiting
+    /// on have already been counted as dropped.
+    pub fn observe_frame_number(&mut self, frame_number: u64) -> SequenceStatus {
+        let status = match self.last_frame_number {
+            None => SequenceStatus::Ok,
+            Some(last) if frame_number <= last => SequenceStatus::OutOfOrder,
+            Some(last) if frame_number == last + 1 => SequenceStatus::Ok,
+            Some(last) => SequenceStatus::Dropped(frame_number - last - 1),
+        };
+
+        match status {
+            SequenceStatus::OutOfOrder => self.out_of_order_frames += 1,
+            SequenceStatus::Dropped(n) => {
+                self.dropped_frames += n;
+                self.last_frame_number = Some(frame_number);
+            }
+            SequenceStatus::Ok => self.last_frame_number = Some(frame_number),
+        }
+        status
+    }
+
+    /// Gets the number of the most recent frame that was observed in order, if any.
+    pub fn last_frame_number(&self) -> Option<u64> {
+        self.last_frame_number
+    }
+
+    /// Gets the total number of frames dropped since the monitor was constructed or reset.
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped_frames
+    }
+
+    /// Gets the total number of frames observed out of order since the monitor was constructed or
+    /// reset.
+    pub fn out_of_order_frames(&self) -> u64 {
+        self.out_of_order_frames
+    }
+
+    /// Forgets every observed frame, e.g. after restarting the stream.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gap_in_frame_numbers_is_reported_as_dropped_frames() {
+        let mut monitor = FrameSequenceMonitor::new();
+
+        assert_eq!(monitor.observe_frame_number(1), SequenceStatus::Ok);
+        assert_eq!(monitor.observe_frame_number(2), SequenceStatus::Ok);
+        assert_eq!(monitor.observe_frame_number(4), SequenceStatus::Dropped(1));
+        assert_eq!(monitor.observe_frame_number(8), SequenceStatus::Dropped(3));
+        assert_eq!(monitor.dropped_frames(), 4);
+        assert_eq!(monitor.last_frame_number(), Some(8));
+    }
+
+    #[test]
+    fn late_and_repeated_frames_are_out_of_order() {
+        let mut monitor = FrameSequenceMonitor::new();
+
+        assert_eq!(monitor.observe_frame_number(10), SequenceStatus::Ok);
+        assert_eq!(monitor.observe_frame_number(12), SequenceStatus::Dropped(1));
+        assert_eq!(monitor.observe_frame_number(11), SequenceStatus::OutOfOrder);
+        assert_eq!(monitor.observe_frame_number(12), SequenceStatus::OutOfOrder);
+        assert_eq!(monitor.observe_frame_number(13), SequenceStatus::Ok);
+        assert_eq!(monitor.out_of_order_frames(), 2);
+        assert_eq!(monitor.dropped_frames(), 1);
+    }
+
+    #[test]
+    fn reset_forgets_observed_frames() {
+        let mut monitor = FrameSequenceMonitor::new();
+        monitor.observe_frame_number(5);
+        monitor.observe_frame_number(7);
+
+        monitor.reset();
+
+        assert_eq!(monitor.last_frame_number(), None);
+        assert_eq!(monitor.dropped_frames(), 0);
+        assert_eq!(monitor.observe_frame_number(1), SequenceStatus::Ok);
+    }
+}
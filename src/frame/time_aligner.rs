@@ -0,0 +1,208 @@
+//! Type for looking up the motion of an IMU stream at the time of another frame.
+
+use super::{motion::MotionFrame, prelude::FrameEx};
+use std::{collections::VecDeque, marker::PhantomData};
+
+/// The motion reported by a motion frame, along with the timestamp of the frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MotionSample {
+    /// The timestamp of the sample, in milliseconds.
+    ///
+    /// See [`FrameEx::timestamp`].
+    pub timestamp: f64,
+    /// The motion vector of the sample, e.g. the
+    /// [rotational velocity](super::GyroFrame::rotational_velocity) of a gyro frame.
+    pub motion: [f32; 3],
+}
+
+impl MotionSample {
+    /// Linearly interpolates between `self` and `other` at `timestamp`.
+    fn lerp(&self, other: &Self, timestamp: f64) -> Self {
+        let weight = ((timestamp - self.timestamp) / (other.timestamp - self.timestamp)) as f32;
+        let mut motion = self.motion;
+        for (m, o) in motion.iter_mut().zip(other.motion.iter()) {
+            *m += (o - *m) * weight;
+        }
+        Self { timestamp, motion }
+    }
+}
+
+/// Buffers the samples of a motion stream, to look up the motion at the time of another frame.
+///
+/// Motion streams run at a much higher rate than video streams, and their samples are not taken
+/// at the same time as video frames. Fusing the two, e.g. for visual-inertial odometry, requires
+/// the motion at the exact timestamp of each video frame, which this interpolates linearly from
+/// the two samples around it.
+///
+/// Samples are copied out of the frames, so the aligner does not hold on to frames of the device.
+/// Once the aligner is full, pushing a sample drops the oldest one, so the capacity should cover
+/// at least the latency between the motion and video streams, e.g. 100 ms worth of samples.
+///
+/// Timestamps are only comparable within the same [domain](FrameEx::timestamp_domain), so the
+/// motion and video streams should both report [`Rs2TimestampDomain::GlobalTime`], which is the
+/// default for devices that support it.
+///
+/// [`Rs2TimestampDomain::GlobalTime`]: crate::kind::Rs2TimestampDomain::GlobalTime
+///
+/// ```no_run
+/// use realsense_rust::frame::{DepthFrame, FrameEx, Gyro, GyroFrame, TimeAligner};
+///
+/// # fn on_frames(gyro_frames: Vec<GyroFrame>, depth_frame: &DepthFrame) {
+/// let mut aligner = TimeAligner::<Gyro>::new(64);
+/// for frame in &gyro_frames {
+///     aligner.push(frame);
+/// }
+/// if let Some(sample) = aligner.sample_at(depth_frame.timestamp()) {
+///     println!("Rotational velocity at depth frame: {:?}", sample.motion);
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TimeAligner<K> {
+    /// The buffered samples, ordered by timestamp from oldest to newest.
+    samples: VecDeque<MotionSample>,
+    /// The maximum number of samples that are buffered.
+    capacity: usize,
+    /// Holds the kind of motion stream that is buffered.
+    _phantom: PhantomData<K>,
+}
+
+impl<K> TimeAligner<K> {
+    /// Constructs an empty aligner which buffers at most `capacity` samples.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Buffers the sample of a motion frame.
+    pub fn push(&mut self, frame: &MotionFrame<K>) {
+        self.push_sample(MotionSample {
+            timestamp: frame.timestamp(),
+            motion: *frame.motion(),
+        });
+    }
+
+    /// Buffers a sample of the motion stream.
+    ///
+    /// This is the same as [`push`](Self::push), for when only the sample is at hand, e.g. after
+    /// the frame has been handed off to another thread. Samples that arrive out of order are
+    /// inserted according to their timestamp. If the aligner is full, the oldest sample is
+    /// dropped, which may be `sample` itself.
+    pub fn push_sample(&mut self, sample: MotionSample) {
+        if self.capacity == 0 {
+            return;
+        }
+        let index = self
+            .samples
+            .iter()
+            .rposition(|s| s.timestamp <= sample.timestamp)
+            .map_or(0, |i| i + 1);
+        self.samples.insert(index, sample);
+        if self.samples.len() > self.capacity {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Gets the motion at `timestamp`, interpolated linearly between the buffered samples around
+    /// it.
+    ///
+    /// Returns `None` if `timestamp` is not between the oldest and newest buffered samples, since
+    /// the motion is not extrapolated. This is the case if the sample after `timestamp` has not
+    /// arrived yet, in which case it is worth trying again after pushing more samples.
+    pub fn sample_at(&self, timestamp: f64) -> Option<MotionSample> {
+        let after = self.samples.iter().position(|s| s.timestamp >= timestamp)?;
+        let next = &self.samples[after];
+        if next.timestamp == timestamp {
+            return Some(*next);
+        }
+        let previous = self.samples.get(after.checked_sub(1)?)?;
+        Some(previous.lerp(next, timestamp))
+    }
+
+    /// Gets the motion at the timestamp of `frame`.
+    ///
+    /// See [`sample_at`](Self::sample_at).
+    pub fn sample_at_frame<F>(&self, frame: &F) -> Option<MotionSample>
+    where
+        F: FrameEx,
+    {
+        self.sample_at(frame.timestamp())
+    }
+
+    /// Gets the buffered samples, ordered by timestamp from oldest to newest.
+    pub fn samples(&self) -> impl Iterator<Item = &MotionSample> {
+        self.samples.iter()
+    }
+
+    /// Gets the number of buffered samples.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Predicate for whether no samples are buffered.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Gets the maximum number of samples that are buffered.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Drops every buffered sample.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::Gyro;
+
+    fn sample(timestamp: f64, motion: [f32; 3]) -> MotionSample {
+        MotionSample { timestamp, motion }
+    }
+
+    #[test]
+    fn interpolates_linearly_between_two_gyro_samples() {
+        let mut aligner = TimeAligner::<Gyro>::new(8);
+        aligner.push_sample(sample(100.0, [0.0, 1.0, -2.0]));
+        aligner.push_sample(sample(110.0, [1.0, 3.0, 2.0]));
+
+        let interpolated = aligner.sample_at(102.5).unwrap();
+        assert_eq!(interpolated.timestamp, 102.5);
+        assert_eq!(interpolated.motion, [0.25, 1.5, -1.0]);
+
+        assert_eq!(aligner.sample_at(100.0).unwrap().motion, [0.0, 1.0, -2.0]);
+        assert_eq!(aligner.sample_at(110.0).unwrap().motion, [1.0, 3.0, 2.0]);
+    }
+
+    #[test]
+    fn does_not_extrapolate() {
+        let mut aligner = TimeAligner::<Gyro>::new(8);
+        assert_eq!(aligner.sample_at(100.0), None);
+
+        aligner.push_sample(sample(100.0, [0.0; 3]));
+        aligner.push_sample(sample(110.0, [1.0; 3]));
+        assert_eq!(aligner.sample_at(99.0), None);
+        assert_eq!(aligner.sample_at(111.0), None);
+    }
+
+    #[test]
+    fn orders_samples_and_drops_the_oldest() {
+        let mut aligner = TimeAligner::<Gyro>::new(3);
+        aligner.push_sample(sample(100.0, [0.0; 3]));
+        aligner.push_sample(sample(120.0, [2.0; 3]));
+        aligner.push_sample(sample(110.0, [1.0; 3]));
+        aligner.push_sample(sample(130.0, [3.0; 3]));
+
+        let timestamps: Vec<f64> = aligner.samples().map(|s| s.timestamp).collect();
+        assert_eq!(timestamps, vec![110.0, 120.0, 130.0]);
+        assert_eq!(aligner.sample_at(115.0).unwrap().motion, [1.5; 3]);
+        assert_eq!(aligner.sample_at(105.0), None);
+    }
+}
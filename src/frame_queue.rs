@@ -1,141 +1,283 @@
 //! Defines the queue type of frames.
+//!
+//! A frame queue is librealsense2's primitive for handing frames from a producer to a consumer,
+//! e.g. from a [sensor callback](crate::sensor::Sensor::start) to a processing thread. This keeps
+//! the producer from blocking while the consumer processes frames.
 
 use crate::{
-    base::DEFAULT_TIMEOUT,
-    error::{Error as RsError, ErrorChecker, Result},
-    frame::{AnyFrame, Frame, GenericFrameEx},
-    frame_kind::FrameKind,
+    check_rs2_error,
+    frame::{CompositeFrame, FrameEx},
+    kind::Rs2Exception,
+    pipeline::FrameWaitError,
 };
+use anyhow::Result;
+use realsense_sys as sys;
+use std::{convert::TryFrom, os::raw::c_void, ptr::NonNull, task::Poll, time::Duration};
+use thiserror::Error;
 
-/// The queue of frames.
+/// Occurs when a frame queue cannot be created.
+#[derive(Error, Debug)]
+#[error("Could not create frame queue. Type: {0}; Reason: {1}")]
+pub struct FrameQueueConstructionError(pub Rs2Exception, pub String);
+
+/// A thread-safe, bounded queue of frames.
+///
+/// Frames of any type can be enqueued, and are dequeued in the order that they were enqueued.
+/// Every method takes `&self`, so the queue can be shared between a producer and a consumer
+/// thread, e.g. by wrapping it in an [`Arc`](std::sync::Arc).
+///
+/// # Capacity
+///
+/// The queue holds at most `capacity` frames. If a frame is enqueued while the queue is full, the
+/// oldest frame in the queue is dropped to make room for it. A consumer that cannot keep up with
+/// the producer will therefore skip frames, rather than blocking the producer.
 #[derive(Debug)]
 pub struct FrameQueue {
-    pub(crate) ptr: NonNull<sys::rs2_frame_queue>,
+    /// A (non-null) pointer to the frame queue.
+    queue_ptr: NonNull<sys::rs2_frame_queue>,
 }
 
+impl Drop for FrameQueue {
+    fn drop(&mut self) {
+        unsafe {
+            sys::rs2_delete_frame_queue(self.queue_ptr.as_ptr());
+        }
+    }
+}
+
+unsafe impl Send for FrameQueue {}
+unsafe impl Sync for FrameQueue {}
+
 impl FrameQueue {
-    /// Creates an instance with given capacity.
-    pub fn with_capacity(capacity: usize) -> Result<Self> {
-        let queue = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_frame_queue(capacity as c_int, checker.inner_mut_ptr());
-            checker.check()?;
-            Self::from_raw(ptr)
-        };
-        Ok(queue)
+    /// Creates a frame queue which holds at most `capacity` frames.
+    ///
+    /// A `capacity` larger than [`i32::MAX`] is treated as [`i32::MAX`], which is the largest
+    /// capacity librealsense2 supports.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameQueueConstructionError`] if the frame queue cannot be created.
+    pub fn new(capacity: usize) -> Result<Self, FrameQueueConstructionError> {
+        let capacity = i32::try_from(capacity).unwrap_or(i32::MAX);
+        let queue_ptr = create_raw_queue(capacity, FrameQueueConstructionError)?;
+        Ok(Self { queue_ptr })
     }
 
-    /// Push a frame to the queue.
-    pub fn enqueue<Kind>(&mut self, frame: Frame<Kind>)
+    /// Pushes a frame to the back of the queue, transferring ownership of the frame to the queue.
+    ///
+    /// If the queue is full, the oldest frame in the queue is dropped.
+    pub fn enqueue<F>(&self, frame: F)
     where
-        Kind: FrameKind,
+        F: FrameEx,
     {
         unsafe {
-            sys::rs2_enqueue_frame(frame.ptr.as_ptr(), self.ptr.cast::<c_void>().as_ptr());
+            sys::rs2_enqueue_frame(
+                frame.get_owned_raw().as_ptr(),
+                self.queue_ptr.as_ptr().cast::<c_void>(),
+            );
         }
     }
 
-    /// Pops a frame from queue.
+    /// Pushes a composite frame to the back of the queue, transferring ownership of the frame to
+    /// the queue.
     ///
-    /// The method blocks until a frame is available.
-    pub fn wait(&mut self, timeout: Option<Duration>) -> Result<AnyFrame> {
-        let timeout_ms = timeout.unwrap_or(DEFAULT_TIMEOUT).as_millis() as c_uint;
-
-        let frame = loop {
-            let mut checker = ErrorChecker::new();
-            let ptr = unsafe {
-                sys::rs2_wait_for_frame(self.ptr.as_ptr(), timeout_ms, checker.inner_mut_ptr())
-            };
-
-            match (timeout, checker.check()) {
-                (None, Err(RsError::Timeout(..))) => continue,
-                tuple => {
-                    let (_, result) = tuple;
-                    result?;
-                }
-            }
+    /// The composite frame can be dequeued again as a [`CompositeFrame`]. If the queue is full,
+    /// the oldest frame in the queue is dropped.
+    pub fn enqueue_composite(&self, frames: CompositeFrame) {
+        unsafe {
+            sys::rs2_enqueue_frame(
+                frames.into_raw().as_ptr(),
+                self.queue_ptr.as_ptr().cast::<c_void>(),
+            );
+        }
+    }
 
-            let frame = unsafe { Frame::from_raw(ptr) };
-            break frame;
-        };
-        Ok(frame)
+    /// Pops the frame at the front of the queue, blocking the calling thread until one is
+    /// available.
+    ///
+    /// If `timeout` is `None`, the [default timeout](crate::base::DEFAULT_TIMEOUT) is applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameWaitError::DidErrorDuringFrameWait`] if an internal error occurs while
+    /// waiting.
+    ///
+    /// Returns [`FrameWaitError::DidTimeoutBeforeFrameArrival`] if no frame is enqueued before
+    /// the timeout.
+    ///
+    /// Returns an error if the frame cannot be converted to `F`. The frame is dropped in that
+    /// case.
+    pub fn wait<F>(&self, timeout: Option<Duration>) -> Result<F>
+    where
+        F: TryFrom<NonNull<sys::rs2_frame>>,
+        F::Error: Into<anyhow::Error>,
+    {
+        unsafe {
+            wait_for_frame(
+                self.queue_ptr,
+                timeout,
+                FrameWaitError::DidErrorDuringFrameWait,
+            )?
+            .ok_or_else(|| FrameWaitError::DidTimeoutBeforeFrameArrival.into())
+        }
     }
 
-    /// Wait for frame asynchronously. It is analogous to [FrameQueue::wait]
-    pub async fn wait_async(&mut self, timeout: Option<Duration>) -> Result<AnyFrame> {
-        let timeout_ms = timeout
-            .map(|duration| duration.as_millis() as c_uint)
-            .unwrap_or(sys::RS2_DEFAULT_TIMEOUT as c_uint);
-        let (tx, rx) = futures::channel::oneshot::channel();
-        let queue_ptr = AtomicPtr::new(self.ptr.as_ptr());
-
-        thread::spawn(move || {
-            let result = unsafe {
-                loop {
-                    let mut checker = ErrorChecker::new();
-                    let ptr = sys::rs2_wait_for_frame(
-                        queue_ptr.load(Ordering::Relaxed),
-                        timeout_ms,
-                        checker.inner_mut_ptr(),
-                    );
-                    let result = match (timeout, checker.check()) {
-                        (None, Err(RsError::Timeout(..))) => continue,
-                        (_, result) => result.map(|_| Frame::from_raw(ptr)),
-                    };
-                    break result;
-                }
-            };
-            let _ = tx.send(result);
-        });
-
-        let frame = rx.await.unwrap()?;
-        Ok(frame)
+    /// Pops the frame at the front of the queue without blocking.
+    ///
+    /// Returns [`Poll::Pending`] if the queue is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameWaitError::DidErrorDuringFramePoll`] if an internal error occurs while
+    /// polling.
+    ///
+    /// Returns an error if the frame cannot be converted to `F`. The frame is dropped in that
+    /// case.
+    pub fn poll<F>(&self) -> Result<Poll<F>>
+    where
+        F: TryFrom<NonNull<sys::rs2_frame>>,
+        F::Error: Into<anyhow::Error>,
+    {
+        unsafe { poll_for_frame(self.queue_ptr, FrameWaitError::DidErrorDuringFramePoll) }
     }
 
-    /// Try to pop a frame and returns immediately.
-    pub fn try_wait(&mut self) -> Result<Option<AnyFrame>> {
+    /// Gets the number of frames currently held in the queue.
+    ///
+    /// Returns zero if the size of the queue cannot be determined.
+    pub fn len(&self) -> usize {
         unsafe {
-            let mut checker = ErrorChecker::new();
-            let mut ptr: *mut sys::rs2_frame = ptr::null_mut();
-            let ret = sys::rs2_poll_for_frame(
-                self.ptr.as_ptr(),
-                &mut ptr as *mut _,
-                checker.inner_mut_ptr(),
-            );
-            checker.check()?;
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let len = sys::rs2_frame_queue_size(self.queue_ptr.as_ptr(), &mut err);
 
-            if ret != 0 {
-                let frame = Frame::from_raw(ptr);
-                Ok(Some(frame))
+            if err.as_ref().is_none() {
+                len as usize
             } else {
-                Ok(None)
+                sys::rs2_free_error(err);
+                0
             }
         }
     }
 
-    pub fn into_raw(self) -> *mut sys::rs2_frame_queue {
-        let ptr = self.ptr;
-        mem::forget(self);
-        ptr.as_ptr()
+    /// Predicate for whether the queue currently holds no frames.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
+}
 
-    pub unsafe fn from_raw(ptr: *mut sys::rs2_frame_queue) -> Self {
-        Self {
-            ptr: NonNull::new(ptr).unwrap(),
-        }
+/// Creates a raw frame queue which holds at most `capacity` frames.
+///
+/// This is shared by [`FrameQueue`] and the queues that processing blocks output to, which report
+/// a failure with their own error types.
+///
+/// # Errors
+///
+/// Returns the error built by `on_error` if the frame queue cannot be created.
+pub(crate) fn create_raw_queue<E>(
+    capacity: i32,
+    on_error: fn(Rs2Exception, String) -> E,
+) -> Result<NonNull<sys::rs2_frame_queue>, E> {
+    unsafe {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        let queue_ptr = sys::rs2_create_frame_queue(capacity, &mut err);
+        check_rs2_error!(err, on_error)?;
+
+        Ok(NonNull::new(queue_ptr).unwrap())
     }
+}
 
-    pub(crate) unsafe fn unsafe_clone(&self) -> Self {
-        Self { ptr: self.ptr }
+/// Pops the frame at the front of a raw frame queue, blocking the calling thread until one is
+/// available.
+///
+/// If `timeout` is `None`, the [default timeout](crate::base::DEFAULT_TIMEOUT) is applied. A
+/// timeout longer than [`u32::MAX`] milliseconds is treated as [`u32::MAX`] milliseconds.
+///
+/// Returns `None` if no frame is enqueued before the timeout.
+///
+/// # Errors
+///
+/// Returns the error built by `on_error` if an internal error occurs while waiting.
+///
+/// Returns an error if the frame cannot be converted to `F`. The frame is dropped in that case.
+///
+/// # Safety
+///
+/// The frame queue must remain valid for the duration of the call.
+pub(crate) unsafe fn wait_for_frame<F, E>(
+    queue_ptr: NonNull<sys::rs2_frame_queue>,
+    timeout: Option<Duration>,
+    on_error: fn(Rs2Exception, String) -> E,
+) -> Result<Option<F>>
+where
+    F: TryFrom<NonNull<sys::rs2_frame>>,
+    F::Error: Into<anyhow::Error>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let timeout_ms = match timeout {
+        Some(d) => u32::try_from(d.as_millis()).unwrap_or(u32::MAX),
+        None => sys::RS2_DEFAULT_TIMEOUT,
+    };
+
+    let mut err = std::ptr::null_mut::<sys::rs2_error>();
+    let mut frame_ptr = std::ptr::null_mut::<sys::rs2_frame>();
+    let did_get_frame =
+        sys::rs2_try_wait_for_frame(queue_ptr.as_ptr(), timeout_ms, &mut frame_ptr, &mut err);
+    check_rs2_error!(err, on_error)?;
+
+    if did_get_frame != 0 {
+        Ok(Some(frame_from_raw(NonNull::new(frame_ptr).unwrap())?))
+    } else {
+        Ok(None)
     }
 }
 
-impl Drop for FrameQueue {
-    fn drop(&mut self) {
-        unsafe {
-            sys::rs2_delete_frame_queue(self.ptr.as_ptr());
-        }
+/// Pops the frame at the front of a raw frame queue without blocking.
+///
+/// Returns [`Poll::Pending`] if the queue is empty.
+///
+/// # Errors
+///
+/// Returns the error built by `on_error` if an internal error occurs while polling.
+///
+/// Returns an error if the frame cannot be converted to `F`. The frame is dropped in that case.
+///
+/// # Safety
+///
+/// The frame queue must remain valid for the duration of the call.
+pub(crate) unsafe fn poll_for_frame<F, E>(
+    queue_ptr: NonNull<sys::rs2_frame_queue>,
+    on_error: fn(Rs2Exception, String) -> E,
+) -> Result<Poll<F>>
+where
+    F: TryFrom<NonNull<sys::rs2_frame>>,
+    F::Error: Into<anyhow::Error>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut err = std::ptr::null_mut::<sys::rs2_error>();
+    let mut frame_ptr = std::ptr::null_mut::<sys::rs2_frame>();
+    let did_get_frame = sys::rs2_poll_for_frame(queue_ptr.as_ptr(), &mut frame_ptr, &mut err);
+    check_rs2_error!(err, on_error)?;
+
+    if did_get_frame != 0 {
+        Ok(Poll::Ready(frame_from_raw(
+            NonNull::new(frame_ptr).unwrap(),
+        )?))
+    } else {
+        Ok(Poll::Pending)
     }
 }
 
-unsafe impl Send for FrameQueue {}
+/// Converts a dequeued frame to `F`, releasing the frame if the conversion fails.
+///
+/// # Safety
+///
+/// The frame pointer must be owned by the caller, e.g. freshly dequeued from a frame queue.
+unsafe fn frame_from_raw<F>(frame_ptr: NonNull<sys::rs2_frame>) -> Result<F>
+where
+    F: TryFrom<NonNull<sys::rs2_frame>>,
+    F::Error: Into<anyhow::Error>,
+{
+    F::try_from(frame_ptr).map_err(|e| {
+        sys::rs2_release_frame(frame_ptr.as_ptr());
+        e.into()
+    })
+}
@@ -34,9 +34,12 @@
 //! approach that this is unlikely to be something that is undertaken, so we don't provide any
 //! direct means to iterate through every value of each enum variant.
 //!
-//! If you truly need to do this, let us know! There are crates that make this possible and
-//! integrate well with native Rust iterators, but we have chosen to avoid the extra depedencies
-//! for this crate.
+//! The exceptions are [`Rs2StreamKind::all`] and [`Rs2Format::all`], which are commonly needed to
+//! let users pick streams and formats by name, e.g. on the command line.
+//!
+//! If you truly need to do this for other enums, let us know! There are crates that make this
+//! possible and integrate well with native Rust iterators, but we have chosen to avoid the extra
+//! depedencies for this crate.
 //!
 //! If you need a workaround, you can always use the lower-level `realsense-sys` API:
 //!
@@ -57,31 +60,40 @@
 
 mod camera_info;
 mod color_scheme;
+mod digital_gain;
 mod distortion_model;
 mod exception;
 mod extension;
 mod format;
 mod frame_metadata;
 mod hole_filling;
+mod log_severity;
+mod notification_category;
 mod option;
 mod persistence_control;
 mod product_line;
 mod stream_kind;
 mod timestamp_domain;
+mod visual_preset;
 
 pub use camera_info::Rs2CameraInfo;
 pub use color_scheme::ColorScheme;
+pub use digital_gain::Rs2DigitalGain;
 pub use distortion_model::Rs2DistortionModel;
 pub use exception::Rs2Exception;
 pub use extension::{
     Rs2Extension, DEVICE_EXTENSIONS, FILTER_EXTENSIONS, FRAME_EXTENSIONS, MISC_EXTENSIONS,
     PROFILE_EXTENSIONS, SENSOR_EXTENSIONS,
 };
-pub use format::Rs2Format;
+pub use format::{ParseFormatError, Rs2Format};
 pub use frame_metadata::Rs2FrameMetadata;
 pub use hole_filling::HoleFillingMode;
-pub use option::{OptionSetError, Rs2Option, Rs2OptionRange};
+pub use log_severity::Rs2LogSeverity;
+pub use notification_category::Rs2NotificationCategory;
+pub use option::{OptionSetError, OutOfRangeMode, Rs2Option, Rs2OptionRange};
 pub use persistence_control::PersistenceControl;
+pub(crate) use product_line::product_line_mask;
 pub use product_line::Rs2ProductLine;
-pub use stream_kind::Rs2StreamKind;
+pub use stream_kind::{ParseStreamKindError, Rs2StreamKind};
 pub use timestamp_domain::Rs2TimestampDomain;
+pub use visual_preset::Rs2VisualPreset;
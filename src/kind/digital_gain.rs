@@ -0,0 +1,65 @@
+//! Enumeration of digital gain settings for L500 series devices.
+//!
+//! The digital gain is applied to the depth sensor through the
+//! [`Rs2Option::DigitalGain`](super::Rs2Option) option, which librealsense2 represents as a float
+//! like every other option.
+
+use num_derive::{FromPrimitive, ToPrimitive};
+use realsense_sys as sys;
+use std::{ffi::CStr, fmt};
+
+/// Enumeration of the digital gain settings of the depth sensor of L500 series devices.
+#[repr(i32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rs2DigitalGain {
+    /// The gain is selected by the device according to the ambient light.
+    Auto = sys::rs2_digital_gain_RS2_DIGITAL_GAIN_AUTO as i32,
+    /// High gain, which favors range and is best suited to low ambient light.
+    High = sys::rs2_digital_gain_RS2_DIGITAL_GAIN_HIGH as i32,
+    /// Low gain, which avoids saturation and is best suited to high ambient light.
+    Low = sys::rs2_digital_gain_RS2_DIGITAL_GAIN_LOW as i32,
+}
+
+impl Rs2DigitalGain {
+    /// Get the digital gain variant as a `&CStr`
+    pub fn as_cstr(&self) -> &'static CStr {
+        unsafe {
+            let ptr = sys::rs2_digital_gain_to_string(*self as sys::rs2_digital_gain);
+            CStr::from_ptr(ptr)
+        }
+    }
+
+    /// Get the digital gain variant as a `&str`
+    pub fn as_str(&self) -> &'static str {
+        self.as_cstr().to_str().unwrap()
+    }
+}
+
+impl fmt::Display for Rs2DigitalGain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<Rs2DigitalGain> for f32 {
+    fn from(gain: Rs2DigitalGain) -> Self {
+        gain as i32 as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::FromPrimitive;
+
+    #[test]
+    fn option_values_round_trip() {
+        for gain in &[
+            Rs2DigitalGain::Auto,
+            Rs2DigitalGain::High,
+            Rs2DigitalGain::Low,
+        ] {
+            assert_eq!(Rs2DigitalGain::from_f32(f32::from(*gain)), Some(*gain));
+        }
+    }
+}
@@ -14,6 +14,7 @@ use realsense_sys as sys;
 /// An enum for the various kinds of distortion models provided by librealsense2.
 #[repr(i32)]
 #[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Rs2DistortionModel {
     /// Rectilinear images. No distortion compensation required.
     None = sys::rs2_distortion_RS2_DISTORTION_NONE as i32,
@@ -47,4 +48,19 @@ mod tests {
             );
         }
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn distortion_models_serialize_as_their_names() {
+        for variant in (0..sys::rs2_distortion_RS2_DISTORTION_COUNT as i32)
+            .filter_map(Rs2DistortionModel::from_i32)
+        {
+            let json = serde_json::to_string(&variant).unwrap();
+            assert_eq!(json, format!("\"{:?}\"", variant));
+            assert_eq!(
+                serde_json::from_str::<Rs2DistortionModel>(&json).unwrap(),
+                variant
+            );
+        }
+    }
 }
@@ -10,6 +10,7 @@ use std::fmt::{Display, Formatter, Result};
 /// [`rs2_error`](realsense_sys::rs2_error) pointer.
 #[repr(i32)]
 #[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Rs2Exception {
     /// Unknown error classification.
     ///
@@ -48,6 +49,31 @@ pub enum Rs2Exception {
     // Count = sys::rs2_exception_type_RS2_EXCEPTION_TYPE_COUNT,
 }
 
+impl Rs2Exception {
+    /// Predicate for whether an operation that failed with this exception may succeed if retried.
+    ///
+    /// Exceptions caused by the environment, such as the device being disconnected or an IO
+    /// failure, are recoverable: retrying the operation (possibly after the device reconnects) can
+    /// succeed. Exceptions caused by how the API was used, such as passing an invalid value or
+    /// calling functions in the wrong order, are not, and will keep failing until the calling code
+    /// is fixed. The same goes for a device in recovery mode, which needs a firmware update.
+    ///
+    /// [`Rs2Exception::Unknown`] is considered unrecoverable, since nothing is known about the
+    /// failure.
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            Rs2Exception::CameraDisconnected
+            | Rs2Exception::Backend
+            | Rs2Exception::IoDeviceFailure => true,
+            Rs2Exception::Unknown
+            | Rs2Exception::InvalidValue
+            | Rs2Exception::WrongApiCallSequence
+            | Rs2Exception::NotImplemented
+            | Rs2Exception::DeviceInRecoveryMode => false,
+        }
+    }
+}
+
 impl Display for Rs2Exception {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         let s = match self {
@@ -80,4 +106,32 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn only_environmental_exceptions_are_recoverable() {
+        assert!(Rs2Exception::CameraDisconnected.is_recoverable());
+        assert!(Rs2Exception::Backend.is_recoverable());
+        assert!(Rs2Exception::IoDeviceFailure.is_recoverable());
+
+        assert!(!Rs2Exception::Unknown.is_recoverable());
+        assert!(!Rs2Exception::InvalidValue.is_recoverable());
+        assert!(!Rs2Exception::WrongApiCallSequence.is_recoverable());
+        assert!(!Rs2Exception::NotImplemented.is_recoverable());
+        assert!(!Rs2Exception::DeviceInRecoveryMode.is_recoverable());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn exceptions_serialize_as_their_names() {
+        for variant in (0..sys::rs2_exception_type_RS2_EXCEPTION_TYPE_COUNT as i32)
+            .filter_map(Rs2Exception::from_i32)
+        {
+            let json = serde_json::to_string(&variant).unwrap();
+            assert_eq!(json, format!("\"{:?}\"", variant));
+            assert_eq!(
+                serde_json::from_str::<Rs2Exception>(&json).unwrap(),
+                variant
+            );
+        }
+    }
 }
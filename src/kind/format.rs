@@ -2,6 +2,8 @@
 
 use num_derive::{FromPrimitive, ToPrimitive};
 use realsense_sys as sys;
+use std::{fmt, str::FromStr};
+use thiserror::Error;
 
 /// A type representing all possible data formats for raw frame data
 #[repr(i32)]
@@ -115,6 +117,175 @@ pub enum Rs2Format {
     // Count = sys::rs2_format_RS2_FORMAT_COUNT,
 }
 
+/// Every format, in the order of their librealsense2 values.
+const ALL_FORMATS: [Rs2Format; 31] = [
+    Rs2Format::Any,
+    Rs2Format::Z16,
+    Rs2Format::Disparity16,
+    Rs2Format::Xyz32F,
+    Rs2Format::Yuyv,
+    Rs2Format::Rgb8,
+    Rs2Format::Bgr8,
+    Rs2Format::Rgba8,
+    Rs2Format::Bgra8,
+    Rs2Format::Y8,
+    Rs2Format::Y16,
+    Rs2Format::Raw10,
+    Rs2Format::Raw16,
+    Rs2Format::Raw8,
+    Rs2Format::Uyvy,
+    Rs2Format::MotionRaw,
+    Rs2Format::MotionXyz32F,
+    Rs2Format::GpioRaw,
+    Rs2Format::_6Dof,
+    Rs2Format::Disparity32,
+    Rs2Format::Y10Bpack,
+    Rs2Format::Distance,
+    Rs2Format::Mjpeg,
+    Rs2Format::Y8I,
+    Rs2Format::Y12I,
+    Rs2Format::Inzi,
+    Rs2Format::Invi,
+    Rs2Format::W10,
+    Rs2Format::Z16H,
+    Rs2Format::Fg,
+    Rs2Format::Y411,
+];
+
+impl Rs2Format {
+    /// Gets every format, including [`Any`](Self::Any).
+    ///
+    /// This is useful to e.g. list the accepted values of a command-line argument.
+    pub fn all() -> &'static [Rs2Format] {
+        &ALL_FORMATS
+    }
+
+    /// Get the format variant as a `&str`, e.g. `"Z16"`.
+    ///
+    /// These are the names that librealsense2 uses for formats, which are also shown by e.g. the
+    /// RealSense Viewer.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Rs2Format::Any => "ANY",
+            Rs2Format::Z16 => "Z16",
+            Rs2Format::Disparity16 => "DISPARITY16",
+            Rs2Format::Xyz32F => "XYZ32F",
+            Rs2Format::Yuyv => "YUYV",
+            Rs2Format::Rgb8 => "RGB8",
+            Rs2Format::Bgr8 => "BGR8",
+            Rs2Format::Rgba8 => "RGBA8",
+            Rs2Format::Bgra8 => "BGRA8",
+            Rs2Format::Y8 => "Y8",
+            Rs2Format::Y16 => "Y16",
+            Rs2Format::Raw10 => "RAW10",
+            Rs2Format::Raw16 => "RAW16",
+            Rs2Format::Raw8 => "RAW8",
+            Rs2Format::Uyvy => "UYVY",
+            Rs2Format::MotionRaw => "MOTION_RAW",
+            Rs2Format::MotionXyz32F => "MOTION_XYZ32F",
+            Rs2Format::GpioRaw => "GPIO_RAW",
+            Rs2Format::_6Dof => "6DOF",
+            Rs2Format::Disparity32 => "DISPARITY32",
+            Rs2Format::Y10Bpack => "Y10BPACK",
+            Rs2Format::Distance => "DISTANCE",
+            Rs2Format::Mjpeg => "MJPEG",
+            Rs2Format::Y8I => "Y8I",
+            Rs2Format::Y12I => "Y12I",
+            Rs2Format::Inzi => "INZI",
+            Rs2Format::Invi => "INVI",
+            Rs2Format::W10 => "W10",
+            Rs2Format::Z16H => "Z16H",
+            Rs2Format::Fg => "FG",
+            Rs2Format::Y411 => "Y411",
+        }
+    }
+
+    /// Get the number of bytes that each pixel of the format takes up.
+    ///
+    /// For motion and pointcloud formats, a "pixel" is a single sample or point.
+    ///
+    /// Returns `None` for formats that do not have a fixed number of whole bytes per pixel. This
+    /// includes [`Any`](Self::Any), bit-packed formats such as [`Raw10`](Self::Raw10) and
+    /// [`Y411`](Self::Y411), compressed formats such as [`Mjpeg`](Self::Mjpeg), and raw or
+    /// multi-planar formats whose layout depends on the device.
+    pub fn bytes_per_pixel(&self) -> Option<usize> {
+        match self {
+            Rs2Format::Y8 | Rs2Format::Raw8 | Rs2Format::Invi => Some(1),
+            Rs2Format::Z16
+            | Rs2Format::Disparity16
+            | Rs2Format::Y16
+            | Rs2Format::Raw16
+            | Rs2Format::Y10Bpack
+            | Rs2Format::Fg
+            | Rs2Format::Y8I
+            | Rs2Format::Yuyv
+            | Rs2Format::Uyvy => Some(2),
+            Rs2Format::Rgb8 | Rs2Format::Bgr8 | Rs2Format::Y12I => Some(3),
+            Rs2Format::Rgba8 | Rs2Format::Bgra8 | Rs2Format::Distance | Rs2Format::Disparity32 => {
+                Some(4)
+            }
+            Rs2Format::Xyz32F | Rs2Format::MotionXyz32F => Some(12),
+            Rs2Format::Any
+            | Rs2Format::MotionRaw
+            | Rs2Format::GpioRaw
+            | Rs2Format::Mjpeg
+            | Rs2Format::Inzi
+            | Rs2Format::_6Dof
+            | Rs2Format::Raw10
+            | Rs2Format::W10
+            | Rs2Format::Z16H
+            | Rs2Format::Y411 => None,
+        }
+    }
+
+    /// Get the number of values (channels) that make up each pixel of the format.
+    ///
+    /// For example, RGB8 has three channels, Z16 has one, and the interleaved infrared formats
+    /// have two (left and right).
+    ///
+    /// Returns `None` for every format without a fixed [number of bytes per
+    /// pixel](Self::bytes_per_pixel), as well as for the chroma-subsampled
+    /// [`Yuyv`](Self::Yuyv) and [`Uyvy`](Self::Uyvy) formats, where pixels share their colour
+    /// values with their neighbours.
+    pub fn channels(&self) -> Option<usize> {
+        match self {
+            Rs2Format::Yuyv | Rs2Format::Uyvy => None,
+            Rs2Format::Y8I | Rs2Format::Y12I => Some(2),
+            Rs2Format::Rgb8 | Rs2Format::Bgr8 | Rs2Format::Xyz32F | Rs2Format::MotionXyz32F => {
+                Some(3)
+            }
+            Rs2Format::Rgba8 | Rs2Format::Bgra8 => Some(4),
+            _ => self.bytes_per_pixel().map(|_| 1),
+        }
+    }
+}
+
+impl fmt::Display for Rs2Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Occurs when a string cannot be parsed as a [`Rs2Format`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Could not parse \"{0}\" as a format.")]
+pub struct ParseFormatError(pub String);
+
+impl FromStr for Rs2Format {
+    type Err = ParseFormatError;
+
+    /// Parses the [name](Self::as_str) of a format, ignoring case, e.g. `"rgb8"`.
+    ///
+    /// This makes it straightforward to take formats as command-line arguments.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|format| format.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseFormatError(s.to_owned()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,4 +301,57 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn common_formats_have_expected_pixel_sizes() {
+        assert_eq!(Rs2Format::Z16.bytes_per_pixel(), Some(2));
+        assert_eq!(Rs2Format::Y8.bytes_per_pixel(), Some(1));
+        assert_eq!(Rs2Format::Y16.bytes_per_pixel(), Some(2));
+        assert_eq!(Rs2Format::Rgb8.bytes_per_pixel(), Some(3));
+        assert_eq!(Rs2Format::Bgra8.bytes_per_pixel(), Some(4));
+        assert_eq!(Rs2Format::Yuyv.bytes_per_pixel(), Some(2));
+        assert_eq!(Rs2Format::Distance.bytes_per_pixel(), Some(4));
+        assert_eq!(Rs2Format::Xyz32F.bytes_per_pixel(), Some(12));
+        assert_eq!(Rs2Format::Raw10.bytes_per_pixel(), None);
+        assert_eq!(Rs2Format::Any.bytes_per_pixel(), None);
+    }
+
+    #[test]
+    fn common_formats_have_expected_channels() {
+        assert_eq!(Rs2Format::Z16.channels(), Some(1));
+        assert_eq!(Rs2Format::Y8.channels(), Some(1));
+        assert_eq!(Rs2Format::Rgb8.channels(), Some(3));
+        assert_eq!(Rs2Format::Rgba8.channels(), Some(4));
+        assert_eq!(Rs2Format::Y8I.channels(), Some(2));
+        assert_eq!(Rs2Format::Xyz32F.channels(), Some(3));
+        assert_eq!(Rs2Format::Yuyv.channels(), None);
+        assert_eq!(Rs2Format::Raw10.channels(), None);
+    }
+
+    #[test]
+    fn all_contains_every_variant_once() {
+        let all = Rs2Format::all();
+        assert_eq!(all.len(), sys::rs2_format_RS2_FORMAT_COUNT as usize);
+        for (i, format) in all.iter().enumerate() {
+            assert_eq!(Rs2Format::from_i32(i as i32), Some(*format));
+        }
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for format in Rs2Format::all() {
+            assert_eq!(format.as_str().parse::<Rs2Format>(), Ok(*format));
+            assert_eq!(format.to_string(), format.as_str());
+        }
+    }
+
+    #[test]
+    fn names_are_parsed_ignoring_case() {
+        assert_eq!("z16".parse::<Rs2Format>(), Ok(Rs2Format::Z16));
+        assert_eq!("Motion_Xyz32F".parse(), Ok(Rs2Format::MotionXyz32F));
+        assert_eq!(
+            "h264".parse::<Rs2Format>(),
+            Err(ParseFormatError("h264".to_owned()))
+        );
+    }
 }
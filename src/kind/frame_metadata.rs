@@ -10,6 +10,7 @@ use realsense_sys as sys;
 ///
 #[repr(i32)]
 #[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Rs2FrameMetadata {
     /// A sequential index managed per-stream, counting up from the first frame at zero.
     FrameCounter = sys::rs2_frame_metadata_value_RS2_FRAME_METADATA_FRAME_COUNTER as i32,
@@ -173,4 +174,19 @@ mod tests {
             );
         }
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn frame_metadata_round_trips_through_serde() {
+        for variant in (0..sys::rs2_frame_metadata_value_RS2_FRAME_METADATA_COUNT as i32)
+            .filter_map(Rs2FrameMetadata::from_i32)
+        {
+            let json = serde_json::to_string(&variant).unwrap();
+            assert_eq!(json, format!("\"{:?}\"", variant));
+            assert_eq!(
+                serde_json::from_str::<Rs2FrameMetadata>(&json).unwrap(),
+                variant
+            );
+        }
+    }
 }
@@ -0,0 +1,68 @@
+//! Enumeration describing the severity of log messages and notifications from librealsense2.
+
+use num_derive::{FromPrimitive, ToPrimitive};
+use realsense_sys as sys;
+use std::{ffi::CStr, fmt};
+
+/// Enumeration of possible severities of log messages and notifications.
+#[repr(i32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rs2LogSeverity {
+    /// Detailed information about ordinary operations
+    Debug = sys::rs2_log_severity_RS2_LOG_SEVERITY_DEBUG as i32,
+    /// Terse information about ordinary operations
+    Info = sys::rs2_log_severity_RS2_LOG_SEVERITY_INFO as i32,
+    /// Indication of possible failure
+    Warn = sys::rs2_log_severity_RS2_LOG_SEVERITY_WARN as i32,
+    /// Indication of definite failure
+    Error = sys::rs2_log_severity_RS2_LOG_SEVERITY_ERROR as i32,
+    /// Indication of unrecoverable failure
+    Fatal = sys::rs2_log_severity_RS2_LOG_SEVERITY_FATAL as i32,
+    /// No logging will occur
+    None = sys::rs2_log_severity_RS2_LOG_SEVERITY_NONE as i32,
+    /* Not included since this just tells us the total number of severities
+     *
+     * Count = sys::rs2_log_severity_RS2_LOG_SEVERITY_COUNT,
+     *
+     * Not included since this aliases `Debug`
+     *
+     * All = sys::rs2_log_severity_RS2_LOG_SEVERITY_ALL, */
+}
+
+impl Rs2LogSeverity {
+    /// Get the log severity variant as a `&CStr`
+    pub fn as_cstr(&self) -> &'static CStr {
+        unsafe {
+            let ptr = sys::rs2_log_severity_to_string(*self as sys::rs2_log_severity);
+            CStr::from_ptr(ptr)
+        }
+    }
+
+    /// Get the log severity variant as a `&str`
+    pub fn as_str(&self) -> &'static str {
+        self.as_cstr().to_str().unwrap()
+    }
+}
+
+impl fmt::Display for Rs2LogSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::FromPrimitive;
+
+    #[test]
+    fn all_variants_exist() {
+        for i in 0..sys::rs2_log_severity_RS2_LOG_SEVERITY_COUNT as i32 {
+            assert!(
+                Rs2LogSeverity::from_i32(i).is_some(),
+                "Rs2LogSeverity variant for ordinal {} does not exist.",
+                i,
+            );
+        }
+    }
+}
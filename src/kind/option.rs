@@ -27,6 +27,26 @@ pub enum OptionSetError {
     /// The requested option could not be set. Reason is reported by the sensor.
     #[error("Could not set option. Type: {0}; Reason: {1}")]
     CouldNotSetOption(Rs2Exception, String),
+    /// The value is outside of the range of values that the option accepts.
+    #[error("Value {value} is outside of the option range [{min}, {max}].")]
+    ValueOutOfRange {
+        /// The value that was requested.
+        value: f32,
+        /// The minimum value that the option accepts.
+        min: f32,
+        /// The maximum value that the option accepts.
+        max: f32,
+    },
+}
+
+/// How to handle a value outside of the [range](Rs2OptionRange) of an option when setting it with
+/// [`Sensor::set_option_checked`](crate::sensor::Sensor::set_option_checked).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OutOfRangeMode {
+    /// Clamp the value to the nearest end of the range.
+    Clamp,
+    /// Reject the value with [`OptionSetError::ValueOutOfRange`].
+    Reject,
 }
 
 /// The enumeration of options available in the RealSense SDK.
@@ -74,6 +94,7 @@ pub enum OptionSetError {
 /// option)."
 #[repr(i32)]
 #[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Rs2Option {
     /// Enable/disable color backlight compensation.
     BacklightCompensation = sys::rs2_option_RS2_OPTION_BACKLIGHT_COMPENSATION as i32,
@@ -330,11 +351,157 @@ pub struct Rs2OptionRange {
     pub default: f32,
 }
 
+impl Rs2OptionRange {
+    /// Predicate for whether `value` is between the minimum and maximum of the range, inclusive.
+    pub fn contains(&self, value: f32) -> bool {
+        self.min <= value && value <= self.max
+    }
+
+    /// Iterates over the values accepted by an option with discrete values, from the minimum to
+    /// the maximum in increments of the step.
+    ///
+    /// This is useful to e.g. build a dropdown for options such as the laser power or visual
+    /// preset. If the range does not divide evenly into steps, the last value is the last step
+    /// below the maximum. For options which accept continuous values, i.e. whose step is zero,
+    /// only the minimum and maximum are yielded.
+    pub fn values(&self) -> impl Iterator<Item = f32> {
+        let (min, max) = (f64::from(self.min), f64::from(self.max));
+        let step = f64::from(self.step);
+
+        let (count, step) = if step > 0.0 && step.is_finite() {
+            let steps = (max - min) / step;
+            // Tolerate rounding errors in the range, so that the maximum is not missed.
+            let steps = if (steps - steps.round()).abs() < 1e-3 {
+                steps.round()
+            } else {
+                steps.floor()
+            };
+            (steps.max(0.0) as usize + 1, step)
+        } else if max > min {
+            (2, max - min)
+        } else {
+            (1, 0.0)
+        };
+
+        (0..count).map(move |i| (min + i as f64 * step).min(max) as f32)
+    }
+
+    /// Checks `value` against the range, and gets the value to set according to `mode`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptionSetError::ValueOutOfRange`] if `value` is outside of the range and `mode`
+    /// is [`OutOfRangeMode::Reject`], or if `value` is NaN.
+    pub fn check(&self, value: f32, mode: OutOfRangeMode) -> Result<f32, OptionSetError> {
+        if self.contains(value) {
+            return Ok(value);
+        }
+        match mode {
+            OutOfRangeMode::Clamp if !value.is_nan() => Ok(value.max(self.min).min(self.max)),
+            _ => Err(OptionSetError::ValueOutOfRange {
+                value,
+                min: self.min,
+                max: self.max,
+            }),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use num_traits::FromPrimitive;
 
+    fn exposure_range() -> Rs2OptionRange {
+        Rs2OptionRange {
+            min: 1.0,
+            max: 10000.0,
+            step: 1.0,
+            default: 33.0,
+        }
+    }
+
+    #[test]
+    fn values_in_range_are_kept() {
+        let range = exposure_range();
+        for mode in [OutOfRangeMode::Clamp, OutOfRangeMode::Reject].iter() {
+            assert_eq!(range.check(1.0, *mode).unwrap(), 1.0);
+            assert_eq!(range.check(500.0, *mode).unwrap(), 500.0);
+            assert_eq!(range.check(10000.0, *mode).unwrap(), 10000.0);
+        }
+    }
+
+    #[test]
+    fn value_above_max_is_clamped_or_rejected() {
+        let range = exposure_range();
+        assert_eq!(
+            range.check(20000.0, OutOfRangeMode::Clamp).unwrap(),
+            10000.0
+        );
+        assert_eq!(range.check(-5.0, OutOfRangeMode::Clamp).unwrap(), 1.0);
+        assert!(matches!(
+            range.check(20000.0, OutOfRangeMode::Reject),
+            Err(OptionSetError::ValueOutOfRange { value, min, max })
+                if value == 20000.0 && min == 1.0 && max == 10000.0
+        ));
+    }
+
+    #[test]
+    fn nan_is_rejected_in_every_mode() {
+        let range = exposure_range();
+        assert!(range.check(f32::NAN, OutOfRangeMode::Clamp).is_err());
+        assert!(range.check(f32::NAN, OutOfRangeMode::Reject).is_err());
+    }
+
+    #[test]
+    fn discrete_range_yields_every_step() {
+        let range = Rs2OptionRange {
+            min: 0.0,
+            max: 3.0,
+            step: 1.0,
+            default: 0.0,
+        };
+        assert_eq!(range.values().count(), 4);
+        assert_eq!(range.values().collect::<Vec<_>>(), vec![0.0, 1.0, 2.0, 3.0]);
+
+        let laser_power = Rs2OptionRange {
+            min: 0.0,
+            max: 360.0,
+            step: 30.0,
+            default: 150.0,
+        };
+        assert_eq!(laser_power.values().count(), 13);
+        assert_eq!(laser_power.values().last(), Some(360.0));
+
+        let uneven = Rs2OptionRange {
+            min: 0.0,
+            max: 1.0,
+            step: 0.3,
+            default: 0.0,
+        };
+        assert_eq!(uneven.values().count(), 4);
+        assert!(uneven.values().all(|value| uneven.contains(value)));
+    }
+
+    #[test]
+    fn continuous_range_yields_min_and_max() {
+        let range = Rs2OptionRange {
+            min: 0.5,
+            max: 4.0,
+            step: 0.0,
+            default: 1.0,
+        };
+        assert_eq!(range.values().collect::<Vec<_>>(), vec![0.5, 4.0]);
+
+        let single = Rs2OptionRange {
+            min: 1.0,
+            max: 1.0,
+            step: 0.0,
+            default: 1.0,
+        };
+        assert_eq!(single.values().collect::<Vec<_>>(), vec![1.0]);
+    }
+
     #[test]
     fn all_variants_exist() {
         let deprecated_options = vec![
@@ -358,4 +525,15 @@ mod tests {
             );
         }
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn options_round_trip_through_serde() {
+        for variant in (0..sys::rs2_option_RS2_OPTION_COUNT as i32).filter_map(Rs2Option::from_i32)
+        {
+            let json = serde_json::to_string(&variant).unwrap();
+            assert_eq!(json, format!("\"{:?}\"", variant));
+            assert_eq!(serde_json::from_str::<Rs2Option>(&json).unwrap(), variant);
+        }
+    }
 }
@@ -45,3 +45,72 @@ pub enum Rs2ProductLine {
     /// Any device that has a depth feed
     Depth = sys::RS2_PRODUCT_LINE_DEPTH,
 }
+
+impl Rs2ProductLine {
+    /// Any tracking device.
+    ///
+    /// This is an alias of [`Rs2ProductLine::T200`], since both share the same flag.
+    #[allow(non_upper_case_globals)]
+    pub const Tracking: Self = Self::T200;
+
+    /// Predicate for whether every product in the `other` product line also belongs to this one.
+    ///
+    /// For example, [`Rs2ProductLine::Depth`] includes [`Rs2ProductLine::D400`], but not
+    /// [`Rs2ProductLine::Tracking`].
+    pub fn includes(self, other: Rs2ProductLine) -> bool {
+        self as u32 & other as u32 == other as u32
+    }
+}
+
+/// Combines product lines into the bitmask expected by librealsense2.
+///
+/// No product lines apply no filter, i.e. the mask of [`Rs2ProductLine::Any`] is returned.
+pub(crate) fn product_line_mask<I>(product_lines: I) -> i32
+where
+    I: IntoIterator<Item = Rs2ProductLine>,
+{
+    match product_lines
+        .into_iter()
+        .fold(0, |mask, line| mask | line as u32)
+    {
+        0 => Rs2ProductLine::Any as i32,
+        mask => mask as i32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_includes_every_depth_product_line_but_not_tracking() {
+        let depth = Rs2ProductLine::Depth;
+
+        assert!(depth.includes(Rs2ProductLine::D400));
+        assert!(depth.includes(Rs2ProductLine::Sr300));
+        assert!(depth.includes(Rs2ProductLine::L500));
+        assert!(!depth.includes(Rs2ProductLine::Tracking));
+        assert!(Rs2ProductLine::Any.includes(Rs2ProductLine::Tracking));
+    }
+
+    #[test]
+    fn tracking_is_t200() {
+        assert_eq!(
+            Rs2ProductLine::Tracking as u32,
+            sys::RS2_PRODUCT_LINE_TRACKING
+        );
+    }
+
+    #[test]
+    fn product_mask_combines_product_lines() {
+        assert_eq!(
+            product_line_mask(vec![Rs2ProductLine::D400, Rs2ProductLine::Tracking]),
+            (sys::RS2_PRODUCT_LINE_D400 | sys::RS2_PRODUCT_LINE_T200) as i32
+        );
+        assert_eq!(
+            product_line_mask(vec![Rs2ProductLine::Depth]) & sys::RS2_PRODUCT_LINE_TRACKING as i32,
+            0
+        );
+        assert_eq!(product_line_mask(vec![]), sys::RS2_PRODUCT_LINE_ANY as i32);
+    }
+}
@@ -4,6 +4,8 @@
 
 use num_derive::{FromPrimitive, ToPrimitive};
 use realsense_sys as sys;
+use std::{fmt, str::FromStr};
+use thiserror::Error;
 
 /// The enumeration of possible stream kinds.
 ///
@@ -41,9 +43,31 @@ pub enum Rs2StreamKind {
      * Count = sys::rs2_stream_RS2_STREAM_COUNT, */
 }
 
-impl std::fmt::Display for Rs2StreamKind {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let string = match self {
+/// Every stream kind, in the order of their librealsense2 values.
+const ALL_STREAM_KINDS: [Rs2StreamKind; 10] = [
+    Rs2StreamKind::Any,
+    Rs2StreamKind::Depth,
+    Rs2StreamKind::Color,
+    Rs2StreamKind::Infrared,
+    Rs2StreamKind::Fisheye,
+    Rs2StreamKind::Gyro,
+    Rs2StreamKind::Accel,
+    Rs2StreamKind::Gpio,
+    Rs2StreamKind::Pose,
+    Rs2StreamKind::Confidence,
+];
+
+impl Rs2StreamKind {
+    /// Gets every stream kind, including [`Any`](Self::Any).
+    ///
+    /// This is useful to e.g. list the accepted values of a command-line argument.
+    pub fn all() -> &'static [Rs2StreamKind] {
+        &ALL_STREAM_KINDS
+    }
+
+    /// Get the stream kind variant as a `&str`, e.g. `"Depth"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
             Rs2StreamKind::Any => "Any",
             Rs2StreamKind::Depth => "Depth",
             Rs2StreamKind::Color => "Color",
@@ -54,8 +78,33 @@ impl std::fmt::Display for Rs2StreamKind {
             Rs2StreamKind::Gpio => "Gpio",
             Rs2StreamKind::Pose => "Pose",
             Rs2StreamKind::Confidence => "Confidence",
-        };
-        write!(f, "{}", string)
+        }
+    }
+}
+
+impl fmt::Display for Rs2StreamKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Occurs when a string cannot be parsed as a [`Rs2StreamKind`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Could not parse \"{0}\" as a stream kind.")]
+pub struct ParseStreamKindError(pub String);
+
+impl FromStr for Rs2StreamKind {
+    type Err = ParseStreamKindError;
+
+    /// Parses the [name](Self::as_str) of a stream kind, ignoring case, e.g. `"depth"`.
+    ///
+    /// This makes it straightforward to take stream kinds as command-line arguments.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|kind| kind.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseStreamKindError(s.to_owned()))
     }
 }
 
@@ -74,4 +123,39 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn all_contains_every_variant_once() {
+        let all = Rs2StreamKind::all();
+        assert_eq!(all.len(), sys::rs2_stream_RS2_STREAM_COUNT as usize);
+        for (i, kind) in all.iter().enumerate() {
+            assert_eq!(Rs2StreamKind::from_i32(i as i32), Some(*kind));
+        }
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for kind in Rs2StreamKind::all() {
+            assert_eq!(kind.as_str().parse::<Rs2StreamKind>(), Ok(*kind));
+            assert_eq!(kind.to_string(), kind.as_str());
+        }
+    }
+
+    #[test]
+    fn names_are_parsed_ignoring_case() {
+        let kinds: Result<Vec<Rs2StreamKind>, _> =
+            "depth,COLOR,Infrared".split(',').map(str::parse).collect();
+        assert_eq!(
+            kinds,
+            Ok(vec![
+                Rs2StreamKind::Depth,
+                Rs2StreamKind::Color,
+                Rs2StreamKind::Infrared
+            ])
+        );
+        assert_eq!(
+            "thermal".parse::<Rs2StreamKind>(),
+            Err(ParseStreamKindError("thermal".to_owned()))
+        );
+    }
 }
@@ -38,6 +38,12 @@ impl Rs2TimestampDomain {
     pub fn as_str(&self) -> &'static str {
         self.as_cstr().to_str().unwrap()
     }
+
+    /// Predicate for whether timestamps in this domain are measured by the device's internal
+    /// clock, rather than relative to the host's system clock.
+    pub fn is_hardware(&self) -> bool {
+        *self == Rs2TimestampDomain::HardwareClock
+    }
 }
 
 impl ToString for Rs2TimestampDomain {
@@ -61,4 +67,11 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn only_hardware_clock_is_hardware() {
+        assert!(Rs2TimestampDomain::HardwareClock.is_hardware());
+        assert!(!Rs2TimestampDomain::SystemTime.is_hardware());
+        assert!(!Rs2TimestampDomain::GlobalTime.is_hardware());
+    }
 }
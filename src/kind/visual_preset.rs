@@ -0,0 +1,84 @@
+//! Enumeration of visual presets for D400 series devices.
+//!
+//! A visual preset is a set of depth settings optimized for a specific type of usage. Presets are
+//! applied to the depth sensor through the [`Rs2Option::VisualPreset`](super::Rs2Option) option,
+//! which librealsense2 represents as a float like every other option.
+
+use num_derive::{FromPrimitive, ToPrimitive};
+use realsense_sys as sys;
+use std::{ffi::CStr, fmt};
+
+/// Enumeration of the visual presets of the depth sensor of D400 series devices.
+#[repr(i32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rs2VisualPreset {
+    /// Custom settings, i.e. settings that do not match any other preset.
+    Custom = sys::rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_CUSTOM as i32,
+    /// The default settings of the device.
+    Default = sys::rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_DEFAULT as i32,
+    /// Settings optimized for hand and gesture tracking.
+    Hand = sys::rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_HAND as i32,
+    /// Settings that favor accurate depth, at the cost of invalidating more pixels.
+    HighAccuracy = sys::rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_HIGH_ACCURACY as i32,
+    /// Settings that favor filling in as many pixels as possible, at the cost of accuracy.
+    HighDensity = sys::rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_HIGH_DENSITY as i32,
+    /// Settings that balance accuracy and density.
+    MediumDensity = sys::rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_MEDIUM_DENSITY as i32,
+    /// Settings that remove the pattern of the IR projector from the infrared streams.
+    RemoveIrPattern = sys::rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_REMOVE_IR_PATTERN as i32,
+    /* Not included since this just tells us the total number of presets
+     *
+     * Count = sys::rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_COUNT, */
+}
+
+impl Rs2VisualPreset {
+    /// Get the visual preset variant as a `&CStr`
+    pub fn as_cstr(&self) -> &'static CStr {
+        unsafe {
+            let ptr = sys::rs2_rs400_visual_preset_to_string(*self as sys::rs2_rs400_visual_preset);
+            CStr::from_ptr(ptr)
+        }
+    }
+
+    /// Get the visual preset variant as a `&str`
+    pub fn as_str(&self) -> &'static str {
+        self.as_cstr().to_str().unwrap()
+    }
+}
+
+impl fmt::Display for Rs2VisualPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<Rs2VisualPreset> for f32 {
+    fn from(preset: Rs2VisualPreset) -> Self {
+        preset as i32 as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::FromPrimitive;
+
+    #[test]
+    fn all_variants_exist() {
+        for i in 0..sys::rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_COUNT as i32 {
+            assert!(
+                Rs2VisualPreset::from_i32(i).is_some(),
+                "Rs2VisualPreset variant for ordinal {} does not exist.",
+                i,
+            );
+        }
+    }
+
+    #[test]
+    fn option_values_round_trip() {
+        for i in 0..sys::rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_COUNT as i32 {
+            let preset = Rs2VisualPreset::from_i32(i).unwrap();
+            assert_eq!(Rs2VisualPreset::from_f32(f32::from(preset)), Some(preset));
+        }
+    }
+}
@@ -27,6 +27,14 @@
 //! Use these by running `cargo run --features <name of feature>`
 //!
 //! - **buildtime-bindgen**: Generate Rust bindings during build time.
+//! - **async**: Enable streaming frames from a pipeline as an asynchronous `Stream`.
+//! - **content-hash**: Enable hashing the contents of image frames with `xxhash-rust`, e.g. to detect
+//!   duplicate frames.
+//! - **image**: Enable converting image frames to images from the `image` crate.
+//! - **log**: Enable forwarding the internal logging of librealsense2 to the `log` crate.
+//! - **ndarray**: Enable converting depth and points frames to arrays from the `ndarray` crate.
+//! - **rayon**: Enable deprojecting depth frames to points on multiple threads with `rayon`.
+//! - **serde**: Enable serializing calibration, pose data and option enums with `serde`.
 //! - **device-test**: Enable tests that requires connections to RealSense devices.
 //!
 //! ## Regenerating the API Bindings
@@ -79,6 +87,7 @@
 //! Apache 2.0. See [LICENSE](LICENSE) file.
 
 pub mod base;
+pub mod capture;
 pub mod config;
 pub mod context;
 pub mod device;
@@ -86,12 +95,15 @@ pub mod device_hub;
 pub mod docs;
 mod error;
 pub mod frame;
+pub mod frame_queue;
 pub mod kind;
+pub mod log;
 pub mod pipeline;
+pub mod processing_blocks;
 pub mod sensor;
 pub mod stream_profile;
+pub mod version;
 
-// pub mod frame_queue;
 // pub mod processing_block;
 // pub mod processing_block_kind;
 // pub mod processing_block_list;
@@ -101,7 +113,6 @@ pub mod prelude {
     pub use crate::frame::{FrameCategory, FrameEx};
 }
 
-// pub use frame_queue::FrameQueue;
 // pub use processing_block::{
 //     Align, AnyProcessingBlock, Colorizer, DecimationFilter, DisparityFilter, HoleFillingFilter,
 //     HuffmanDepthDecompress, PointCloud, ProcessingBlock, RatesPrinter, SpatialFilter, Syncer,
@@ -0,0 +1,167 @@
+//! Functions for controlling the internal logging of librealsense2.
+//!
+//! librealsense2 does not log anything by default, which can make it difficult to find out why a
+//! device misbehaves. Logging can be directed to the console, to a file, or with the `log` feature
+//! to the `log` crate, and only messages at or above the given minimum severity are logged.
+//!
+//! Logging is global to the process, and applies to every [`Context`](crate::context::Context).
+
+use crate::{base::from_path, check_rs2_error, kind::Rs2Exception, kind::Rs2LogSeverity};
+use anyhow::Result;
+#[cfg(feature = "log")]
+use num_traits::FromPrimitive;
+use realsense_sys as sys;
+use std::path::Path;
+#[cfg(feature = "log")]
+use std::{
+    ffi::CStr,
+    os::raw::c_void,
+    panic::{self, AssertUnwindSafe},
+};
+use thiserror::Error;
+
+/// The target that messages forwarded by [`log_to_callback`] are logged to.
+#[cfg(feature = "log")]
+pub const LOG_TARGET: &str = "librealsense2";
+
+/// Occurs when the logging of librealsense2 cannot be configured.
+#[derive(Error, Debug)]
+#[error("Could not configure logging. Type: {0}; Reason: {1}")]
+pub struct CouldNotConfigureLoggingError(pub Rs2Exception, pub String);
+
+/// Logs messages from librealsense2 of at least `min_severity` to the console.
+///
+/// # Errors
+///
+/// Returns [`CouldNotConfigureLoggingError`] if logging to the console cannot be enabled.
+pub fn log_to_console(min_severity: Rs2LogSeverity) -> Result<(), CouldNotConfigureLoggingError> {
+    unsafe {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        sys::rs2_log_to_console(min_severity as sys::rs2_log_severity, &mut err);
+        check_rs2_error!(err, CouldNotConfigureLoggingError)
+    }
+}
+
+/// Logs messages from librealsense2 of at least `min_severity` to the file at `path`.
+///
+/// The file is created if it does not exist.
+///
+/// # Errors
+///
+/// Returns [`NulError`](std::ffi::NulError) if the provided file path cannot be cleanly
+/// represented as a [`CString`](std::ffi::CString).
+///
+/// Returns [`CouldNotConfigureLoggingError`] if logging to the file cannot be enabled.
+pub fn log_to_file<P>(min_severity: Rs2LogSeverity, path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let path = from_path(path)?;
+    unsafe {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        sys::rs2_log_to_file(
+            min_severity as sys::rs2_log_severity,
+            path.as_ptr(),
+            &mut err,
+        );
+        check_rs2_error!(err, CouldNotConfigureLoggingError)?;
+        Ok(())
+    }
+}
+
+/// Forwards messages from librealsense2 of at least `min_severity` to the [`log`] crate.
+///
+/// Messages are logged with the [`LOG_TARGET`] target, at the [level](::log::Level)
+/// corresponding to their severity. Fatal messages are logged as errors.
+///
+/// # Errors
+///
+/// Returns [`CouldNotConfigureLoggingError`] if the messages cannot be forwarded.
+#[cfg(feature = "log")]
+pub fn log_to_callback(min_severity: Rs2LogSeverity) -> Result<(), CouldNotConfigureLoggingError> {
+    unsafe {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        sys::rs2_log_to_callback(
+            min_severity as sys::rs2_log_severity,
+            Some(log_trampoline),
+            std::ptr::null_mut(),
+            &mut err,
+        );
+        check_rs2_error!(err, CouldNotConfigureLoggingError)
+    }
+}
+
+/// Gets the [`log`] level that messages of the given severity are logged at.
+///
+/// Returns `None` for [`Rs2LogSeverity::None`], since such messages are never logged.
+#[cfg(feature = "log")]
+fn level_for_severity(severity: Rs2LogSeverity) -> Option<::log::Level> {
+    match severity {
+        Rs2LogSeverity::Debug => Some(::log::Level::Debug),
+        Rs2LogSeverity::Info => Some(::log::Level::Info),
+        Rs2LogSeverity::Warn => Some(::log::Level::Warn),
+        Rs2LogSeverity::Error | Rs2LogSeverity::Fatal => Some(::log::Level::Error),
+        Rs2LogSeverity::None => None,
+    }
+}
+
+/// Trampoline through which librealsense2 forwards log messages to the [`log`] crate.
+///
+/// # Safety
+///
+/// `message` must be a valid log message handle. The handle is only borrowed.
+#[cfg(feature = "log")]
+unsafe extern "C" fn log_trampoline(
+    severity: sys::rs2_log_severity,
+    message: *const sys::rs2_log_message,
+    _user: *mut c_void,
+) {
+    let level = match Rs2LogSeverity::from_i32(severity as i32).and_then(level_for_severity) {
+        Some(level) => level,
+        None => return,
+    };
+
+    // Unwinding across the FFI boundary is undefined behaviour, so any panic in the logger stops
+    // here.
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        let raw_message = sys::rs2_get_raw_log_message(message, &mut err);
+
+        if err.as_ref().is_none() {
+            let raw_message = CStr::from_ptr(raw_message).to_string_lossy();
+            ::log::log!(target: LOG_TARGET, level, "{}", raw_message.trim_end());
+        } else {
+            sys::rs2_free_error(err);
+        }
+    }));
+}
+
+#[cfg(all(test, feature = "log"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn severities_map_to_log_levels() {
+        assert_eq!(
+            level_for_severity(Rs2LogSeverity::Debug),
+            Some(::log::Level::Debug)
+        );
+        assert_eq!(
+            level_for_severity(Rs2LogSeverity::Info),
+            Some(::log::Level::Info)
+        );
+        assert_eq!(
+            level_for_severity(Rs2LogSeverity::Warn),
+            Some(::log::Level::Warn)
+        );
+        assert_eq!(
+            level_for_severity(Rs2LogSeverity::Error),
+            Some(::log::Level::Error)
+        );
+        assert_eq!(
+            level_for_severity(Rs2LogSeverity::Fatal),
+            Some(::log::Level::Error)
+        );
+        assert_eq!(level_for_severity(Rs2LogSeverity::None), None);
+    }
+}
@@ -19,7 +19,11 @@
 mod active;
 mod inactive;
 mod profile;
+#[cfg(feature = "async")]
+mod stream;
 
 pub use active::{ActivePipeline, FrameWaitError};
 pub use inactive::{InactivePipeline, PipelineActivationError, PipelineConstructionError};
 pub use profile::{PipelineProfile, PipelineProfileConstructionError};
+#[cfg(feature = "async")]
+pub use stream::{Backpressure, FrameStream};
@@ -8,6 +8,11 @@ use std::{ptr::NonNull, task::Poll, time::Duration};
 use thiserror::Error;
 
 /// Enumeration over possible errors that can occur when waiting for a frame.
+///
+/// Timeouts are always reported as [`FrameWaitError::DidTimeoutBeforeFrameArrival`], and a
+/// disconnected device as [`FrameWaitError::DeviceDisconnected`], never as an internal error. This
+/// lets callers decide how to recover by matching on the error: a render loop will typically want
+/// to retry after a timeout, but re-enumerate devices after a disconnect.
 #[derive(Error, Debug)]
 pub enum FrameWaitError {
     /// librealsense2 had an internal error occur while waiting for frames.
@@ -19,6 +24,31 @@ pub enum FrameWaitError {
     /// The associated function timed out while waiting for frames.
     #[error("Timed out while waiting for frame.")]
     DidTimeoutBeforeFrameArrival,
+    /// The device streaming to the pipeline was disconnected.
+    #[error("The device was disconnected while waiting for frames. Reason: {0}")]
+    DeviceDisconnected(String),
+}
+
+impl FrameWaitError {
+    /// Maps an exception that occurred while waiting for frames to the error describing it.
+    ///
+    /// This is the constructor passed to `check_rs2_error!` when waiting for frames.
+    pub(crate) fn during_wait(exception: Rs2Exception, message: String) -> Self {
+        match exception {
+            Rs2Exception::CameraDisconnected => FrameWaitError::DeviceDisconnected(message),
+            _ => FrameWaitError::DidErrorDuringFrameWait(exception, message),
+        }
+    }
+
+    /// Maps an exception that occurred while polling for frames to the error describing it.
+    ///
+    /// This is the constructor passed to `check_rs2_error!` when polling for frames.
+    pub(crate) fn during_poll(exception: Rs2Exception, message: String) -> Self {
+        match exception {
+            Rs2Exception::CameraDisconnected => FrameWaitError::DeviceDisconnected(message),
+            _ => FrameWaitError::DidErrorDuringFramePoll(exception, message),
+        }
+    }
 }
 
 /// Type representing an "active" pipeline which is configured and can acquire frames.
@@ -70,6 +100,9 @@ impl ActivePipeline {
             // `NonNull` to try and guarantee that even beyond our state management), so there
             // dealing with the error (and thus returning a result type) is superfluous here.
             sys::rs2_pipeline_stop(self.pipeline_ptr.as_ptr(), &mut err);
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+            }
 
             let inactive = InactivePipeline::new(self.pipeline_ptr);
 
@@ -92,12 +125,41 @@ impl ActivePipeline {
     ///
     /// # Errors
     ///
-    /// Returns [`FrameWaitError::DidErrorDuringFrameWait`] if an internal error occurs while
-    /// waiting for next frame(s).
+    /// Returns [`FrameWaitError::DeviceDisconnected`] if the device is disconnected while waiting.
+    ///
+    /// Returns [`FrameWaitError::DidErrorDuringFrameWait`] if any other internal error occurs
+    /// while waiting for next frame(s).
     ///
     /// Returns [`FrameWaitError::DidTimeoutBeforeFrameArrival`] if the thread waits more than
     /// `timeout_ms` (in milliseconds) without returning a frame.
     pub fn wait(&mut self, timeout_ms: Option<Duration>) -> Result<CompositeFrame, FrameWaitError> {
+        self.try_wait(timeout_ms)?
+            .ok_or(FrameWaitError::DidTimeoutBeforeFrameArrival)
+    }
+
+    /// Waits to get a new composite frame, returning `None` if the timeout expires first.
+    ///
+    /// This behaves like [`ActivePipeline::wait`], except that running out of time is not treated
+    /// as an error. This makes it possible to distinguish "no frame yet" (`Ok(None)`) from an
+    /// actual failure of the device or pipeline (`Err`), which is useful in e.g. render loops that
+    /// should not block indefinitely.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout_ms` - The timeout in milliseconds. If the thread blocks for longer than this
+    ///   duration, it will exit early and return `Ok(None)`. If `None` is passed in, the [default
+    ///   timeout](realsense_sys::RS2_DEFAULT_TIMEOUT) is applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameWaitError::DeviceDisconnected`] if the device is disconnected while waiting.
+    ///
+    /// Returns [`FrameWaitError::DidErrorDuringFrameWait`] if any other internal error occurs
+    /// while waiting for next frame(s).
+    pub fn try_wait(
+        &mut self,
+        timeout_ms: Option<Duration>,
+    ) -> Result<Option<CompositeFrame>, FrameWaitError> {
         let timeout_ms = match timeout_ms {
             Some(d) => d.as_millis() as u32,
             None => sys::RS2_DEFAULT_TIMEOUT,
@@ -121,12 +183,12 @@ impl ActivePipeline {
                 timeout_ms,
                 &mut err,
             );
-            check_rs2_error!(err, FrameWaitError::DidErrorDuringFrameWait)?;
+            check_rs2_error!(err, FrameWaitError::during_wait)?;
 
             if did_get_frame != 0 {
-                Ok(CompositeFrame::from(NonNull::new(frame).unwrap()))
+                Ok(Some(CompositeFrame::from(NonNull::new(frame).unwrap())))
             } else {
-                Err(FrameWaitError::DidTimeoutBeforeFrameArrival)
+                Ok(None)
             }
         }
     }
@@ -139,8 +201,10 @@ impl ActivePipeline {
     ///
     /// # Errors
     ///
-    /// Returns [`FrameWaitError::DidErrorDuringFramePoll`] if an internal error occurs while
-    /// polling for the next frame.
+    /// Returns [`FrameWaitError::DeviceDisconnected`] if the device is disconnected while polling.
+    ///
+    /// Returns [`FrameWaitError::DidErrorDuringFramePoll`] if any other internal error occurs
+    /// while polling for the next frame.
     pub fn poll(&mut self) -> Result<Poll<CompositeFrame>, FrameWaitError> {
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
@@ -150,7 +214,7 @@ impl ActivePipeline {
                 &mut frame_ptr,
                 &mut err,
             );
-            check_rs2_error!(err, FrameWaitError::DidErrorDuringFramePoll)?;
+            check_rs2_error!(err, FrameWaitError::during_poll)?;
 
             if did_get_frame != 0 {
                 Ok(Poll::Ready(CompositeFrame::from(
@@ -162,3 +226,55 @@ impl ActivePipeline {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every exception type, as librealsense2 may report it while waiting or polling for frames.
+    const EXCEPTIONS: [Rs2Exception; 8] = [
+        Rs2Exception::Unknown,
+        Rs2Exception::CameraDisconnected,
+        Rs2Exception::Backend,
+        Rs2Exception::InvalidValue,
+        Rs2Exception::WrongApiCallSequence,
+        Rs2Exception::NotImplemented,
+        Rs2Exception::DeviceInRecoveryMode,
+        Rs2Exception::IoDeviceFailure,
+    ];
+
+    #[test]
+    fn disconnects_while_waiting_are_mapped_to_device_disconnected() {
+        let error = FrameWaitError::during_wait(
+            Rs2Exception::CameraDisconnected,
+            String::from("disconnected"),
+        );
+        assert!(matches!(error, FrameWaitError::DeviceDisconnected(m) if m == "disconnected"));
+    }
+
+    #[test]
+    fn disconnects_while_polling_are_mapped_to_device_disconnected() {
+        let error = FrameWaitError::during_poll(
+            Rs2Exception::CameraDisconnected,
+            String::from("disconnected"),
+        );
+        assert!(matches!(error, FrameWaitError::DeviceDisconnected(m) if m == "disconnected"));
+    }
+
+    #[test]
+    fn other_exceptions_are_mapped_to_internal_errors() {
+        for &exception in EXCEPTIONS
+            .iter()
+            .filter(|&&e| e != Rs2Exception::CameraDisconnected)
+        {
+            assert!(matches!(
+                FrameWaitError::during_wait(exception, String::new()),
+                FrameWaitError::DidErrorDuringFrameWait(e, _) if e == exception
+            ));
+            assert!(matches!(
+                FrameWaitError::during_poll(exception, String::new()),
+                FrameWaitError::DidErrorDuringFramePoll(e, _) if e == exception
+            ));
+        }
+    }
+}
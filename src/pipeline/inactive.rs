@@ -29,6 +29,11 @@ pub enum PipelineActivationError {
 }
 
 /// A type describing an "inactive" pipeline which is unconfigured and cannot acquire frames.
+///
+/// Any number of pipelines can be created from the same [`Context`], and each of them can stream
+/// from a different device. To capture from several cameras at once, bind a pipeline to each
+/// device with [`Config::enable_device_from_serial`] (or [`Config::enable_device_from_file`] for
+/// recordings) before starting it.
 #[derive(Debug)]
 pub struct InactivePipeline {
     /// A (non-null) pointer to the pipeline.
@@ -156,4 +161,16 @@ impl InactivePipeline {
             }
         }
     }
+
+    /// Get the underlying low-level pointer to the pipeline object.
+    ///
+    /// # Safety
+    ///
+    /// This method is not intended to be called or used outside of the crate itself. Be warned, it
+    /// is _undefined behaviour_ to call [`realsense_sys::rs2_delete_pipeline`] on this pointer. If
+    /// you do, you risk a double-free error when the [`InactivePipeline`] struct itself is dropped.
+    ///
+    pub(crate) unsafe fn get_raw(&self) -> NonNull<sys::rs2_pipeline> {
+        self.pipeline_ptr
+    }
 }
@@ -0,0 +1,206 @@
+//! Defines an asynchronous stream of frames from a pipeline.
+
+use super::{
+    active::{ActivePipeline, FrameWaitError},
+    inactive::InactivePipeline,
+};
+use crate::frame::CompositeFrame;
+use futures_core::Stream;
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    task::{Context, Poll, Waker},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// How long the background thread waits for frames before checking if the stream was stopped.
+const WAIT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Describes what a [`FrameStream`] does when frames arrive faster than they are consumed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Backpressure {
+    /// Drop the oldest buffered frame to make room for the newest one.
+    ///
+    /// Consumers always see the most recent frames, which is usually what you want for e.g.
+    /// displaying a live feed.
+    DropOldest,
+    /// Stop taking frames from the pipeline until there is room in the buffer.
+    ///
+    /// Note that the pipeline itself only holds a small number of frames, and will drop frames
+    /// once it is full.
+    Block,
+}
+
+/// State shared between a [`FrameStream`] and the thread waiting for frames on its behalf.
+#[derive(Debug, Default)]
+struct State {
+    /// Frames (or errors) received from the pipeline that have not been consumed yet.
+    frames: VecDeque<Result<CompositeFrame, FrameWaitError>>,
+    /// The waker of the task that last polled an empty stream.
+    waker: Option<Waker>,
+    /// Whether the stream has asked the thread to stop waiting for frames.
+    is_stopped: bool,
+    /// Whether the thread has stopped waiting for frames.
+    is_finished: bool,
+}
+
+/// Synchronized [`State`], along with a condition variable signalled whenever a frame is taken.
+#[derive(Debug, Default)]
+struct Shared {
+    /// The shared state.
+    state: Mutex<State>,
+    /// Signalled whenever there is more room in the buffer, or the stream is stopped.
+    frame_taken: Condvar,
+}
+
+impl Shared {
+    /// Locks the shared state, ignoring poisoning since the state is always left consistent.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// An asynchronous [`Stream`] of the frames produced by an [`ActivePipeline`].
+///
+/// The stream is created with [`ActivePipeline::into_frame_stream`], and takes ownership of the
+/// pipeline. Frames are waited for on a dedicated background thread and buffered until they are
+/// consumed. How the buffer behaves once it is full is determined by the [`Backpressure`] that
+/// the stream was created with.
+///
+/// If an error occurs while waiting for frames, the error is yielded by the stream and the stream
+/// ends afterwards. Timeouts are not errors; the stream keeps waiting until frames arrive.
+///
+/// Dropping the stream stops the background thread and drops the pipeline. To get the pipeline
+/// back instead, use [`FrameStream::stop`].
+#[derive(Debug)]
+pub struct FrameStream {
+    /// State shared with the background thread.
+    shared: Arc<Shared>,
+    /// Handle to the background thread, which returns the pipeline once it is done.
+    thread: Option<JoinHandle<ActivePipeline>>,
+}
+
+impl Drop for FrameStream {
+    fn drop(&mut self) {
+        if let Some(thread) = self.stop_thread() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Stream for FrameStream {
+    type Item = Result<CompositeFrame, FrameWaitError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.shared.lock();
+
+        if let Some(frame) = state.frames.pop_front() {
+            self.shared.frame_taken.notify_one();
+            Poll::Ready(Some(frame))
+        } else if state.is_finished {
+            Poll::Ready(None)
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl FrameStream {
+    /// Stops waiting for frames and stops the pipeline.
+    ///
+    /// Any frames that have been buffered but not consumed are dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the background thread panicked.
+    pub fn stop(mut self) -> InactivePipeline {
+        let thread = self.stop_thread().unwrap();
+        thread.join().expect("frame stream thread panicked").stop()
+    }
+
+    /// Asks the background thread to stop, and returns its handle if it has not been joined yet.
+    fn stop_thread(&mut self) -> Option<JoinHandle<ActivePipeline>> {
+        self.shared.lock().is_stopped = true;
+        self.shared.frame_taken.notify_all();
+        self.thread.take()
+    }
+
+    /// Waits for frames from `pipeline` until the stream is stopped or an error occurs, then
+    /// returns the pipeline.
+    fn wait_for_frames(
+        mut pipeline: ActivePipeline,
+        shared: Arc<Shared>,
+        capacity: usize,
+        backpressure: Backpressure,
+    ) -> ActivePipeline {
+        loop {
+            {
+                let mut state = shared.lock();
+                if backpressure == Backpressure::Block {
+                    while state.frames.len() >= capacity && !state.is_stopped {
+                        state = shared
+                            .frame_taken
+                            .wait(state)
+                            .unwrap_or_else(|e| e.into_inner());
+                    }
+                }
+                if state.is_stopped {
+                    break;
+                }
+            }
+
+            let (frame, is_error) = match pipeline.try_wait(Some(WAIT_INTERVAL)) {
+                Ok(Some(frame)) => (Ok(frame), false),
+                Ok(None) => continue,
+                Err(e) => (Err(e), true),
+            };
+
+            let mut state = shared.lock();
+            if state.frames.len() >= capacity {
+                state.frames.pop_front();
+            }
+            state.frames.push_back(frame);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+
+            if is_error {
+                break;
+            }
+        }
+
+        let mut state = shared.lock();
+        state.is_finished = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        pipeline
+    }
+}
+
+impl ActivePipeline {
+    /// Converts the pipeline into an asynchronous [`Stream`] of frames.
+    ///
+    /// At most `capacity` frames are buffered by the stream (a capacity of zero is treated as
+    /// one). `backpressure` determines what happens when the buffer is full. See [`FrameStream`]
+    /// for more details.
+    ///
+    /// This requires the `async` feature.
+    pub fn into_frame_stream(self, capacity: usize, backpressure: Backpressure) -> FrameStream {
+        let shared = Arc::new(Shared::default());
+        let capacity = capacity.max(1);
+
+        let thread_shared = Arc::clone(&shared);
+        let thread = thread::spawn(move || {
+            FrameStream::wait_for_frames(self, thread_shared, capacity, backpressure)
+        });
+
+        FrameStream {
+            shared,
+            thread: Some(thread),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Module containing processing blocks used to transform frames on the host.
+//!
+//! Processing blocks are librealsense2's mechanism for applying computation to frames after they
+//! have been captured, e.g. converting depth to disparity, filtering, or generating point clouds.
+//! Every processing block in librealsense2 is the same opaque `* rs2_processing_block` type,
+//! which accepts any frame and produces any frame. Here, each block is given its own type so that
+//! only frames the block knows how to handle can be passed in, and the output is returned as the
+//! correct frame type.
+//!
+//! All processing blocks follow the same pattern: frames are passed in with `queue`, and the
+//! processed results are retrieved with either `wait` (blocking) or `poll` (non-blocking). For
+//! the simple case where a single frame is processed at a time, `process` does both in one step.
+//!
+//! # Frame allocation
+//!
+//! librealsense2 does not allocate a new buffer for every frame that a processing block outputs.
+//! Each block owns a pool of frames, and a frame's buffer is returned to the pool once every
+//! reference to the frame has been dropped, to be reused for a later output. Blocks therefore do
+//! not cause allocation churn in steady state, as long as output frames are dropped once they are
+//! no longer needed. Holding on to many outputs of the same block (e.g. in a
+//! [`KeepLast`](crate::frame::KeepLast) buffer) can exhaust its pool, after which the block drops
+//! frames until some are released.
+//!
+//! A [`CustomProcessingBlock`] allocates the frames it outputs from the same kind of pool, through
+//! the [`FramePool`] of the [`FrameSource`] passed to its callback.
+//!
+//! # Queue size
+//!
+//! Processed frames are held in a queue owned by the block until they are retrieved with `wait` or
+//! `poll`. When the queue is full, the oldest processed frame in it is dropped to make room for
+//! the next one. By default the queue holds a single frame, which minimizes latency, since only
+//! the most recent output is kept. This suits interactive applications, which only care about
+//! the latest frame. When a chain of blocks is fed in bursts, or the consumer falls behind now and
+//! then, a larger queue smooths this out at the cost of returning older frames, rather than
+//! dropping them. The size is set with `new_with_queue_size` or `set_queue_size`.
+//!
+
+mod align;
+mod custom_processing_block;
+mod decimation_filter;
+mod disparity_transform;
+mod frame_pool;
+mod frame_source;
+mod pointcloud;
+mod processing_block;
+mod syncer;
+mod threshold_filter;
+mod units_transform;
+
+pub use align::Align;
+pub use custom_processing_block::CustomProcessingBlock;
+pub use decimation_filter::DecimationFilter;
+pub use disparity_transform::DisparityTransform;
+pub use frame_pool::{FramePool, FramePoolError};
+pub use frame_source::{FrameSource, FrameSourceError};
+pub use pointcloud::PointCloud;
+pub use processing_block::{ProcessFrameError, ProcessingBlockConstructionError};
+pub use syncer::Syncer;
+pub use threshold_filter::ThresholdFilter;
+pub use units_transform::UnitsTransform;
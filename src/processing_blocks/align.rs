@@ -0,0 +1,145 @@
+//! Processing block for aligning the frames of a frameset to the viewport of one stream.
+
+use super::processing_block::{
+    ProcessFrameError, ProcessingBlock, ProcessingBlockConstructionError, DEFAULT_QUEUE_SIZE,
+};
+use crate::{
+    check_rs2_error,
+    frame::CompositeFrame,
+    kind::{Rs2Option, Rs2OptionRange, Rs2StreamKind},
+};
+use anyhow::Result;
+use realsense_sys as sys;
+use std::{convert::TryInto, ptr::NonNull, task::Poll, time::Duration};
+
+/// Processing block that aligns the frames of a frameset to the viewport of one stream.
+///
+/// The depth and color imagers of a device are physically apart, and have different fields of
+/// view and resolutions, so the same pixel in a depth frame and a color frame generally does not
+/// see the same point. Aligning reprojects the frames of a [`CompositeFrame`] so that they are
+/// co-registered with the stream that is aligned to, i.e. share its intrinsics and coordinate
+/// frame. Most commonly, depth is aligned to color so that every color pixel gets a depth.
+///
+/// The output frameset holds the same streams as the input. Whether a frameset is aligned can be
+/// checked with [`CompositeFrame::are_aligned`].
+#[derive(Debug)]
+pub struct Align {
+    /// The underlying processing block and its output queue.
+    block: ProcessingBlock,
+}
+
+impl Align {
+    /// Constructs a block which aligns framesets to the viewport of the `align_to` stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the processing block or its queue cannot
+    /// be created.
+    pub fn new(align_to: Rs2StreamKind) -> Result<Self, ProcessingBlockConstructionError> {
+        Self::new_with_queue_size(align_to, DEFAULT_QUEUE_SIZE)
+    }
+
+    /// Like [`new`](Self::new), but holds up to `queue_size` aligned framesets until they are
+    /// retrieved.
+    ///
+    /// See [queue size](crate::processing_blocks#queue-size) for how this trades latency against
+    /// dropped framesets.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the processing block or its queue cannot
+    /// be created.
+    pub fn new_with_queue_size(
+        align_to: Rs2StreamKind,
+        queue_size: u32,
+    ) -> Result<Self, ProcessingBlockConstructionError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let block_ptr = sys::rs2_create_align(
+                #[allow(clippy::useless_conversion)]
+                (align_to as i32).try_into().unwrap(),
+                &mut err,
+            );
+            check_rs2_error!(
+                err,
+                ProcessingBlockConstructionError::CouldNotCreateProcessingBlock
+            )?;
+
+            Ok(Self {
+                block: ProcessingBlock::new(NonNull::new(block_ptr).unwrap(), queue_size)?,
+            })
+        }
+    }
+
+    /// Gets every option supported by the block, along with the range of values it accepts.
+    ///
+    /// This is useful to e.g. generate the controls of a settings panel for the block.
+    pub fn supported_options(&self) -> Vec<(Rs2Option, Rs2OptionRange)> {
+        self.block.supported_options()
+    }
+
+    /// Sets the number of processed frames that the block holds until they are retrieved.
+    ///
+    /// See [queue size](crate::processing_blocks#queue-size) for how this trades latency against
+    /// dropped frames. Processed frames that have not been retrieved yet are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the new queue cannot be created, in which
+    /// case the previous queue is kept.
+    pub fn set_queue_size(
+        &mut self,
+        queue_size: u32,
+    ) -> Result<(), ProcessingBlockConstructionError> {
+        self.block.set_queue_size(queue_size)
+    }
+
+    /// Queues a frameset to be aligned, transferring ownership of the frameset to the block.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::CouldNotProcessFrame`] if the frameset cannot be processed.
+    pub fn queue(&mut self, frames: CompositeFrame) -> Result<(), ProcessFrameError> {
+        unsafe { self.block.queue_raw(frames.into_raw()) }
+    }
+
+    /// Waits for the next aligned frameset, blocking the calling thread.
+    ///
+    /// If `timeout` is `None`, the [default timeout](realsense_sys::RS2_DEFAULT_TIMEOUT) is
+    /// applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::DidTimeoutBeforeFrameArrival`] if no aligned frameset becomes
+    /// available before the timeout, or another [`ProcessFrameError`] if an internal error occurs
+    /// while waiting.
+    pub fn wait(&mut self, timeout: Option<Duration>) -> Result<CompositeFrame> {
+        self.block.wait(timeout)
+    }
+
+    /// Polls for the next aligned frameset without blocking.
+    ///
+    /// Returns [`Poll::Pending`] if no aligned frameset is available yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::DidErrorDuringFramePoll`] if an internal error occurs while
+    /// polling.
+    pub fn poll(&mut self) -> Result<Poll<CompositeFrame>> {
+        self.block.poll()
+    }
+
+    /// Aligns a single frameset, blocking until the result is available.
+    ///
+    /// This is equivalent to calling [`queue`](Self::queue) followed by [`wait`](Self::wait)
+    /// with the default timeout.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the frameset cannot be processed or the aligned frameset does not
+    /// become available.
+    pub fn process(&mut self, frames: CompositeFrame) -> Result<CompositeFrame> {
+        self.queue(frames)?;
+        self.wait(None)
+    }
+}
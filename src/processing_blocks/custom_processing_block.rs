@@ -0,0 +1,206 @@
+//! Processing block which runs a Rust closure on every frame.
+
+use super::{
+    frame_source::FrameSource,
+    processing_block::{
+        ProcessFrameError, ProcessingBlock, ProcessingBlockConstructionError, DEFAULT_QUEUE_SIZE,
+    },
+};
+use crate::{
+    check_rs2_error,
+    frame::{frame_of_category, FrameCategory, FrameEx},
+};
+use anyhow::Result;
+use realsense_sys as sys;
+use std::{
+    convert::TryFrom,
+    fmt,
+    os::raw::c_void,
+    panic::{self, AssertUnwindSafe},
+    ptr::NonNull,
+    task::Poll,
+    time::Duration,
+};
+
+/// Type of the callback invoked by librealsense2 with every frame passed to a custom processing
+/// block.
+///
+/// The callback takes ownership of the frame pointer it is passed.
+type ProcessCallback = Box<dyn FnMut(NonNull<sys::rs2_frame>, &FrameSource<'_>) + Send>;
+
+/// Trampoline through which librealsense2 invokes the callback of a custom processing block.
+///
+/// # Safety
+///
+/// `user` must point to the [`ProcessCallback`] owned by the [`CustomProcessingBlock`] that
+/// registered this trampoline. Ownership of the frame is taken by this function.
+unsafe extern "C" fn process_trampoline(
+    frame: *mut sys::rs2_frame,
+    source: *mut sys::rs2_source,
+    user: *mut c_void,
+) {
+    let frame = match NonNull::new(frame) {
+        Some(frame) => frame,
+        None => return,
+    };
+    let source = match NonNull::new(source) {
+        Some(source) => FrameSource::new(source),
+        None => {
+            sys::rs2_release_frame(frame.as_ptr());
+            return;
+        }
+    };
+
+    let callback = &mut *(user as *mut ProcessCallback);
+
+    // Unwinding across the FFI boundary is undefined behaviour, so any panic in the callback stops
+    // here.
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| callback(frame, &source)));
+}
+
+/// Processing block that runs a Rust closure on every frame.
+///
+/// This is the way to implement your own processing on the CPU, while still composing with the
+/// other processing blocks and their queues. The closure is invoked with every frame that is
+/// [queued](Self::queue) on the block, along with a [`FrameSource`] through which it outputs any
+/// number of frames. The output frames are then retrieved with [`wait`](Self::wait) or
+/// [`poll`](Self::poll), like with any other block.
+///
+/// The closure is invoked on the thread that queues the frame.
+pub struct CustomProcessingBlock {
+    /// The underlying processing block and its output queue.
+    ///
+    /// This is declared before the callback so that the processing block is deleted before the
+    /// callback it invokes is dropped.
+    block: ProcessingBlock,
+    /// The callback invoked by the processing block, boxed twice so that it has a stable address
+    /// which can be passed through librealsense2.
+    _callback: Box<ProcessCallback>,
+}
+
+impl fmt::Debug for CustomProcessingBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomProcessingBlock")
+            .field("block", &self.block)
+            .finish()
+    }
+}
+
+impl CustomProcessingBlock {
+    /// Constructs a processing block which invokes `callback` with every frame passed to it.
+    ///
+    /// Frames that do not belong to the [frame category](FrameCategory) of `F`, e.g. infrared
+    /// frames when `F` is [`DepthFrame`](crate::frame::DepthFrame), are dropped without invoking
+    /// the callback. To output a frame, pass it to [`FrameSource::frame_ready`]. For example, a
+    /// block which passes every depth frame through unchanged is:
+    ///
+    /// ```no_run
+    /// # use realsense_rust::{frame::DepthFrame, processing_blocks::CustomProcessingBlock};
+    /// let block = CustomProcessingBlock::from_fn(|frame: DepthFrame, source| {
+    ///     let _ = source.frame_ready(frame);
+    /// })?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the processing block or its queue cannot
+    /// be created.
+    pub fn from_fn<F, C>(mut callback: C) -> Result<Self, ProcessingBlockConstructionError>
+    where
+        F: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory,
+        C: FnMut(F, &FrameSource<'_>) + Send + 'static,
+    {
+        let mut process_callback: Box<ProcessCallback> =
+            Box::new(Box::new(move |frame_ptr, source| {
+                if let Some(frame) = unsafe { frame_of_category::<F>(frame_ptr) } {
+                    callback(frame, source);
+                }
+            }));
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let block_ptr = sys::rs2_create_processing_block_fptr(
+                Some(process_trampoline),
+                &mut *process_callback as *mut ProcessCallback as *mut c_void,
+                &mut err,
+            );
+            check_rs2_error!(
+                err,
+                ProcessingBlockConstructionError::CouldNotCreateProcessingBlock
+            )?;
+
+            Ok(Self {
+                block: ProcessingBlock::new(NonNull::new(block_ptr).unwrap(), DEFAULT_QUEUE_SIZE)?,
+                _callback: process_callback,
+            })
+        }
+    }
+
+    /// Sets the number of processed frames that the block holds until they are retrieved.
+    ///
+    /// See [queue size](crate::processing_blocks#queue-size) for how this trades latency against
+    /// dropped frames. Processed frames that have not been retrieved yet are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the new queue cannot be created, in which
+    /// case the previous queue is kept.
+    pub fn set_queue_size(
+        &mut self,
+        queue_size: u32,
+    ) -> Result<(), ProcessingBlockConstructionError> {
+        self.block.set_queue_size(queue_size)
+    }
+
+    /// Queues a frame to be processed, transferring ownership of the frame to the block.
+    ///
+    /// The callback of the block is invoked before this returns.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::CouldNotProcessFrame`] if the frame cannot be processed.
+    pub fn queue<F>(&mut self, frame: F) -> Result<(), ProcessFrameError>
+    where
+        F: FrameEx,
+    {
+        self.block.queue(frame)
+    }
+
+    /// Waits for the next frame output by the callback, blocking the calling thread.
+    ///
+    /// If `timeout` is `None`, the [default timeout](realsense_sys::RS2_DEFAULT_TIMEOUT) is
+    /// applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::DidTimeoutBeforeFrameArrival`] if no frame is output before
+    /// the timeout, or another [`ProcessFrameError`] if an internal error occurs while waiting.
+    ///
+    /// Returns an error if the output frame cannot be converted to `F`.
+    pub fn wait<F>(&mut self, timeout: Option<Duration>) -> Result<F>
+    where
+        F: TryFrom<NonNull<sys::rs2_frame>>,
+        F::Error: Into<anyhow::Error>,
+    {
+        self.block.wait(timeout)
+    }
+
+    /// Polls for the next frame output by the callback without blocking.
+    ///
+    /// Returns [`Poll::Pending`] if no output frame is available yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::DidErrorDuringFramePoll`] if an internal error occurs while
+    /// polling.
+    ///
+    /// Returns an error if the output frame cannot be converted to `F`.
+    pub fn poll<F>(&mut self) -> Result<Poll<F>>
+    where
+        F: TryFrom<NonNull<sys::rs2_frame>>,
+        F::Error: Into<anyhow::Error>,
+    {
+        self.block.poll()
+    }
+}
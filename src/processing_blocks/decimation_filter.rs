@@ -0,0 +1,152 @@
+//! Processing block for downsampling depth frames.
+
+use super::processing_block::{
+    ProcessFrameError, ProcessingBlock, ProcessingBlockConstructionError, DEFAULT_QUEUE_SIZE,
+};
+use crate::{
+    check_rs2_error,
+    frame::DepthFrame,
+    kind::{OptionSetError, Rs2Option, Rs2OptionRange},
+};
+use anyhow::Result;
+use realsense_sys as sys;
+use std::{ptr::NonNull, task::Poll, time::Duration};
+
+/// Processing block that downsamples depth frames.
+///
+/// The resolution of every [`DepthFrame`] is reduced by the [magnitude](Self::set_magnitude) of
+/// the filter in both dimensions, so the output frame has a different width and height than the
+/// input frame. Each output pixel is computed from a block of input pixels, using the median for
+/// small magnitudes and the mean for larger ones, ignoring pixels without valid depth.
+///
+/// This is the cheapest way to reduce the cost of downstream processing, such as calculating a
+/// [`PointCloud`](super::PointCloud).
+#[derive(Debug)]
+pub struct DecimationFilter {
+    /// The underlying processing block and its output queue.
+    block: ProcessingBlock,
+}
+
+impl DecimationFilter {
+    /// Constructs a new decimation filter with the default magnitude of librealsense2.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the processing block or its queue cannot
+    /// be created.
+    pub fn new() -> Result<Self, ProcessingBlockConstructionError> {
+        Self::new_with_queue_size(DEFAULT_QUEUE_SIZE)
+    }
+
+    /// Like [`new`](Self::new), but holds up to `queue_size` processed frames until they are
+    /// retrieved.
+    ///
+    /// See [queue size](crate::processing_blocks#queue-size) for how this trades latency against
+    /// dropped frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the processing block or its queue cannot
+    /// be created.
+    pub fn new_with_queue_size(queue_size: u32) -> Result<Self, ProcessingBlockConstructionError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let block_ptr = sys::rs2_create_decimation_filter_block(&mut err);
+            check_rs2_error!(
+                err,
+                ProcessingBlockConstructionError::CouldNotCreateProcessingBlock
+            )?;
+
+            Ok(Self {
+                block: ProcessingBlock::new(NonNull::new(block_ptr).unwrap(), queue_size)?,
+            })
+        }
+    }
+
+    /// Sets the factor by which the width and height of depth frames are reduced.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptionSetError::CouldNotSetOption`] if `magnitude` is outside of the range
+    /// supported by the processing block.
+    pub fn set_magnitude(&mut self, magnitude: u8) -> Result<(), OptionSetError> {
+        self.block
+            .set_option(Rs2Option::FilterMagnitude, magnitude as f32)
+    }
+
+    /// Gets every option supported by the block, along with the range of values it accepts.
+    ///
+    /// This is useful to e.g. generate the controls of a settings panel for the block.
+    pub fn supported_options(&self) -> Vec<(Rs2Option, Rs2OptionRange)> {
+        self.block.supported_options()
+    }
+
+    /// Sets the number of processed frames that the block holds until they are retrieved.
+    ///
+    /// See [queue size](crate::processing_blocks#queue-size) for how this trades latency against
+    /// dropped frames. Processed frames that have not been retrieved yet are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the new queue cannot be created, in which
+    /// case the previous queue is kept.
+    pub fn set_queue_size(
+        &mut self,
+        queue_size: u32,
+    ) -> Result<(), ProcessingBlockConstructionError> {
+        self.block.set_queue_size(queue_size)
+    }
+
+    /// Queues a depth frame to be decimated, transferring ownership of the frame to the block.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::CouldNotProcessFrame`] if the frame cannot be processed.
+    pub fn queue(&mut self, frame: DepthFrame) -> Result<(), ProcessFrameError> {
+        self.block.queue(frame)
+    }
+
+    /// Waits for the next decimated depth frame, blocking the calling thread.
+    ///
+    /// If `timeout` is `None`, the [default timeout](realsense_sys::RS2_DEFAULT_TIMEOUT) is
+    /// applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::DidTimeoutBeforeFrameArrival`] if no decimated frame becomes
+    /// available before the timeout, or another [`ProcessFrameError`] if an internal error occurs
+    /// while waiting.
+    ///
+    /// Returns an error if the decimated frame cannot be constructed.
+    pub fn wait(&mut self, timeout: Option<Duration>) -> Result<DepthFrame> {
+        self.block.wait(timeout)
+    }
+
+    /// Polls for the next decimated depth frame without blocking.
+    ///
+    /// Returns [`Poll::Pending`] if no decimated frame is available yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::DidErrorDuringFramePoll`] if an internal error occurs while
+    /// polling.
+    ///
+    /// Returns an error if the decimated frame cannot be constructed.
+    pub fn poll(&mut self) -> Result<Poll<DepthFrame>> {
+        self.block.poll()
+    }
+
+    /// Decimates a single depth frame, blocking until the result is available.
+    ///
+    /// This is equivalent to calling [`queue`](Self::queue) followed by [`wait`](Self::wait)
+    /// with the default timeout.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the frame cannot be processed or the decimated frame does not become
+    /// available.
+    pub fn process(&mut self, frame: DepthFrame) -> Result<DepthFrame> {
+        self.queue(frame)?;
+        self.wait(None)
+    }
+}
@@ -0,0 +1,158 @@
+//! Processing block for converting between depth and disparity frames.
+
+use super::processing_block::{
+    ProcessFrameError, ProcessingBlock, ProcessingBlockConstructionError, DEFAULT_QUEUE_SIZE,
+};
+use crate::{
+    check_rs2_error,
+    frame::{DepthFrame, DisparityFrame, FrameEx},
+    kind::{Rs2Option, Rs2OptionRange},
+};
+use anyhow::Result;
+use realsense_sys as sys;
+use std::{convert::TryFrom, marker::PhantomData, ptr::NonNull, task::Poll, time::Duration};
+
+/// Processing block that converts depth frames to disparity frames, or vice-versa.
+///
+/// The direction of the transform is fixed when the block is constructed, and is encoded in the
+/// type of the block:
+///
+/// * [`DisparityTransform::depth_to_disparity`] accepts a [`DepthFrame`] and produces a
+///   [`DisparityFrame`].
+/// * [`DisparityTransform::disparity_to_depth`] accepts a [`DisparityFrame`] and produces a
+///   [`DepthFrame`].
+///
+/// Converting to disparity is the standard preprocessing step before applying the spatial and
+/// temporal filters, as these operate best in the disparity domain.
+#[derive(Debug)]
+pub struct DisparityTransform<I, O> {
+    /// The underlying processing block and its output queue.
+    block: ProcessingBlock,
+    /// Holds the input and output frame types of the transform.
+    _phantom: PhantomData<(I, O)>,
+}
+
+impl DisparityTransform<DepthFrame, DisparityFrame> {
+    /// Constructs a disparity transform which converts depth frames into disparity frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the processing block or its queue cannot
+    /// be created.
+    pub fn depth_to_disparity() -> Result<Self, ProcessingBlockConstructionError> {
+        Self::create(true)
+    }
+}
+
+impl DisparityTransform<DisparityFrame, DepthFrame> {
+    /// Constructs a disparity transform which converts disparity frames into depth frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the processing block or its queue cannot
+    /// be created.
+    pub fn disparity_to_depth() -> Result<Self, ProcessingBlockConstructionError> {
+        Self::create(false)
+    }
+}
+
+impl<I, O> DisparityTransform<I, O>
+where
+    I: FrameEx,
+    O: TryFrom<NonNull<sys::rs2_frame>, Error = anyhow::Error>,
+{
+    /// Creates the processing block, transforming to disparity if `transform_to_disparity` is
+    /// true, and to depth otherwise.
+    fn create(transform_to_disparity: bool) -> Result<Self, ProcessingBlockConstructionError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let block_ptr =
+                sys::rs2_create_disparity_transform_block(transform_to_disparity as u8, &mut err);
+            check_rs2_error!(
+                err,
+                ProcessingBlockConstructionError::CouldNotCreateProcessingBlock
+            )?;
+
+            Ok(Self {
+                block: ProcessingBlock::new(NonNull::new(block_ptr).unwrap(), DEFAULT_QUEUE_SIZE)?,
+                _phantom: PhantomData {},
+            })
+        }
+    }
+
+    /// Gets every option supported by the block, along with the range of values it accepts.
+    ///
+    /// This is useful to e.g. generate the controls of a settings panel for the block.
+    pub fn supported_options(&self) -> Vec<(Rs2Option, Rs2OptionRange)> {
+        self.block.supported_options()
+    }
+
+    /// Sets the number of processed frames that the block holds until they are retrieved.
+    ///
+    /// See [queue size](crate::processing_blocks#queue-size) for how this trades latency against
+    /// dropped frames. Processed frames that have not been retrieved yet are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the new queue cannot be created, in which
+    /// case the previous queue is kept.
+    pub fn set_queue_size(
+        &mut self,
+        queue_size: u32,
+    ) -> Result<(), ProcessingBlockConstructionError> {
+        self.block.set_queue_size(queue_size)
+    }
+
+    /// Queues a frame to be transformed, transferring ownership of the frame to the block.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::CouldNotProcessFrame`] if the frame cannot be processed.
+    pub fn queue(&mut self, frame: I) -> Result<(), ProcessFrameError> {
+        self.block.queue(frame)
+    }
+
+    /// Waits for the next transformed frame, blocking the calling thread.
+    ///
+    /// If `timeout` is `None`, the [default timeout](realsense_sys::RS2_DEFAULT_TIMEOUT) is
+    /// applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::DidTimeoutBeforeFrameArrival`] if no transformed frame
+    /// becomes available before the timeout, or another [`ProcessFrameError`] if an internal
+    /// error occurs while waiting.
+    ///
+    /// Returns an error if the transformed frame cannot be constructed.
+    pub fn wait(&mut self, timeout: Option<Duration>) -> Result<O> {
+        self.block.wait(timeout)
+    }
+
+    /// Polls for the next transformed frame without blocking.
+    ///
+    /// Returns [`Poll::Pending`] if no transformed frame is available yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::DidErrorDuringFramePoll`] if an internal error occurs while
+    /// polling.
+    ///
+    /// Returns an error if the transformed frame cannot be constructed.
+    pub fn poll(&mut self) -> Result<Poll<O>> {
+        self.block.poll()
+    }
+
+    /// Transforms a single frame, blocking until the result is available.
+    ///
+    /// This is equivalent to calling [`queue`](Self::queue) followed by [`wait`](Self::wait)
+    /// with the default timeout.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the frame cannot be processed or the transformed frame does not become
+    /// available.
+    pub fn process(&mut self, frame: I) -> Result<O> {
+        self.queue(frame)?;
+        self.wait(None)
+    }
+}
@@ -0,0 +1,172 @@
+//! Defines the pool from which custom processing blocks allocate their output frames.
+
+use crate::{
+    check_rs2_error,
+    frame::{FormatError, FrameCategory, ImageFrame},
+    kind::Rs2Exception,
+    stream_profile::StreamProfile,
+};
+use anyhow::Result;
+use realsense_sys as sys;
+use std::{
+    convert::{TryFrom, TryInto},
+    marker::PhantomData,
+    os::raw::c_int,
+    ptr::NonNull,
+};
+use thiserror::Error;
+
+/// Enumeration over possible errors that can occur when allocating frames from a frame pool.
+#[derive(Error, Debug)]
+pub enum FramePoolError {
+    /// A new frame could not be allocated.
+    #[error("Could not allocate frame. Type: {0}; Reason: {1}")]
+    CouldNotAllocateFrame(Rs2Exception, String),
+    /// The data for a new frame does not have the size of the frame.
+    #[error("A new frame holds {expected} bytes, but {actual} bytes were given.")]
+    DataSizeMismatch {
+        /// The number of bytes held by the new frame.
+        expected: usize,
+        /// The number of bytes given for the new frame.
+        actual: usize,
+    },
+}
+
+/// The pool from which a [`CustomProcessingBlock`](super::CustomProcessingBlock) allocates the
+/// frames it outputs.
+///
+/// Every processing block owns a pool of frames. Once every reference to an output frame has
+/// been dropped, its buffer is returned to the pool and reused for a later allocation, so a block
+/// which outputs a new frame for every input does not allocate a new buffer for each of them. See
+/// [frame allocation](crate::processing_blocks#frame-allocation) for how holding on to output
+/// frames affects this.
+///
+/// A frame pool is obtained from the [`FrameSource`](super::FrameSource) passed to the block's
+/// callback with [`pool`](super::FrameSource::pool), and is only valid for the duration of that
+/// callback invocation.
+#[derive(Debug)]
+pub struct FramePool<'a> {
+    /// A (non-null) pointer to the frame source which owns the pool.
+    source_ptr: NonNull<sys::rs2_source>,
+    /// Ties the frame pool to the callback invocation it was created for.
+    _phantom: PhantomData<&'a ()>,
+}
+
+impl<'a> FramePool<'a> {
+    /// Wraps the pool of the frame source that librealsense2 passed to a processing block
+    /// callback.
+    ///
+    /// # Safety
+    ///
+    /// The frame source must remain valid for the lifetime `'a`, i.e. for the duration of the
+    /// callback invocation.
+    pub(crate) unsafe fn new(source_ptr: NonNull<sys::rs2_source>) -> Self {
+        Self {
+            source_ptr,
+            _phantom: PhantomData {},
+        }
+    }
+
+    /// Allocates a new image frame holding `bytes` from the pool.
+    ///
+    /// The new frame belongs to the stream of `profile`, and has its resolution and format. Its
+    /// metadata and timestamps are copied from `original`, which is usually the input frame of the
+    /// callback. `bytes` holds the rows of the frame without any padding, i.e. the size of a row is
+    /// the width of the frame times the [bytes per pixel](crate::kind::Rs2Format::bytes_per_pixel)
+    /// of its format.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DataError`](crate::stream_profile::DataError) if `profile` is not a video stream.
+    ///
+    /// Returns [`FramePoolError::DataSizeMismatch`] if `bytes` does not have the size of the new
+    /// frame.
+    ///
+    /// Otherwise fails like
+    /// [`allocate_uninitialized_video_frame`](Self::allocate_uninitialized_video_frame).
+    pub fn allocate_video_frame<K>(
+        &self,
+        original: &ImageFrame<K>,
+        profile: &StreamProfile,
+        bytes: &[u8],
+    ) -> Result<ImageFrame<K>>
+    where
+        ImageFrame<K>: FrameCategory,
+    {
+        let resolution = profile.resolution()?;
+        let mut frame = self.allocate_uninitialized_video_frame(
+            original,
+            profile,
+            resolution.width,
+            resolution.height,
+        )?;
+
+        let data = frame.data_mut()?;
+        if data.len() != bytes.len() {
+            return Err(FramePoolError::DataSizeMismatch {
+                expected: data.len(),
+                actual: bytes.len(),
+            }
+            .into());
+        }
+        data.copy_from_slice(bytes);
+        Ok(frame)
+    }
+
+    /// Allocates a new image frame from the pool, without initializing its contents.
+    ///
+    /// The new frame belongs to the stream of `profile` and is `width` x `height` pixels, with a
+    /// layout determined by the format of `profile`. Its metadata and timestamps are copied from
+    /// `original`, which is usually the input frame of the callback. The contents of the new frame
+    /// are undefined, and should be written through [`ImageFrame::data_mut`] before the frame is
+    /// [output](super::FrameSource::frame_ready).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FormatError`] if the format of `profile` does not have a fixed number of
+    /// [bytes per pixel](crate::kind::Rs2Format::bytes_per_pixel).
+    ///
+    /// Returns [`FramePoolError::CouldNotAllocateFrame`] if the frame cannot be allocated.
+    ///
+    /// Returns an error if the allocated frame cannot be constructed.
+    pub fn allocate_uninitialized_video_frame<K>(
+        &self,
+        original: &ImageFrame<K>,
+        profile: &StreamProfile,
+        width: usize,
+        height: usize,
+    ) -> Result<ImageFrame<K>>
+    where
+        ImageFrame<K>: FrameCategory,
+    {
+        let format = profile.format();
+        let bytes_per_pixel = format.bytes_per_pixel().ok_or(FormatError(format))?;
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let frame_ptr = sys::rs2_allocate_synthetic_video_frame(
+                self.source_ptr.as_ptr(),
+                profile.get_raw().as_ptr(),
+                original.get_raw().as_ptr(),
+                (bytes_per_pixel * 8) as c_int,
+                width as c_int,
+                height as c_int,
+                (width * bytes_per_pixel) as c_int,
+                #[allow(clippy::useless_conversion)]
+                (ImageFrame::<K>::extension() as i32).try_into().unwrap(),
+                &mut err,
+            );
+            check_rs2_error!(err, FramePoolError::CouldNotAllocateFrame)?;
+
+            let frame_ptr = NonNull::new(frame_ptr).unwrap();
+            match ImageFrame::try_from(frame_ptr) {
+                // The frame was just allocated, so nothing else refers to it until it is output.
+                Ok(frame) => Ok(frame.assume_exclusive()),
+                Err(e) => {
+                    sys::rs2_release_frame(frame_ptr.as_ptr());
+                    Err(e)
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Defines the source through which custom processing blocks output frames.
+
+use super::frame_pool::FramePool;
+use crate::{check_rs2_error, frame::FrameEx, kind::Rs2Exception};
+use realsense_sys as sys;
+use std::ptr::NonNull;
+use thiserror::Error;
+
+/// Enumeration over possible errors that can occur when outputting frames from a frame source.
+#[derive(Error, Debug)]
+pub enum FrameSourceError {
+    /// A frame could not be output from the processing block.
+    #[error("Could not output frame. Type: {0}; Reason: {1}")]
+    CouldNotOutputFrame(Rs2Exception, String),
+}
+
+/// The source through which a [`CustomProcessingBlock`](super::CustomProcessingBlock) outputs
+/// frames.
+///
+/// A frame source is only valid for the duration of a single invocation of the block's callback,
+/// which is why it is only ever handed out by reference.
+///
+/// New frames to output are allocated from the block's [`FramePool`], so outputting a new frame
+/// for every input does not allocate a new buffer every time.
+#[derive(Debug)]
+pub struct FrameSource<'a> {
+    /// A (non-null) pointer to the frame source.
+    source_ptr: NonNull<sys::rs2_source>,
+    /// The pool of the frame source, which is valid for as long as the source itself.
+    pool: FramePool<'a>,
+}
+
+impl<'a> FrameSource<'a> {
+    /// Wraps the frame source that librealsense2 passed to a processing block callback.
+    ///
+    /// # Safety
+    ///
+    /// The frame source must remain valid for the lifetime `'a`, i.e. for the duration of the
+    /// callback invocation.
+    pub(crate) unsafe fn new(source_ptr: NonNull<sys::rs2_source>) -> Self {
+        Self {
+            source_ptr,
+            pool: FramePool::new(source_ptr),
+        }
+    }
+
+    /// Gets the pool from which frames to output are allocated.
+    pub fn pool(&self) -> &FramePool<'a> {
+        &self.pool
+    }
+
+    /// Outputs a frame from the processing block, transferring ownership of the frame.
+    ///
+    /// The frame can be any frame, e.g. the input frame of the callback itself to pass it through
+    /// unchanged, or a frame [allocated](FramePool::allocate_video_frame) from the
+    /// [pool](Self::pool) of this source.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameSourceError::CouldNotOutputFrame`] if the frame cannot be output.
+    pub fn frame_ready<F>(&self, frame: F) -> Result<(), FrameSourceError>
+    where
+        F: FrameEx,
+    {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_synthetic_frame_ready(
+                self.source_ptr.as_ptr(),
+                frame.get_owned_raw().as_ptr(),
+                &mut err,
+            );
+            check_rs2_error!(err, FrameSourceError::CouldNotOutputFrame)
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! Processing block for generating point clouds from depth frames.
+
+use super::processing_block::{
+    ProcessFrameError, ProcessingBlock, ProcessingBlockConstructionError, DEFAULT_QUEUE_SIZE,
+};
+use crate::{
+    check_rs2_error,
+    frame::{DepthFrame, FrameEx, PointsFrame},
+    kind::{Rs2Option, Rs2OptionRange},
+};
+use anyhow::Result;
+use realsense_sys as sys;
+use std::{ptr::NonNull, task::Poll, time::Duration};
+
+/// Processing block that generates a point cloud from depth frames.
+///
+/// Every pixel of a [`DepthFrame`] is deprojected into a vertex of the resulting [`PointsFrame`],
+/// using the intrinsics of the depth stream. Pixels without a valid depth produce a vertex at the
+/// origin.
+///
+/// To texture the point cloud, pass a frame from another stream (typically a
+/// [`ColorFrame`](crate::frame::ColorFrame)) to [`map_to`](PointCloud::map_to) before
+/// calculating the point cloud. The texture coordinates of every point cloud calculated afterwards
+/// will then line up with that stream.
+#[derive(Debug)]
+pub struct PointCloud {
+    /// The underlying processing block and its output queue.
+    block: ProcessingBlock,
+}
+
+impl PointCloud {
+    /// Constructs a new point cloud processing block.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the processing block or its queue cannot
+    /// be created.
+    pub fn new() -> Result<Self, ProcessingBlockConstructionError> {
+        Self::new_with_queue_size(DEFAULT_QUEUE_SIZE)
+    }
+
+    /// Like [`new`](Self::new), but holds up to `queue_size` processed frames until they are
+    /// retrieved.
+    ///
+    /// See [queue size](crate::processing_blocks#queue-size) for how this trades latency against
+    /// dropped frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the processing block or its queue cannot
+    /// be created.
+    pub fn new_with_queue_size(queue_size: u32) -> Result<Self, ProcessingBlockConstructionError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let block_ptr = sys::rs2_create_pointcloud(&mut err);
+            check_rs2_error!(
+                err,
+                ProcessingBlockConstructionError::CouldNotCreateProcessingBlock
+            )?;
+
+            Ok(Self {
+                block: ProcessingBlock::new(NonNull::new(block_ptr).unwrap(), queue_size)?,
+            })
+        }
+    }
+
+    /// Gets every option supported by the block, along with the range of values it accepts.
+    ///
+    /// This is useful to e.g. generate the controls of a settings panel for the block.
+    pub fn supported_options(&self) -> Vec<(Rs2Option, Rs2OptionRange)> {
+        self.block.supported_options()
+    }
+
+    /// Maps the texture coordinates of subsequent point clouds to the stream of `frame`.
+    ///
+    /// The point cloud is restricted to texturing from streams with the same kind, format, and
+    /// index as `frame`, and `frame` itself is used as the texture source until a newer frame of
+    /// that stream is mapped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream filter cannot be set on the processing block, or if the
+    /// frame cannot be processed.
+    pub fn map_to<F>(&mut self, frame: F) -> Result<()>
+    where
+        F: FrameEx,
+    {
+        let profile = frame.stream_profile();
+        let kind = profile.kind();
+        let format = profile.format();
+        let index = profile.index();
+
+        self.block
+            .set_option(Rs2Option::StreamFilter, kind as i32 as f32)?;
+        self.block
+            .set_option(Rs2Option::StreamFormatFilter, format as i32 as f32)?;
+        self.block
+            .set_option(Rs2Option::StreamIndexFilter, index as f32)?;
+        self.block.queue(frame)?;
+        Ok(())
+    }
+
+    /// Sets the number of processed frames that the block holds until they are retrieved.
+    ///
+    /// See [queue size](crate::processing_blocks#queue-size) for how this trades latency against
+    /// dropped frames. Processed frames that have not been retrieved yet are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the new queue cannot be created, in which
+    /// case the previous queue is kept.
+    pub fn set_queue_size(
+        &mut self,
+        queue_size: u32,
+    ) -> Result<(), ProcessingBlockConstructionError> {
+        self.block.set_queue_size(queue_size)
+    }
+
+    /// Queues a depth frame to generate a point cloud from, transferring ownership of the frame
+    /// to the block.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::CouldNotProcessFrame`] if the frame cannot be processed.
+    pub fn queue(&mut self, frame: DepthFrame) -> Result<(), ProcessFrameError> {
+        self.block.queue(frame)
+    }
+
+    /// Waits for the next point cloud, blocking the calling thread.
+    ///
+    /// If `timeout` is `None`, the [default timeout](realsense_sys::RS2_DEFAULT_TIMEOUT) is
+    /// applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::DidTimeoutBeforeFrameArrival`] if no point cloud becomes
+    /// available before the timeout, or another [`ProcessFrameError`] if an internal error occurs
+    /// while waiting.
+    ///
+    /// Returns an error if the points frame cannot be constructed.
+    pub fn wait(&mut self, timeout: Option<Duration>) -> Result<PointsFrame> {
+        self.block.wait(timeout)
+    }
+
+    /// Polls for the next point cloud without blocking.
+    ///
+    /// Returns [`Poll::Pending`] if no point cloud is available yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::DidErrorDuringFramePoll`] if an internal error occurs while
+    /// polling.
+    ///
+    /// Returns an error if the points frame cannot be constructed.
+    pub fn poll(&mut self) -> Result<Poll<PointsFrame>> {
+        self.block.poll()
+    }
+
+    /// Calculates the point cloud of a single depth frame, blocking until it is available.
+    ///
+    /// This is equivalent to calling [`queue`](Self::queue) followed by [`wait`](Self::wait)
+    /// with the default timeout.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the frame cannot be processed or the point cloud does not become
+    /// available.
+    pub fn calculate(&mut self, frame: DepthFrame) -> Result<PointsFrame> {
+        self.queue(frame)?;
+        self.wait(None)
+    }
+}
@@ -0,0 +1,362 @@
+//! Defines the processing block type shared across all specific processing blocks.
+
+use crate::{
+    check_rs2_error,
+    frame::FrameEx,
+    frame_queue::{create_raw_queue, poll_for_frame, wait_for_frame},
+    kind::{OptionSetError, Rs2Exception, Rs2Option, Rs2OptionRange},
+};
+use anyhow::Result;
+use num_traits::FromPrimitive;
+use realsense_sys as sys;
+use std::{
+    convert::{TryFrom, TryInto},
+    mem::MaybeUninit,
+    ptr::NonNull,
+    task::Poll,
+    time::Duration,
+};
+use thiserror::Error;
+
+/// The number of processed frames that are held in a processing block's output queue, unless
+/// configured otherwise.
+pub(crate) const DEFAULT_QUEUE_SIZE: u32 = 1;
+
+/// Enumeration over possible errors that can occur when constructing a processing block.
+#[derive(Error, Debug)]
+pub enum ProcessingBlockConstructionError {
+    /// The processing block itself could not be created.
+    #[error("Could not create the processing block. Type: {0}; Reason: {1}")]
+    CouldNotCreateProcessingBlock(Rs2Exception, String),
+    /// The frame queue that the processing block outputs to could not be created.
+    #[error("Could not create the processing queue. Type: {0}; Reason: {1}")]
+    CouldNotCreateProcessingQueue(Rs2Exception, String),
+    /// The processing block could not be directed to output to its frame queue.
+    #[error("Could not start the processing queue. Type: {0}; Reason: {1}")]
+    CouldNotStartProcessingQueue(Rs2Exception, String),
+}
+
+/// Enumeration over possible errors that can occur when processing a frame.
+#[derive(Error, Debug)]
+pub enum ProcessFrameError {
+    /// librealsense2 had an internal error occur while processing the frame.
+    #[error("Could not process the frame. Type: {0}; Reason: {1}")]
+    CouldNotProcessFrame(Rs2Exception, String),
+    /// librealsense2 had an internal error occur while waiting for the processed frame.
+    #[error(
+        "An internal error occurred while waiting for the processed frame. Type: {0}; Reason: {1}"
+    )]
+    DidErrorDuringFrameWait(Rs2Exception, String),
+    /// librealsense2 had an internal error occur while polling for the processed frame.
+    #[error(
+        "An internal error occurred while polling for the processed frame. Type: {0}; Reason: {1}"
+    )]
+    DidErrorDuringFramePoll(Rs2Exception, String),
+    /// The processing block timed out while waiting for the processed frame.
+    #[error("Timed out while waiting for the processed frame.")]
+    DidTimeoutBeforeFrameArrival,
+}
+
+/// Holds a processing block and the frame queue that it writes its output to.
+///
+/// This is the common core for all of the processing block types in this module. It is not
+/// exposed directly, since it accepts any kind of frame and returns untyped frame pointers.
+#[derive(Debug)]
+pub(crate) struct ProcessingBlock {
+    /// A (non-null) pointer to the processing block.
+    block_ptr: NonNull<sys::rs2_processing_block>,
+    /// A (non-null) pointer to the frame queue that the processing block outputs to.
+    queue_ptr: NonNull<sys::rs2_frame_queue>,
+}
+
+impl Drop for ProcessingBlock {
+    fn drop(&mut self) {
+        unsafe {
+            // The block is deleted first so that nothing can be written to the queue after it is
+            // gone.
+            sys::rs2_delete_processing_block(self.block_ptr.as_ptr());
+            sys::rs2_delete_frame_queue(self.queue_ptr.as_ptr());
+        }
+    }
+}
+
+unsafe impl Send for ProcessingBlock {}
+
+impl ProcessingBlock {
+    /// Takes ownership of the processing block and directs its output to a new frame queue, which
+    /// holds up to `queue_size` processed frames.
+    ///
+    /// The processing block is deleted if the queue cannot be created or started.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError::CouldNotCreateProcessingQueue`] if the frame
+    /// queue cannot be created.
+    ///
+    /// Returns [`ProcessingBlockConstructionError::CouldNotStartProcessingQueue`] if the
+    /// processing block cannot be directed to output to the frame queue.
+    pub(crate) fn new(
+        block_ptr: NonNull<sys::rs2_processing_block>,
+        queue_size: u32,
+    ) -> Result<Self, ProcessingBlockConstructionError> {
+        let queue_ptr = match create_queue(queue_size) {
+            Ok(queue_ptr) => queue_ptr,
+            Err(e) => {
+                unsafe { sys::rs2_delete_processing_block(block_ptr.as_ptr()) };
+                return Err(e);
+            }
+        };
+
+        let block = Self {
+            block_ptr,
+            queue_ptr,
+        };
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_start_processing_queue(
+                block.block_ptr.as_ptr(),
+                block.queue_ptr.as_ptr(),
+                &mut err,
+            );
+            check_rs2_error!(
+                err,
+                ProcessingBlockConstructionError::CouldNotStartProcessingQueue
+            )?;
+        }
+
+        Ok(block)
+    }
+
+    /// Directs the output of the processing block to a new frame queue, which holds up to
+    /// `queue_size` processed frames.
+    ///
+    /// Any processed frames that are still held in the previous queue are dropped. If the new
+    /// queue cannot be created or started, the block keeps outputting to the previous queue.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError::CouldNotCreateProcessingQueue`] if the frame
+    /// queue cannot be created.
+    ///
+    /// Returns [`ProcessingBlockConstructionError::CouldNotStartProcessingQueue`] if the
+    /// processing block cannot be directed to output to the frame queue.
+    pub(crate) fn set_queue_size(
+        &mut self,
+        queue_size: u32,
+    ) -> Result<(), ProcessingBlockConstructionError> {
+        let queue_ptr = create_queue(queue_size)?;
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_start_processing_queue(self.block_ptr.as_ptr(), queue_ptr.as_ptr(), &mut err);
+            if let Err(e) = check_rs2_error!(
+                err,
+                ProcessingBlockConstructionError::CouldNotStartProcessingQueue
+            ) {
+                sys::rs2_delete_frame_queue(queue_ptr.as_ptr());
+                return Err(e);
+            }
+
+            sys::rs2_delete_frame_queue(self.queue_ptr.as_ptr());
+        }
+
+        self.queue_ptr = queue_ptr;
+        Ok(())
+    }
+
+    /// Sets the `value` associated with the provided `option` for the processing block.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptionSetError::CouldNotSetOption`] if the option could not be set, e.g. if the
+    /// processing block does not support it or the value is invalid.
+    pub(crate) fn set_option(
+        &mut self,
+        option: Rs2Option,
+        value: f32,
+    ) -> Result<(), OptionSetError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_set_option(
+                self.block_ptr.as_ptr().cast::<sys::rs2_options>(),
+                #[allow(clippy::useless_conversion)]
+                (option as i32).try_into().unwrap(),
+                value,
+                &mut err,
+            );
+            check_rs2_error!(err, OptionSetError::CouldNotSetOption)
+        }
+    }
+
+    /// Predicate for whether the processing block supports the given option.
+    pub(crate) fn supports_option(&self, option: Rs2Option) -> bool {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let val = sys::rs2_supports_option(
+                self.block_ptr.as_ptr().cast::<sys::rs2_options>(),
+                #[allow(clippy::useless_conversion)]
+                (option as i32).try_into().unwrap(),
+                &mut err,
+            );
+
+            if err.as_ref().is_none() {
+                val != 0
+            } else {
+                sys::rs2_free_error(err);
+                false
+            }
+        }
+    }
+
+    /// Gets the range for the given option.
+    ///
+    /// Returns `None` if the processing block does not support the option.
+    pub(crate) fn get_option_range(&self, option: Rs2Option) -> Option<Rs2OptionRange> {
+        if !self.supports_option(option) {
+            return None;
+        }
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+
+            let mut min = MaybeUninit::uninit();
+            let mut max = MaybeUninit::uninit();
+            let mut step = MaybeUninit::uninit();
+            let mut default = MaybeUninit::uninit();
+
+            sys::rs2_get_option_range(
+                self.block_ptr.as_ptr().cast::<sys::rs2_options>(),
+                #[allow(clippy::useless_conversion)]
+                (option as i32).try_into().unwrap(),
+                min.as_mut_ptr(),
+                max.as_mut_ptr(),
+                step.as_mut_ptr(),
+                default.as_mut_ptr(),
+                &mut err,
+            );
+
+            if err.as_ref().is_none() {
+                Some(Rs2OptionRange {
+                    min: min.assume_init(),
+                    max: max.assume_init(),
+                    step: step.assume_init(),
+                    default: default.assume_init(),
+                })
+            } else {
+                sys::rs2_free_error(err);
+                None
+            }
+        }
+    }
+
+    /// Gets every option supported by the processing block, along with the range of values it
+    /// accepts.
+    ///
+    /// Options whose range cannot be retrieved are omitted.
+    pub(crate) fn supported_options(&self) -> Vec<(Rs2Option, Rs2OptionRange)> {
+        (0..sys::rs2_option_RS2_OPTION_COUNT as i32)
+            .filter_map(Rs2Option::from_i32)
+            .filter_map(|option| Some((option, self.get_option_range(option)?)))
+            .collect()
+    }
+
+    /// Passes a frame to the processing block, transferring ownership of the frame to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::CouldNotProcessFrame`] if the processing block fails to
+    /// process the frame.
+    pub(crate) fn queue<F>(&mut self, frame: F) -> Result<(), ProcessFrameError>
+    where
+        F: FrameEx,
+    {
+        unsafe { self.queue_raw(frame.get_owned_raw()) }
+    }
+
+    /// Passes a raw frame to the processing block, transferring ownership of the frame to it.
+    ///
+    /// This is used for frames that do not implement [`FrameEx`], such as framesets.
+    ///
+    /// # Safety
+    ///
+    /// The caller must own a reference to `frame_ptr`, which is consumed by this call.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::CouldNotProcessFrame`] if the processing block fails to
+    /// process the frame.
+    pub(crate) unsafe fn queue_raw(
+        &mut self,
+        frame_ptr: NonNull<sys::rs2_frame>,
+    ) -> Result<(), ProcessFrameError> {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        sys::rs2_process_frame(self.block_ptr.as_ptr(), frame_ptr.as_ptr(), &mut err);
+        check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)
+    }
+
+    /// Waits for the next processed frame, blocking the calling thread.
+    ///
+    /// If `timeout` is `None`, the [default timeout](realsense_sys::RS2_DEFAULT_TIMEOUT) is
+    /// applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::DidErrorDuringFrameWait`] if an internal error occurs while
+    /// waiting for the processed frame.
+    ///
+    /// Returns [`ProcessFrameError::DidTimeoutBeforeFrameArrival`] if the thread waits longer
+    /// than `timeout` without a processed frame becoming available.
+    ///
+    /// Returns an error if the processed frame cannot be converted to `F`.
+    pub(crate) fn wait<F>(&mut self, timeout: Option<Duration>) -> Result<F>
+    where
+        F: TryFrom<NonNull<sys::rs2_frame>>,
+        F::Error: Into<anyhow::Error>,
+    {
+        unsafe {
+            wait_for_frame(
+                self.queue_ptr,
+                timeout,
+                ProcessFrameError::DidErrorDuringFrameWait,
+            )?
+            .ok_or_else(|| ProcessFrameError::DidTimeoutBeforeFrameArrival.into())
+        }
+    }
+
+    /// Polls for the next processed frame without blocking.
+    ///
+    /// Returns [`Poll::Pending`] if no processed frame is available yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::DidErrorDuringFramePoll`] if an internal error occurs while
+    /// polling for the processed frame.
+    ///
+    /// Returns an error if the processed frame cannot be converted to `F`.
+    pub(crate) fn poll<F>(&mut self) -> Result<Poll<F>>
+    where
+        F: TryFrom<NonNull<sys::rs2_frame>>,
+        F::Error: Into<anyhow::Error>,
+    {
+        unsafe { poll_for_frame(self.queue_ptr, ProcessFrameError::DidErrorDuringFramePoll) }
+    }
+}
+
+/// Creates a frame queue which holds up to `queue_size` frames.
+///
+/// A size of zero is treated as one, since the queue has to be able to hold at least one frame. A
+/// size larger than [`i32::MAX`] is treated as [`i32::MAX`].
+///
+/// # Errors
+///
+/// Returns [`ProcessingBlockConstructionError::CouldNotCreateProcessingQueue`] if the frame queue
+/// cannot be created.
+fn create_queue(
+    queue_size: u32,
+) -> Result<NonNull<sys::rs2_frame_queue>, ProcessingBlockConstructionError> {
+    create_raw_queue(
+        i32::try_from(queue_size.max(1)).unwrap_or(i32::MAX),
+        ProcessingBlockConstructionError::CouldNotCreateProcessingQueue,
+    )
+}
@@ -0,0 +1,127 @@
+//! Processing block for grouping frames from different streams into framesets.
+
+use super::processing_block::{
+    ProcessFrameError, ProcessingBlock, ProcessingBlockConstructionError, DEFAULT_QUEUE_SIZE,
+};
+use crate::{
+    check_rs2_error,
+    frame::{CompositeFrame, FrameEx},
+    kind::{Rs2Option, Rs2OptionRange},
+};
+use anyhow::Result;
+use realsense_sys as sys;
+use std::{ptr::NonNull, task::Poll, time::Duration};
+
+/// Processing block that groups frames from different streams into framesets.
+///
+/// Frames of any stream can be queued, and are matched up by their timestamps. Once a frame from
+/// every stream that has been seen so far is available, they are output together as a
+/// [`CompositeFrame`].
+///
+/// This is the same synchronization that a pipeline performs internally. It is needed to get a
+/// synchronized frameset back after the frames of a frameset have been split up, e.g. to run each
+/// of them through a different processing block, or when streaming directly from
+/// [sensors](crate::sensor::Sensor) instead of from a pipeline.
+#[derive(Debug)]
+pub struct Syncer {
+    /// The underlying processing block and its output queue.
+    block: ProcessingBlock,
+}
+
+impl Syncer {
+    /// Constructs a new syncer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the processing block or its queue cannot
+    /// be created.
+    pub fn new() -> Result<Self, ProcessingBlockConstructionError> {
+        Self::new_with_queue_size(DEFAULT_QUEUE_SIZE)
+    }
+
+    /// Like [`new`](Self::new), but holds up to `queue_size` processed frames until they are
+    /// retrieved.
+    ///
+    /// See [queue size](crate::processing_blocks#queue-size) for how this trades latency against
+    /// dropped frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the processing block or its queue cannot
+    /// be created.
+    pub fn new_with_queue_size(queue_size: u32) -> Result<Self, ProcessingBlockConstructionError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let block_ptr = sys::rs2_create_sync_processing_block(&mut err);
+            check_rs2_error!(
+                err,
+                ProcessingBlockConstructionError::CouldNotCreateProcessingBlock
+            )?;
+
+            Ok(Self {
+                block: ProcessingBlock::new(NonNull::new(block_ptr).unwrap(), queue_size)?,
+            })
+        }
+    }
+
+    /// Gets every option supported by the block, along with the range of values it accepts.
+    ///
+    /// This is useful to e.g. generate the controls of a settings panel for the block.
+    pub fn supported_options(&self) -> Vec<(Rs2Option, Rs2OptionRange)> {
+        self.block.supported_options()
+    }
+
+    /// Sets the number of processed frames that the block holds until they are retrieved.
+    ///
+    /// See [queue size](crate::processing_blocks#queue-size) for how this trades latency against
+    /// dropped frames. Processed frames that have not been retrieved yet are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the new queue cannot be created, in which
+    /// case the previous queue is kept.
+    pub fn set_queue_size(
+        &mut self,
+        queue_size: u32,
+    ) -> Result<(), ProcessingBlockConstructionError> {
+        self.block.set_queue_size(queue_size)
+    }
+
+    /// Queues a frame to be synchronized, transferring ownership of the frame to the block.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::CouldNotProcessFrame`] if the frame cannot be processed.
+    pub fn queue<F>(&mut self, frame: F) -> Result<(), ProcessFrameError>
+    where
+        F: FrameEx,
+    {
+        self.block.queue(frame)
+    }
+
+    /// Waits for the next synchronized frameset, blocking the calling thread.
+    ///
+    /// If `timeout` is `None`, the [default timeout](realsense_sys::RS2_DEFAULT_TIMEOUT) is
+    /// applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::DidTimeoutBeforeFrameArrival`] if no frameset becomes
+    /// available before the timeout, or another [`ProcessFrameError`] if an internal error occurs
+    /// while waiting.
+    pub fn wait(&mut self, timeout: Option<Duration>) -> Result<CompositeFrame> {
+        self.block.wait(timeout)
+    }
+
+    /// Polls for the next synchronized frameset without blocking.
+    ///
+    /// Returns [`Poll::Pending`] if no frameset is available yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::DidErrorDuringFramePoll`] if an internal error occurs while
+    /// polling.
+    pub fn poll(&mut self) -> Result<Poll<CompositeFrame>> {
+        self.block.poll()
+    }
+}
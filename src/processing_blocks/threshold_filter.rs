@@ -0,0 +1,151 @@
+//! Processing block for discarding depth outside of a range of distances.
+
+use super::processing_block::{
+    ProcessFrameError, ProcessingBlock, ProcessingBlockConstructionError, DEFAULT_QUEUE_SIZE,
+};
+use crate::{
+    check_rs2_error,
+    frame::DepthFrame,
+    kind::{OptionSetError, Rs2Option, Rs2OptionRange},
+};
+use anyhow::Result;
+use realsense_sys as sys;
+use std::{ptr::NonNull, task::Poll, time::Duration};
+
+/// Processing block that discards depth outside of a range of distances.
+///
+/// Every pixel of a [`DepthFrame`] that is closer than the minimum distance or further than the
+/// maximum distance is set to zero, i.e. marked as having no valid depth. Pixels within the range
+/// are left as-is.
+///
+/// This is a cheap way to crop the depth to a working volume, e.g. before calculating a
+/// [`PointCloud`](super::PointCloud).
+#[derive(Debug)]
+pub struct ThresholdFilter {
+    /// The underlying processing block and its output queue.
+    block: ProcessingBlock,
+}
+
+impl ThresholdFilter {
+    /// Constructs a new threshold filter with the default distance range of librealsense2.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the processing block or its queue cannot
+    /// be created.
+    pub fn new() -> Result<Self, ProcessingBlockConstructionError> {
+        Self::new_with_queue_size(DEFAULT_QUEUE_SIZE)
+    }
+
+    /// Like [`new`](Self::new), but holds up to `queue_size` processed frames until they are
+    /// retrieved.
+    ///
+    /// See [queue size](crate::processing_blocks#queue-size) for how this trades latency against
+    /// dropped frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the processing block or its queue cannot
+    /// be created.
+    pub fn new_with_queue_size(queue_size: u32) -> Result<Self, ProcessingBlockConstructionError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let block_ptr = sys::rs2_create_threshold(&mut err);
+            check_rs2_error!(
+                err,
+                ProcessingBlockConstructionError::CouldNotCreateProcessingBlock
+            )?;
+
+            Ok(Self {
+                block: ProcessingBlock::new(NonNull::new(block_ptr).unwrap(), queue_size)?,
+            })
+        }
+    }
+
+    /// Sets the range of distances, in meters, that are kept by the filter.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptionSetError::CouldNotSetOption`] if either distance is outside of the range
+    /// supported by the processing block.
+    pub fn set_distance_range(&mut self, min: f32, max: f32) -> Result<(), OptionSetError> {
+        self.block.set_option(Rs2Option::MinDistance, min)?;
+        self.block.set_option(Rs2Option::MaxDistance, max)
+    }
+
+    /// Gets every option supported by the block, along with the range of values it accepts.
+    ///
+    /// This is useful to e.g. generate the controls of a settings panel for the block.
+    pub fn supported_options(&self) -> Vec<(Rs2Option, Rs2OptionRange)> {
+        self.block.supported_options()
+    }
+
+    /// Sets the number of processed frames that the block holds until they are retrieved.
+    ///
+    /// See [queue size](crate::processing_blocks#queue-size) for how this trades latency against
+    /// dropped frames. Processed frames that have not been retrieved yet are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the new queue cannot be created, in which
+    /// case the previous queue is kept.
+    pub fn set_queue_size(
+        &mut self,
+        queue_size: u32,
+    ) -> Result<(), ProcessingBlockConstructionError> {
+        self.block.set_queue_size(queue_size)
+    }
+
+    /// Queues a depth frame to be filtered, transferring ownership of the frame to the block.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::CouldNotProcessFrame`] if the frame cannot be processed.
+    pub fn queue(&mut self, frame: DepthFrame) -> Result<(), ProcessFrameError> {
+        self.block.queue(frame)
+    }
+
+    /// Waits for the next filtered depth frame, blocking the calling thread.
+    ///
+    /// If `timeout` is `None`, the [default timeout](realsense_sys::RS2_DEFAULT_TIMEOUT) is
+    /// applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::DidTimeoutBeforeFrameArrival`] if no filtered frame becomes
+    /// available before the timeout, or another [`ProcessFrameError`] if an internal error occurs
+    /// while waiting.
+    ///
+    /// Returns an error if the filtered frame cannot be constructed.
+    pub fn wait(&mut self, timeout: Option<Duration>) -> Result<DepthFrame> {
+        self.block.wait(timeout)
+    }
+
+    /// Polls for the next filtered depth frame without blocking.
+    ///
+    /// Returns [`Poll::Pending`] if no filtered frame is available yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::DidErrorDuringFramePoll`] if an internal error occurs while
+    /// polling.
+    ///
+    /// Returns an error if the filtered frame cannot be constructed.
+    pub fn poll(&mut self) -> Result<Poll<DepthFrame>> {
+        self.block.poll()
+    }
+
+    /// Filters a single depth frame, blocking until the result is available.
+    ///
+    /// This is equivalent to calling [`queue`](Self::queue) followed by [`wait`](Self::wait)
+    /// with the default timeout.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the frame cannot be processed or the filtered frame does not become
+    /// available.
+    pub fn process(&mut self, frame: DepthFrame) -> Result<DepthFrame> {
+        self.queue(frame)?;
+        self.wait(None)
+    }
+}
@@ -15,18 +15,27 @@ use crate::{
     base::Rs2Roi,
     check_rs2_error,
     device::{Device, DeviceConstructionError},
+    frame::{frame_of_category, FrameCategory},
     kind::{
-        OptionSetError, Rs2CameraInfo, Rs2Exception, Rs2Extension, Rs2Option, Rs2OptionRange,
+        OptionSetError, OutOfRangeMode, Rs2CameraInfo, Rs2Exception, Rs2Extension, Rs2LogSeverity,
+        Rs2NotificationCategory, Rs2Option, Rs2OptionRange, Rs2StreamKind, Rs2VisualPreset,
         SENSOR_EXTENSIONS,
     },
     stream_profile::StreamProfile,
 };
 use anyhow::Result;
+use num_traits::FromPrimitive;
 use realsense_sys as sys;
 use std::{
-    convert::{From, TryInto},
+    collections::HashMap,
+    convert::{From, TryFrom, TryInto},
     ffi::CStr,
+    fmt,
+    iter::FromIterator,
     mem::MaybeUninit,
+    ops::{Deref, DerefMut},
+    os::raw::{c_uint, c_void},
+    panic::{self, AssertUnwindSafe},
     ptr::NonNull,
 };
 use thiserror::Error;
@@ -50,11 +59,249 @@ pub enum SensorConstructionError {
 /// from any C++ exceptions that occur.
 #[derive(Error, Debug)]
 pub enum RoiSetError {
+    /// The sensor does not support the [`Rs2Extension::Roi`] extension.
+    #[error("Sensor does not support setting a region of interest.")]
+    RoiNotSupported,
     /// Could not set region of interest for sensor.
     #[error("Could not set region of interest for sensor. Type: {0}; Reason: {1}")]
     CouldNotSetRoi(Rs2Exception, String),
 }
 
+/// Type describing errors that can occur when streaming directly from a sensor.
+///
+/// Follows the standard pattern of errors where the enum variant describes what the low-level code
+/// was attempting to do while the string carried alongside describes the underlying error message
+/// from any C++ exceptions that occur.
+#[derive(Error, Debug)]
+pub enum SensorStreamingError {
+    /// Could not open the requested stream profiles on the sensor.
+    #[error("Could not open stream profiles on sensor. Type: {0}; Reason: {1}")]
+    CouldNotOpenStreams(Rs2Exception, String),
+    /// Could not start streaming from the sensor.
+    #[error("Could not start streaming from sensor. Type: {0}; Reason: {1}")]
+    CouldNotStartStreaming(Rs2Exception, String),
+    /// Could not stop streaming from the sensor.
+    #[error("Could not stop streaming from sensor. Type: {0}; Reason: {1}")]
+    CouldNotStopStreaming(Rs2Exception, String),
+    /// Could not close the stream profiles opened on the sensor.
+    #[error("Could not close stream profiles on sensor. Type: {0}; Reason: {1}")]
+    CouldNotCloseStreams(Rs2Exception, String),
+}
+
+/// Occurs when the depth scale of a depth sensor cannot be retrieved.
+#[derive(Error, Debug)]
+#[error("Could not get depth scale from sensor. Type: {0}; Reason: {1}")]
+pub struct CouldNotGetDepthScaleError(pub Rs2Exception, pub String);
+
+/// Occurs when the notifications callback of a sensor cannot be set.
+#[derive(Error, Debug)]
+#[error("Could not set the notifications callback. Type: {0}; Reason: {1}")]
+pub struct CouldNotSetNotificationsCallbackError(pub Rs2Exception, pub String);
+
+/// Enumeration over possible errors that can occur when managing the map of a pose sensor.
+#[derive(Error, Debug)]
+pub enum LocalizationMapError {
+    /// Could not export the localization map from the sensor.
+    #[error("Could not export localization map. Type: {0}; Reason: {1}")]
+    CouldNotExportMap(Rs2Exception, String),
+    /// Could not import the localization map onto the sensor.
+    #[error("Could not import localization map. Type: {0}; Reason: {1}")]
+    CouldNotImportMap(Rs2Exception, String),
+    /// The sensor rejected the imported localization map.
+    #[error("Localization map was rejected by the sensor.")]
+    MapRejected,
+    /// Could not set a static node.
+    #[error("Could not set static node. Type: {0}; Reason: {1}")]
+    CouldNotSetStaticNode(Rs2Exception, String),
+    /// The sensor rejected the static node.
+    #[error("Static node was rejected by the sensor.")]
+    StaticNodeRejected,
+    /// Could not get a static node.
+    #[error("Could not get static node. Type: {0}; Reason: {1}")]
+    CouldNotGetStaticNode(Rs2Exception, String),
+    /// Could not remove a static node.
+    #[error("Could not remove static node. Type: {0}; Reason: {1}")]
+    CouldNotRemoveStaticNode(Rs2Exception, String),
+}
+
+/// A named location saved in the localization map of a pose sensor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StaticNode {
+    /// X, Y, Z values of translation, in meters, in the coordinate system of the tracking session.
+    pub translation: [f32; 3],
+    /// Qi, Qj, Qk, Qr components of rotation, in the coordinate system of the tracking session.
+    pub rotation: [f32; 4],
+}
+
+/// A snapshot of the temperatures and illumination state of a sensor.
+///
+/// Every field is `None` if the sensor does not support the corresponding option, e.g. color
+/// sensors have no projector. Returned by [`Sensor::health_check`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SensorHealth {
+    /// The temperature of the ASIC, in degrees Celsius.
+    pub asic_temperature: Option<f32>,
+    /// The temperature of the projector, in degrees Celsius.
+    pub projector_temperature: Option<f32>,
+    /// The temperature of the motion module, in degrees Celsius.
+    pub motion_module_temperature: Option<f32>,
+    /// The current exposure of the sensor, in microseconds.
+    pub exposure: Option<f32>,
+    /// Whether auto-exposure is enabled.
+    pub auto_exposure_enabled: Option<bool>,
+    /// Whether the emitter (i.e. the laser projector) is enabled.
+    pub emitter_enabled: Option<bool>,
+    /// The power of the laser projector, in milliwatts.
+    pub laser_power: Option<f32>,
+}
+
+/// The values of the writable options of a sensor, taken at one point in time.
+///
+/// Taken with [`Sensor::snapshot_options`] and applied with [`Sensor::restore_options`], e.g. to
+/// save a tuned configuration and reapply it after a hardware reset, or on another unit of the
+/// same model. With the `serde` feature enabled, snapshots can be serialized to persist them.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptionsSnapshot {
+    /// The value of every option in the snapshot.
+    values: HashMap<Rs2Option, f32>,
+}
+
+impl OptionsSnapshot {
+    /// Gets the value of `option` in the snapshot, if the snapshot contains it.
+    pub fn get(&self, option: Rs2Option) -> Option<f32> {
+        self.values.get(&option).copied()
+    }
+
+    /// Sets the value of `option` in the snapshot, returning its previous value if any.
+    pub fn set(&mut self, option: Rs2Option, value: f32) -> Option<f32> {
+        self.values.insert(option, value)
+    }
+
+    /// Removes `option` from the snapshot, returning its value if any.
+    ///
+    /// Removed options are left untouched when the snapshot is restored.
+    pub fn remove(&mut self, option: Rs2Option) -> Option<f32> {
+        self.values.remove(&option)
+    }
+
+    /// Iterates over the options in the snapshot and their values, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Rs2Option, f32)> + '_ {
+        self.values.iter().map(|(option, value)| (*option, *value))
+    }
+
+    /// Gets the number of options in the snapshot.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Predicate for whether the snapshot contains no options.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Gets the options in the order in which they are restored.
+    ///
+    /// Applying a visual preset overwrites many other options, so it is restored first. The
+    /// remaining options are restored in the order librealsense2 defines them, so that restoring
+    /// is deterministic.
+    fn restore_order(&self) -> Vec<(Rs2Option, f32)> {
+        let mut options = self.iter().collect::<Vec<_>>();
+        options.sort_by_key(|(option, _)| (*option != Rs2Option::VisualPreset, *option as i32));
+        options
+    }
+}
+
+impl FromIterator<(Rs2Option, f32)> for OptionsSnapshot {
+    fn from_iter<I: IntoIterator<Item = (Rs2Option, f32)>>(iter: I) -> Self {
+        Self {
+            values: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Type of the callback invoked by librealsense2 with every frame streamed from a sensor.
+///
+/// The callback takes ownership of the frame pointer it is passed.
+type FrameCallback = Box<dyn FnMut(NonNull<sys::rs2_frame>) + Send>;
+
+/// The boxed closure type that is invoked with every notification emitted by a sensor.
+type NotificationsCallback = Box<dyn FnMut(Notification) + Send>;
+
+/// A notification emitted by a sensor, e.g. about dropped frames or a hardware error.
+///
+/// Notifications are delivered to the callback registered through
+/// [`Sensor::set_notifications_callback`].
+#[derive(Debug, Clone)]
+pub struct Notification {
+    /// A human-readable description of the notification.
+    description: String,
+    /// The time at which the notification was raised, in milliseconds.
+    timestamp: f64,
+    /// The severity of the notification.
+    severity: Rs2LogSeverity,
+    /// The category of the notification.
+    category: Rs2NotificationCategory,
+}
+
+impl Notification {
+    /// Gets a human-readable description of the notification.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Gets the time at which the notification was raised, in milliseconds.
+    pub fn timestamp(&self) -> f64 {
+        self.timestamp
+    }
+
+    /// Gets the severity of the notification.
+    pub fn severity(&self) -> Rs2LogSeverity {
+        self.severity
+    }
+
+    /// Gets the category of the notification.
+    pub fn category(&self) -> Rs2NotificationCategory {
+        self.category
+    }
+
+    /// Copies the contents of a notification handle.
+    ///
+    /// Returns `None` if any part of the notification cannot be read.
+    ///
+    /// # Safety
+    ///
+    /// `notification_ptr` must be a valid notification handle, such as one passed to
+    /// [`notifications_trampoline`]. The handle is only borrowed.
+    unsafe fn from_raw(notification_ptr: NonNull<sys::rs2_notification>) -> Option<Self> {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        let ptr = notification_ptr.as_ptr();
+
+        let description = sys::rs2_get_notification_description(ptr, &mut err);
+        if err.as_ref().is_none() {
+            let description = CStr::from_ptr(description).to_string_lossy().into_owned();
+            let timestamp = sys::rs2_get_notification_timestamp(ptr, &mut err);
+            if err.as_ref().is_none() {
+                let severity = sys::rs2_get_notification_severity(ptr, &mut err);
+                if err.as_ref().is_none() {
+                    let category = sys::rs2_get_notification_category(ptr, &mut err);
+                    if err.as_ref().is_none() {
+                        return Some(Self {
+                            description,
+                            timestamp,
+                            severity: Rs2LogSeverity::from_i32(severity as i32)?,
+                            category: Rs2NotificationCategory::from_i32(category as i32)?,
+                        });
+                    }
+                }
+            }
+        }
+
+        sys::rs2_free_error(err);
+        None
+    }
+}
+
 /// Type for holding sensor-related data.
 ///
 /// A sensor in librealsense2 corresponds to a physical component on the unit in some way, shape,
@@ -73,11 +320,57 @@ pub struct Sensor {
     sensor_ptr: NonNull<sys::rs2_sensor>,
     /// Boolean used for telling us if we should drop the sensor pointer or not.
     should_drop: bool,
+    /// The callback that frames are delivered to while the sensor is streaming.
+    ///
+    /// This is double-boxed so that the pointer handed to librealsense2 stays thin and stable.
+    frame_callback: Option<Box<FrameCallback>>,
+    /// The callback registered through [`Sensor::set_notifications_callback`], if any.
+    ///
+    /// This is double-boxed so that the pointer handed to librealsense2 stays thin and stable.
+    notifications_callback: Option<Box<NotificationsCallback>>,
+}
+
+impl fmt::Debug for Sensor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sensor")
+            .field("sensor_ptr", &self.sensor_ptr)
+            .field("should_drop", &self.should_drop)
+            .field("is_streaming", &self.frame_callback.is_some())
+            .field(
+                "has_notifications_callback",
+                &self.notifications_callback.is_some(),
+            )
+            .finish()
+    }
 }
 
 impl Drop for Sensor {
     fn drop(&mut self) {
         unsafe {
+            // librealsense2 must not call back into the frame callback once it has been freed.
+            if self.frame_callback.is_some() {
+                let mut err = std::ptr::null_mut::<sys::rs2_error>();
+                sys::rs2_stop(self.sensor_ptr.as_ptr(), &mut err);
+                if err.as_ref().is_some() {
+                    sys::rs2_free_error(err);
+                }
+            }
+
+            // The sensor itself outlives this handle, so its notifications are redirected away from
+            // the callback before it is freed.
+            if self.notifications_callback.is_some() {
+                let mut err = std::ptr::null_mut::<sys::rs2_error>();
+                sys::rs2_set_notifications_callback(
+                    self.sensor_ptr.as_ptr(),
+                    Some(ignore_notifications_trampoline),
+                    std::ptr::null_mut(),
+                    &mut err,
+                );
+                if err.as_ref().is_some() {
+                    sys::rs2_free_error(err);
+                }
+            }
+
             if self.should_drop {
                 sys::rs2_delete_sensor(self.sensor_ptr.as_ptr());
             }
@@ -93,10 +386,63 @@ impl std::convert::From<NonNull<sys::rs2_sensor>> for Sensor {
         Sensor {
             sensor_ptr,
             should_drop: false,
+            frame_callback: None,
+            notifications_callback: None,
         }
     }
 }
 
+/// Trampoline through which librealsense2 invokes the frame callback of a streaming sensor.
+///
+/// # Safety
+///
+/// `user` must point to the [`FrameCallback`] owned by the [`Sensor`] that started streaming with
+/// this trampoline. Ownership of the frame is taken by this function.
+unsafe extern "C" fn frame_trampoline(frame: *mut sys::rs2_frame, user: *mut c_void) {
+    let frame = match NonNull::new(frame) {
+        Some(frame) => frame,
+        None => return,
+    };
+
+    let callback = &mut *(user as *mut FrameCallback);
+
+    // Unwinding across the FFI boundary is undefined behaviour, so any panic in the callback stops
+    // here.
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| callback(frame)));
+}
+
+/// Trampoline through which librealsense2 invokes the notifications callback of a sensor.
+///
+/// # Safety
+///
+/// `user` must point to the [`NotificationsCallback`] owned by the [`Sensor`] that registered this
+/// trampoline.
+unsafe extern "C" fn notifications_trampoline(
+    notification: *mut sys::rs2_notification,
+    user: *mut c_void,
+) {
+    let notification = match NonNull::new(notification).and_then(|n| Notification::from_raw(n)) {
+        Some(notification) => notification,
+        None => return,
+    };
+
+    dispatch_notification(&mut *(user as *mut NotificationsCallback), notification);
+}
+
+/// Trampoline which discards notifications, registered once a notifications callback is dropped.
+unsafe extern "C" fn ignore_notifications_trampoline(
+    _notification: *mut sys::rs2_notification,
+    _user: *mut c_void,
+) {
+}
+
+/// Invokes the notifications `callback` with `notification`.
+fn dispatch_notification(callback: &mut NotificationsCallback, notification: Notification) {
+    // Unwinding across the FFI boundary is undefined behaviour, so any panic in the callback stops
+    // here.
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| callback(notification)));
+}
+
 impl Sensor {
     /// Create a sensor from a sensor list and an index
     ///
@@ -160,26 +506,77 @@ impl Sensor {
     pub fn extension(&self) -> Rs2Extension {
         let ext = SENSOR_EXTENSIONS
             .iter()
-            .find(|ext| unsafe {
-                let mut err = std::ptr::null_mut::<sys::rs2_error>();
-                let is_extendable = sys::rs2_is_sensor_extendable_to(
-                    self.sensor_ptr.as_ptr(),
-                    #[allow(clippy::useless_conversion)]
-                    (**ext as i32).try_into().unwrap(),
-                    &mut err,
-                );
-
-                if err.as_ref().is_none() {
-                    is_extendable != 0
-                } else {
-                    sys::rs2_free_error(err);
-                    false
-                }
-            })
+            .find(|ext| self.is_extendable_to(**ext))
             .unwrap();
         *ext
     }
 
+    /// Get every extension supported by the sensor.
+    ///
+    /// A sensor usually supports several extensions, e.g. the stereo module of a D400 device is
+    /// both a [`DepthSensor`](Rs2Extension::DepthSensor) and a
+    /// [`DepthStereoSensor`](Rs2Extension::DepthStereoSensor), whereas
+    /// [`extension`](Self::extension) only returns the first of them.
+    pub fn extensions(&self) -> Vec<Rs2Extension> {
+        SENSOR_EXTENSIONS
+            .iter()
+            .copied()
+            .filter(|ext| self.is_extendable_to(*ext))
+            .collect()
+    }
+
+    /// Predicate for whether the sensor can be extended to the given extension type.
+    fn is_extendable_to(&self, extension: Rs2Extension) -> bool {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let is_extendable = sys::rs2_is_sensor_extendable_to(
+                self.sensor_ptr.as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (extension as i32).try_into().unwrap(),
+                &mut err,
+            );
+
+            if err.as_ref().is_none() {
+                is_extendable != 0
+            } else {
+                sys::rs2_free_error(err);
+                false
+            }
+        }
+    }
+
+    /// Attempts to convert the sensor into a [`DepthSensor`].
+    ///
+    /// Succeeds iff the sensor supports the [`Rs2Extension::DepthSensor`] extension, which gives
+    /// access to depth-only APIs such as [`DepthSensor::depth_scale`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the original sensor back if it is not a depth sensor.
+    pub fn try_into_depth_sensor(self) -> Result<DepthSensor, Sensor> {
+        if self.is_extendable_to(Rs2Extension::DepthSensor) {
+            Ok(DepthSensor(self))
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Attempts to convert the sensor into a [`PoseSensor`].
+    ///
+    /// Succeeds iff the sensor supports the [`Rs2Extension::PoseSensor`] extension, i.e. it is the
+    /// tracking module of a T200 series device such as the T265.
+    ///
+    /// # Errors
+    ///
+    /// Returns the original sensor back if it is not a pose sensor.
+    pub fn try_into_pose_sensor(self) -> Result<PoseSensor, Sensor> {
+        if self.is_extendable_to(Rs2Extension::PoseSensor) {
+            Ok(PoseSensor(self))
+        } else {
+            Err(self)
+        }
+    }
+
     /// Get the value associated with the provided Rs2Option for the sensor.
     ///
     /// Returns An `f32` value corresponding to that option within the librealsense2 library, or None
@@ -245,6 +642,229 @@ impl Sensor {
         }
     }
 
+    /// Sets the `value` associated with the provided `option`, after checking it against the
+    /// [range](Self::get_option_range) of the option.
+    ///
+    /// A value outside of the range is either clamped to the range or rejected, depending on
+    /// `mode`, which gives e.g. sliders in a UI predictable behavior across sensors. Returns the
+    /// value that the option has after setting it, which is read back from the sensor since the
+    /// sensor may round the value, e.g. to its step. If it cannot be read back, the value that
+    /// was set is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptionSetError::ValueOutOfRange`] if `value` is outside of the range of the
+    /// option and `mode` is [`OutOfRangeMode::Reject`], or if `value` is NaN.
+    ///
+    /// Returns the same errors as [`set_option`](Self::set_option) otherwise.
+    pub fn set_option_checked(
+        &mut self,
+        option: Rs2Option,
+        value: f32,
+        mode: OutOfRangeMode,
+    ) -> Result<f32, OptionSetError> {
+        let range = self
+            .get_option_range(option)
+            .ok_or(OptionSetError::OptionNotSupported)?;
+        let value = range.check(value, mode)?;
+
+        self.set_option(option, value)?;
+        Ok(self.get_option(option).unwrap_or(value))
+    }
+
+    /// Sets an option whose value represents one of a discrete set of states, such as a preset.
+    ///
+    /// This is [`set_option`](Self::set_option) for options whose values are an enumeration,
+    /// which librealsense2 encodes as floats. Any type that converts to the float value of the
+    /// option, e.g. [`Rs2VisualPreset`], can be passed directly instead of its magic number.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`set_option`](Self::set_option).
+    pub fn set_option_enum<T>(&mut self, option: Rs2Option, value: T) -> Result<(), OptionSetError>
+    where
+        T: Into<f32>,
+    {
+        self.set_option(option, value.into())
+    }
+
+    /// Applies a visual preset to the sensor.
+    ///
+    /// Visual presets are only supported by the depth sensor of D400 series devices.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`set_option`](Self::set_option) for
+    /// [`Rs2Option::VisualPreset`].
+    pub fn set_visual_preset(&mut self, preset: Rs2VisualPreset) -> Result<(), OptionSetError> {
+        self.set_option_enum(Rs2Option::VisualPreset, preset)
+    }
+
+    /// Gets the visual preset currently applied to the sensor.
+    ///
+    /// Returns `None` if the sensor does not support visual presets, or reports a value that is
+    /// not a known preset. Changing any of the options that a preset controls switches the sensor
+    /// to [`Rs2VisualPreset::Custom`].
+    pub fn get_visual_preset(&self) -> Option<Rs2VisualPreset> {
+        self.get_option(Rs2Option::VisualPreset)
+            .and_then(Rs2VisualPreset::from_f32)
+    }
+
+    /// Enables or disables global time on the sensor.
+    ///
+    /// With global time enabled, librealsense2 translates the hardware timestamps of the sensor's
+    /// frames into the host's clock, and their
+    /// [timestamp domain](crate::frame::FrameEx::timestamp_domain) is reported as
+    /// [`GlobalTime`](crate::kind::Rs2TimestampDomain::GlobalTime). This makes timestamps
+    /// comparable across sensors and devices, which is necessary to time-synchronize them. With
+    /// global time disabled, timestamps are reported in the sensor's own domain instead, usually
+    /// its hardware clock.
+    ///
+    /// The option applies to frames streamed after it has been set.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptionSetError::OptionNotSupported`] if the sensor does not support global time,
+    /// or another [`OptionSetError`] if the option cannot be set.
+    pub fn enable_global_time(&mut self, enabled: bool) -> Result<(), OptionSetError> {
+        self.set_option(
+            Rs2Option::GlobalTimeEnabled,
+            if enabled { 1.0 } else { 0.0 },
+        )
+    }
+
+    /// Predicate for whether global time is enabled on the sensor.
+    ///
+    /// Returns `false` if the sensor does not support global time. See
+    /// [`enable_global_time`](Self::enable_global_time) for details.
+    pub fn is_global_time_enabled(&self) -> bool {
+        self.get_option(Rs2Option::GlobalTimeEnabled).unwrap_or(0.0) != 0.0
+    }
+
+    /// Gets the temperature of the ASIC, in degrees Celsius.
+    ///
+    /// Returns `None` if the sensor does not report the temperature of its ASIC.
+    pub fn asic_temperature(&self) -> Option<f32> {
+        self.get_option(Rs2Option::AsicTemperature)
+    }
+
+    /// Gets the temperature of the projector, in degrees Celsius.
+    ///
+    /// Returns `None` if the sensor does not report the temperature of a projector.
+    pub fn projector_temperature(&self) -> Option<f32> {
+        self.get_option(Rs2Option::ProjectorTemperature)
+    }
+
+    /// Gets the temperature of the motion module, in degrees Celsius.
+    ///
+    /// Returns `None` if the sensor does not report the temperature of a motion module.
+    pub fn motion_module_temperature(&self) -> Option<f32> {
+        self.get_option(Rs2Option::MotionModuleTemperature)
+    }
+
+    /// Gets a snapshot of the temperatures, exposure and emitter state of the sensor.
+    ///
+    /// This is useful for long-running deployments that need to throttle or raise alerts when a
+    /// device overheats. Each value is read with a separate call to
+    /// [`get_option`](Self::get_option), so the snapshot is not atomic.
+    pub fn health_check(&self) -> SensorHealth {
+        let is_enabled = |option| self.get_option(option).map(|value| value != 0.0);
+
+        SensorHealth {
+            asic_temperature: self.asic_temperature(),
+            projector_temperature: self.projector_temperature(),
+            motion_module_temperature: self.motion_module_temperature(),
+            exposure: self.get_option(Rs2Option::Exposure),
+            auto_exposure_enabled: is_enabled(Rs2Option::EnableAutoExposure),
+            emitter_enabled: is_enabled(Rs2Option::EmitterEnabled),
+            laser_power: self.get_option(Rs2Option::LaserPower),
+        }
+    }
+
+    /// Sets a group of options on the sensor, in order, as a single operation.
+    ///
+    /// This is useful for options that are correlated with one another, e.g. disabling
+    /// auto-exposure before setting the exposure and gain manually. If any option in the batch
+    /// fails to be set, every option that was already changed is restored to the value it had
+    /// before, so that the sensor is not left half-configured.
+    ///
+    /// Every option is checked for support and mutability before any of them are set, so these
+    /// failures never modify the sensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptionSetError::OptionNotSupported`] if any option is not supported on this
+    /// sensor.
+    ///
+    /// Returns [`OptionSetError::OptionIsReadOnly`] if any option is supported but cannot be set
+    /// on this sensor.
+    ///
+    /// Returns [`OptionSetError::CouldNotSetOption`] if an option could not be set for another
+    /// reason (invalid value, internal exception, etc.). Restoring the previous values is done on
+    /// a best-effort basis, and any errors while doing so are ignored.
+    pub fn set_options(&mut self, options: &[(Rs2Option, f32)]) -> Result<(), OptionSetError> {
+        for (option, _) in options {
+            if !self.supports_option(*option) {
+                return Err(OptionSetError::OptionNotSupported);
+            }
+
+            if self.is_option_read_only(*option) {
+                return Err(OptionSetError::OptionIsReadOnly);
+            }
+        }
+
+        let mut previous_values = Vec::with_capacity(options.len());
+        for (option, value) in options {
+            let previous_value = self.get_option(*option);
+
+            if let Err(e) = self.set_option(*option, *value) {
+                for (option, previous_value) in previous_values.into_iter().rev() {
+                    if let Some(previous_value) = previous_value {
+                        let _ = self.set_option(option, previous_value);
+                    }
+                }
+                return Err(e);
+            }
+            previous_values.push((*option, previous_value));
+        }
+        Ok(())
+    }
+
+    /// Takes a snapshot of the current values of every option that can be set on this sensor.
+    ///
+    /// Read-only options, such as temperatures, are not included since they cannot be restored.
+    pub fn snapshot_options(&self) -> OptionsSnapshot {
+        self.supported_options()
+            .into_iter()
+            .filter(|(option, _)| !self.is_option_read_only(*option))
+            .filter_map(|(option, _)| Some((option, self.get_option(option)?)))
+            .collect()
+    }
+
+    /// Restores the option values of a snapshot taken with
+    /// [`snapshot_options`](Self::snapshot_options).
+    ///
+    /// Options in the snapshot that this sensor does not support, or cannot set, are skipped. This
+    /// makes it possible to restore a snapshot taken on a similar, but not identical, sensor. The
+    /// remaining options are set as a batch with [`set_options`](Self::set_options), so if any of
+    /// them cannot be set, the sensor is restored to the values it had before.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptionSetError::CouldNotSetOption`] if an option could not be set, e.g. because
+    /// its value in the snapshot is out of range for this sensor.
+    pub fn restore_options(&mut self, snapshot: &OptionsSnapshot) -> Result<(), OptionSetError> {
+        let options = snapshot
+            .restore_order()
+            .into_iter()
+            .filter(|(option, _)| {
+                self.supports_option(*option) && !self.is_option_read_only(*option)
+            })
+            .collect::<Vec<_>>();
+
+        self.set_options(&options)
+    }
+
     /// Gets the range for a given option.
     ///
     /// Returns some option range if the sensor supports the option, else `None`.
@@ -286,6 +906,78 @@ impl Sensor {
         }
     }
 
+    /// Gets a human-readable description of what the given option controls.
+    ///
+    /// Returns `None` if the option is not supported or has no description.
+    pub fn get_option_description(&self, option: Rs2Option) -> Option<String> {
+        if !self.supports_option(option) {
+            return None;
+        }
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let description = sys::rs2_get_option_description(
+                self.sensor_ptr.as_ptr().cast::<sys::rs2_options>(),
+                #[allow(clippy::useless_conversion)]
+                (option as i32).try_into().unwrap(),
+                &mut err,
+            );
+
+            if err.as_ref().is_none() {
+                description
+                    .as_ref()
+                    .map(|d| CStr::from_ptr(d).to_string_lossy().into_owned())
+            } else {
+                sys::rs2_free_error(err);
+                None
+            }
+        }
+    }
+
+    /// Gets a human-readable description of what a specific `value` of the given option means.
+    ///
+    /// This is mostly useful for options whose values represent a discrete set of states, e.g. the
+    /// different presets of [`Rs2Option::VisualPreset`].
+    ///
+    /// Returns `None` if the option is not supported or the value has no description.
+    pub fn get_option_value_description(&self, option: Rs2Option, value: f32) -> Option<String> {
+        if !self.supports_option(option) {
+            return None;
+        }
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let description = sys::rs2_get_option_value_description(
+                self.sensor_ptr.as_ptr().cast::<sys::rs2_options>(),
+                #[allow(clippy::useless_conversion)]
+                (option as i32).try_into().unwrap(),
+                value,
+                &mut err,
+            );
+
+            if err.as_ref().is_none() {
+                description
+                    .as_ref()
+                    .map(|d| CStr::from_ptr(d).to_string_lossy().into_owned())
+            } else {
+                sys::rs2_free_error(err);
+                None
+            }
+        }
+    }
+
+    /// Gets every option supported by this sensor, along with the range of values it accepts.
+    ///
+    /// All known [`Rs2Option`] variants are checked against the sensor, so this is a convenient
+    /// way to discover which options can be queried or set, e.g. when building a settings panel.
+    /// Options whose range cannot be retrieved are omitted.
+    pub fn supported_options(&self) -> Vec<(Rs2Option, Rs2OptionRange)> {
+        (0..sys::rs2_option_RS2_OPTION_COUNT as i32)
+            .filter_map(Rs2Option::from_i32)
+            .filter_map(|option| Some((option, self.get_option_range(option)?)))
+            .collect()
+    }
+
     /// Predicate for determining if this sensor supports a given option
     ///
     /// Returns true iff the option is supported by this sensor.
@@ -339,41 +1031,186 @@ impl Sensor {
     /// Returns a vector containing all the stream profiles associated with the sensor. The vector
     /// will have a length of zero if an error occurs while getting the stream profiles.
     pub fn stream_profiles(&self) -> Vec<StreamProfile> {
-        let mut profiles = Vec::new();
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             let profiles_ptr = sys::rs2_get_stream_profiles(self.sensor_ptr.as_ptr(), &mut err);
             if err.as_ref().is_some() {
                 sys::rs2_free_error(err);
-                return profiles;
+                return Vec::new();
             }
 
-            let nonnull_profiles_ptr =
-                NonNull::new(profiles_ptr as *mut sys::rs2_stream_profile_list).unwrap();
+            Self::take_stream_profiles(NonNull::new(profiles_ptr).unwrap())
+        }
+    }
 
-            let len = sys::rs2_get_stream_profiles_count(nonnull_profiles_ptr.as_ptr(), &mut err);
+    /// Get a list of the stream profiles of this sensor which stream `kind`, e.g. only the depth
+    /// profiles of a stereo module
+    ///
+    /// Passing [`Rs2StreamKind::Any`] gives every stream profile, like
+    /// [`stream_profiles`](Self::stream_profiles). The vector will have a length of zero if the
+    /// sensor has no profiles of that kind, or if an error occurs while getting the stream
+    /// profiles.
+    pub fn stream_profiles_of_kind(&self, kind: Rs2StreamKind) -> Vec<StreamProfile> {
+        let mut profiles = self.stream_profiles();
+        if kind != Rs2StreamKind::Any {
+            profiles.retain(|profile| profile.kind() == kind);
+        }
+        profiles
+    }
 
+    /// Get a list of the stream profiles that are currently open on this sensor
+    ///
+    /// Unlike [`stream_profiles`](Self::stream_profiles), this only contains the profiles that
+    /// the sensor was [opened](Self::open) with, e.g. by a pipeline resolving its configuration.
+    /// The vector will have a length of zero if the sensor is not open, or if an error occurs
+    /// while getting the stream profiles.
+    pub fn active_stream_profiles(&self) -> Vec<StreamProfile> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let profiles_ptr = sys::rs2_get_active_streams(self.sensor_ptr.as_ptr(), &mut err);
             if err.as_ref().is_some() {
                 sys::rs2_free_error(err);
-                sys::rs2_delete_stream_profiles_list(nonnull_profiles_ptr.as_ptr());
-                return profiles;
+                return Vec::new();
             }
 
+            Self::take_stream_profiles(NonNull::new(profiles_ptr).unwrap())
+        }
+    }
+
+    /// Collects the stream profiles in a stream profile list, and deletes the list.
+    ///
+    /// Profiles that cannot be constructed are skipped.
+    ///
+    /// # Safety
+    ///
+    /// The list must be owned by the caller, e.g. freshly returned by librealsense2.
+    unsafe fn take_stream_profiles(
+        profiles_ptr: NonNull<sys::rs2_stream_profile_list>,
+    ) -> Vec<StreamProfile> {
+        let mut profiles = Vec::new();
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        let len = sys::rs2_get_stream_profiles_count(profiles_ptr.as_ptr(), &mut err);
+
+        if err.as_ref().is_some() {
+            sys::rs2_free_error(err);
+        } else {
             for i in 0..len {
-                match StreamProfile::try_create(&nonnull_profiles_ptr, i) {
-                    Ok(s) => {
-                        profiles.push(s);
-                    }
-                    Err(_) => {
-                        continue;
-                    }
+                if let Ok(s) = StreamProfile::try_create(&profiles_ptr, i) {
+                    profiles.push(s);
                 }
             }
-            sys::rs2_delete_stream_profiles_list(nonnull_profiles_ptr.as_ptr());
         }
+        sys::rs2_delete_stream_profiles_list(profiles_ptr.as_ptr());
         profiles
     }
 
+    /// Opens the given stream profiles on the sensor for exclusive use.
+    ///
+    /// This is the first step of streaming directly from a sensor, without going through a
+    /// [pipeline](crate::pipeline). Only the streams that are opened will be delivered once the
+    /// sensor is [started](Self::start), e.g. opening only an infrared profile will stream only
+    /// infrared frames. All profiles must belong to this sensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SensorStreamingError::CouldNotOpenStreams`] if the profiles cannot be opened,
+    /// e.g. if they do not belong to this sensor or the sensor is already in use.
+    pub fn open(&mut self, profiles: &[StreamProfile]) -> Result<(), SensorStreamingError> {
+        unsafe {
+            let mut profile_ptrs = profiles
+                .iter()
+                .map(|p| p.get_raw().as_ptr() as *const sys::rs2_stream_profile)
+                .collect::<Vec<_>>();
+
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_open_multiple(
+                self.sensor_ptr.as_ptr(),
+                profile_ptrs.as_mut_ptr(),
+                profile_ptrs.len() as i32,
+                &mut err,
+            );
+            check_rs2_error!(err, SensorStreamingError::CouldNotOpenStreams)?;
+
+            Ok(())
+        }
+    }
+
+    /// Starts streaming from the streams [opened](Self::open) on this sensor.
+    ///
+    /// Every frame streamed from the sensor that belongs to the [frame category](FrameCategory) of
+    /// `F` is passed to `callback`, which is invoked from a thread owned by librealsense2. Frames
+    /// of another extension or stream kind, e.g. infrared frames when `F` is
+    /// [`DepthFrame`](crate::frame::DepthFrame), are dropped.
+    ///
+    /// The callback is kept alive until the sensor is [stopped](Self::stop) or dropped, and
+    /// dropping a streaming sensor stops it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SensorStreamingError::CouldNotStartStreaming`] if the sensor cannot start
+    /// streaming, e.g. if no streams have been opened or the sensor is already streaming.
+    pub fn start<F, C>(&mut self, mut callback: C) -> Result<(), SensorStreamingError>
+    where
+        F: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory,
+        C: FnMut(F) + Send + 'static,
+    {
+        let mut frame_callback: Box<FrameCallback> = Box::new(Box::new(move |frame_ptr| {
+            if let Some(frame) = unsafe { frame_of_category::<F>(frame_ptr) } {
+                callback(frame);
+            }
+        }));
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_start(
+                self.sensor_ptr.as_ptr(),
+                Some(frame_trampoline),
+                &mut *frame_callback as *mut FrameCallback as *mut c_void,
+                &mut err,
+            );
+            check_rs2_error!(err, SensorStreamingError::CouldNotStartStreaming)?;
+        }
+
+        self.frame_callback = Some(frame_callback);
+        Ok(())
+    }
+
+    /// Stops streaming from the sensor.
+    ///
+    /// Once this returns, the callback passed to [`start`](Self::start) will no longer be invoked
+    /// and has been dropped. The streams remain open until the sensor is [closed](Self::close).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SensorStreamingError::CouldNotStopStreaming`] if the sensor cannot stop
+    /// streaming, e.g. if it was not streaming.
+    pub fn stop(&mut self) -> Result<(), SensorStreamingError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_stop(self.sensor_ptr.as_ptr(), &mut err);
+            check_rs2_error!(err, SensorStreamingError::CouldNotStopStreaming)?;
+        }
+
+        self.frame_callback = None;
+        Ok(())
+    }
+
+    /// Closes the streams [opened](Self::open) on this sensor, releasing it for other users.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SensorStreamingError::CouldNotCloseStreams`] if the streams cannot be closed,
+    /// e.g. if the sensor is still streaming or no streams were opened.
+    pub fn close(&mut self) -> Result<(), SensorStreamingError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_close(self.sensor_ptr.as_ptr(), &mut err);
+            check_rs2_error!(err, SensorStreamingError::CouldNotCloseStreams)?;
+
+            Ok(())
+        }
+    }
+
     // fn recommended_processing_blocks(&self) -> Vec<ProcessingBlock>{}
 
     /// Gets the value associated with the provided camera info key from the sensor.
@@ -426,11 +1263,23 @@ impl Sensor {
         }
     }
 
+    /// Predicate for whether the sensor supports an auto exposure region of interest.
+    ///
+    /// This is true iff the sensor supports the [`Rs2Extension::Roi`] extension.
+    pub fn supports_region_of_interest(&self) -> bool {
+        self.is_extendable_to(Rs2Extension::Roi)
+    }
+
     /// Gets the auto exposure's region of interest for the sensor.
     ///
     /// Returns the region of interest for the auto exposure or None
-    /// if this isn't available.
+    /// if this isn't available, e.g. if the sensor does not
+    /// [support](Self::supports_region_of_interest) a region of interest.
     pub fn get_region_of_interest(&self) -> Option<Rs2Roi> {
+        if !self.supports_region_of_interest() {
+            return None;
+        }
+
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             let mut roi = Rs2Roi {
@@ -462,6 +1311,9 @@ impl Sensor {
     ///
     /// # Errors
     ///
+    /// Returns [`RoiSetError::RoiNotSupported`] if the sensor does not
+    /// [support](Self::supports_region_of_interest) a region of interest.
+    ///
     /// Returns [`RoiSetError::CouldNotSetRoi`] if setting the region of interest failed.
     ///
     /// # Known issues
@@ -471,6 +1323,10 @@ impl Sensor {
     /// with a delay until it succeeds as suggested by Intel.
     /// Issue at librealsense: https://github.com/IntelRealSense/librealsense/issues/8004
     pub fn set_region_of_interest(&mut self, roi: Rs2Roi) -> Result<(), RoiSetError> {
+        if !self.supports_region_of_interest() {
+            return Err(RoiSetError::RoiNotSupported);
+        }
+
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             sys::rs2_set_region_of_interest(
@@ -484,4 +1340,395 @@ impl Sensor {
             check_rs2_error!(err, RoiSetError::CouldNotSetRoi)
         }
     }
+
+    /// Set a callback to be invoked with every notification emitted by the sensor.
+    ///
+    /// Sensors emit notifications for events such as frames not arriving in time, corrupted
+    /// frames, or errors reported by the hardware. The callback is invoked from a thread internal
+    /// to librealsense2, which is why it must be `Send`. It is owned by this sensor handle, and is
+    /// dropped when either the handle is dropped or another callback replaces it. Only one
+    /// callback can be registered at a time.
+    ///
+    /// Any panic inside the callback is caught and discarded, since unwinding into librealsense2
+    /// is undefined behaviour.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CouldNotSetNotificationsCallbackError`] if the callback cannot be registered. In
+    /// this case any previously registered callback remains active.
+    pub fn set_notifications_callback<F>(
+        &mut self,
+        callback: F,
+    ) -> Result<(), CouldNotSetNotificationsCallbackError>
+    where
+        F: FnMut(Notification) + Send + 'static,
+    {
+        let mut callback: Box<NotificationsCallback> = Box::new(Box::new(callback));
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_set_notifications_callback(
+                self.sensor_ptr.as_ptr(),
+                Some(notifications_trampoline),
+                &mut *callback as *mut NotificationsCallback as *mut c_void,
+                &mut err,
+            );
+            check_rs2_error!(err, CouldNotSetNotificationsCallbackError)?;
+        }
+
+        self.notifications_callback = Some(callback);
+        Ok(())
+    }
+}
+
+/// A sensor which is known to produce depth data.
+///
+/// Depth sensors are obtained from [`Sensor::try_into_depth_sensor`], and expose the APIs which
+/// only apply to depth sensors. All other sensor APIs remain available through [`Deref`].
+#[derive(Debug)]
+pub struct DepthSensor(Sensor);
+
+impl Deref for DepthSensor {
+    type Target = Sensor;
+
+    fn deref(&self) -> &Sensor {
+        &self.0
+    }
+}
+
+impl DerefMut for DepthSensor {
+    fn deref_mut(&mut self) -> &mut Sensor {
+        &mut self.0
+    }
+}
+
+impl DepthSensor {
+    /// Gets the number of meters represented by a single unit of depth.
+    ///
+    /// Multiplying the raw values of a [`DepthFrame`](crate::frame::DepthFrame) by the depth scale
+    /// gives the depth in meters.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CouldNotGetDepthScaleError`] if the depth scale cannot be retrieved, e.g. if the
+    /// device has been disconnected.
+    pub fn depth_scale(&self) -> Result<f32, CouldNotGetDepthScaleError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let scale = sys::rs2_get_depth_scale(self.0.sensor_ptr.as_ptr(), &mut err);
+            check_rs2_error!(err, CouldNotGetDepthScaleError)?;
+
+            Ok(scale)
+        }
+    }
+
+    /// Turns the IR emitter of the sensor on or off.
+    ///
+    /// The emitter projects a pattern onto the scene which adds texture for active stereo.
+    /// Turning it off gives passive stereo, e.g. to avoid interfering with other devices.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`set_option`](Sensor::set_option) for
+    /// [`Rs2Option::EmitterEnabled`].
+    pub fn set_emitter_enabled(&mut self, enabled: bool) -> Result<(), OptionSetError> {
+        self.0
+            .set_option(Rs2Option::EmitterEnabled, if enabled { 1.0 } else { 0.0 })
+    }
+
+    /// Gets whether the IR emitter of the sensor is on.
+    ///
+    /// Returns `None` if the sensor has no controllable emitter.
+    pub fn emitter_enabled(&self) -> Option<bool> {
+        self.0
+            .get_option(Rs2Option::EmitterEnabled)
+            .map(|value| value != 0.0)
+    }
+
+    /// Enables or disables alternating the IR emitter on and off from one frame to the next.
+    ///
+    /// This gives both active and passive stereo from a single stream, where the
+    /// [`Rs2FrameMetadata::FrameLaserPowerMode`] metadata of each frame tells which one it is. The
+    /// emitter has to be [enabled](Self::set_emitter_enabled) for this to have any effect.
+    ///
+    /// [`Rs2FrameMetadata::FrameLaserPowerMode`]: crate::kind::Rs2FrameMetadata::FrameLaserPowerMode
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`set_option`](Sensor::set_option) for
+    /// [`Rs2Option::EmitterOnOff`].
+    pub fn set_emitter_on_off(&mut self, enabled: bool) -> Result<(), OptionSetError> {
+        self.0
+            .set_option(Rs2Option::EmitterOnOff, if enabled { 1.0 } else { 0.0 })
+    }
+
+    /// Converts back into a plain [`Sensor`].
+    pub fn into_sensor(self) -> Sensor {
+        self.0
+    }
+}
+
+/// A sensor which tracks the pose of the device, such as the tracking module of the T265.
+///
+/// Pose sensors are obtained from [`Sensor::try_into_pose_sensor`], and expose the APIs for
+/// managing the localization map that the sensor builds while tracking. A map can be exported at
+/// the end of a session and imported at the start of the next, so that the sensor relocalizes
+/// against the known map instead of starting a new one. All other sensor APIs remain available
+/// through [`Deref`].
+#[derive(Debug)]
+pub struct PoseSensor(Sensor);
+
+impl Deref for PoseSensor {
+    type Target = Sensor;
+
+    fn deref(&self) -> &Sensor {
+        &self.0
+    }
+}
+
+impl DerefMut for PoseSensor {
+    fn deref_mut(&mut self) -> &mut Sensor {
+        &mut self.0
+    }
+}
+
+impl PoseSensor {
+    /// Exports the localization map of the sensor as a serialized buffer.
+    ///
+    /// The sensor must be stopped, but not closed, for the map to be available.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LocalizationMapError::CouldNotExportMap`] if the map cannot be exported.
+    pub fn export_localization_map(&self) -> Result<Vec<u8>, LocalizationMapError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let buffer = sys::rs2_export_localization_map(self.0.sensor_ptr.as_ptr(), &mut err);
+            check_rs2_error!(err, LocalizationMapError::CouldNotExportMap)?;
+
+            let size = sys::rs2_get_raw_data_size(buffer, &mut err);
+            if let Err(e) = check_rs2_error!(err, LocalizationMapError::CouldNotExportMap) {
+                sys::rs2_delete_raw_data(buffer);
+                return Err(e);
+            }
+
+            let data = sys::rs2_get_raw_data(buffer, &mut err);
+            if let Err(e) = check_rs2_error!(err, LocalizationMapError::CouldNotExportMap) {
+                sys::rs2_delete_raw_data(buffer);
+                return Err(e);
+            }
+
+            let map = std::slice::from_raw_parts(data, size as usize).to_vec();
+            sys::rs2_delete_raw_data(buffer);
+            Ok(map)
+        }
+    }
+
+    /// Imports a localization map, such as one produced by
+    /// [`export_localization_map`](Self::export_localization_map), onto the sensor.
+    ///
+    /// The map must be imported before the sensor is started.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LocalizationMapError::CouldNotImportMap`] if the map cannot be imported, or
+    /// [`LocalizationMapError::MapRejected`] if the sensor does not accept it.
+    pub fn import_localization_map(&mut self, map: &[u8]) -> Result<(), LocalizationMapError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let imported = sys::rs2_import_localization_map(
+                self.0.sensor_ptr.as_ptr(),
+                map.as_ptr(),
+                map.len() as c_uint,
+                &mut err,
+            );
+            check_rs2_error!(err, LocalizationMapError::CouldNotImportMap)?;
+
+            if imported == 0 {
+                Err(LocalizationMapError::MapRejected)
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    /// Saves a named location in the localization map of the sensor, e.g. as a waypoint.
+    ///
+    /// The `guid` may be up to 127 characters long. The sensor must be streaming, and the node is
+    /// only retained across sessions if the map is then [exported](Self::export_localization_map).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LocalizationMapError::CouldNotSetStaticNode`] if the node cannot be set, or
+    /// [`LocalizationMapError::StaticNodeRejected`] if the sensor does not accept it.
+    pub fn set_static_node(
+        &mut self,
+        guid: &CStr,
+        node: StaticNode,
+    ) -> Result<(), LocalizationMapError> {
+        let [x, y, z] = node.translation;
+        let position = sys::rs2_vector { x, y, z };
+        let [x, y, z, w] = node.rotation;
+        let orientation = sys::rs2_quaternion { x, y, z, w };
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let set = sys::rs2_set_static_node(
+                self.0.sensor_ptr.as_ptr(),
+                guid.as_ptr(),
+                position,
+                orientation,
+                &mut err,
+            );
+            check_rs2_error!(err, LocalizationMapError::CouldNotSetStaticNode)?;
+
+            if set == 0 {
+                Err(LocalizationMapError::StaticNodeRejected)
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    /// Gets a named location from the localization map of the sensor.
+    ///
+    /// Returns `Ok(None)` if the map has no node named `guid`, or if the sensor has not yet
+    /// relocalized against the map.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LocalizationMapError::CouldNotGetStaticNode`] if the node cannot be retrieved.
+    pub fn get_static_node(&self, guid: &CStr) -> Result<Option<StaticNode>, LocalizationMapError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let mut position = MaybeUninit::<sys::rs2_vector>::zeroed();
+            let mut orientation = MaybeUninit::<sys::rs2_quaternion>::zeroed();
+            let found = sys::rs2_get_static_node(
+                self.0.sensor_ptr.as_ptr(),
+                guid.as_ptr(),
+                position.as_mut_ptr(),
+                orientation.as_mut_ptr(),
+                &mut err,
+            );
+            check_rs2_error!(err, LocalizationMapError::CouldNotGetStaticNode)?;
+
+            if found == 0 {
+                return Ok(None);
+            }
+
+            let sys::rs2_vector { x, y, z } = position.assume_init();
+            let translation = [x, y, z];
+            let sys::rs2_quaternion { x, y, z, w } = orientation.assume_init();
+            Ok(Some(StaticNode {
+                translation,
+                rotation: [x, y, z, w],
+            }))
+        }
+    }
+
+    /// Removes a named location from the localization map of the sensor.
+    ///
+    /// Returns whether a node named `guid` was removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LocalizationMapError::CouldNotRemoveStaticNode`] if the node cannot be removed.
+    pub fn remove_static_node(&mut self, guid: &CStr) -> Result<bool, LocalizationMapError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let removed =
+                sys::rs2_remove_static_node(self.0.sensor_ptr.as_ptr(), guid.as_ptr(), &mut err);
+            check_rs2_error!(err, LocalizationMapError::CouldNotRemoveStaticNode)?;
+
+            Ok(removed != 0)
+        }
+    }
+
+    /// Converts back into a plain [`Sensor`].
+    pub fn into_sensor(self) -> Sensor {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    fn notification() -> Notification {
+        Notification {
+            description: String::from("Frames didn't arrive within 5 seconds"),
+            timestamp: 1627.5,
+            severity: Rs2LogSeverity::Warn,
+            category: Rs2NotificationCategory::FramesTimeout,
+        }
+    }
+
+    #[test]
+    fn notifications_are_dispatched_to_the_callback() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let callback_received = Arc::clone(&received);
+
+        let mut callback: NotificationsCallback = Box::new(move |notification| {
+            callback_received.lock().unwrap().push(notification);
+        });
+
+        dispatch_notification(&mut callback, notification());
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(
+            received[0].description(),
+            "Frames didn't arrive within 5 seconds"
+        );
+        assert_eq!(received[0].timestamp(), 1627.5);
+        assert_eq!(received[0].severity(), Rs2LogSeverity::Warn);
+        assert_eq!(
+            received[0].category(),
+            Rs2NotificationCategory::FramesTimeout
+        );
+    }
+
+    #[test]
+    fn panicking_notifications_callback_does_not_unwind() {
+        let mut callback: NotificationsCallback = Box::new(|_| panic!("callback panicked"));
+
+        dispatch_notification(&mut callback, notification());
+        dispatch_notification(&mut callback, notification());
+    }
+
+    #[test]
+    fn visual_preset_is_restored_before_other_options() {
+        let snapshot = vec![
+            (Rs2Option::LaserPower, 150.0),
+            (Rs2Option::VisualPreset, 3.0),
+            (Rs2Option::Exposure, 8500.0),
+        ]
+        .into_iter()
+        .collect::<OptionsSnapshot>();
+
+        assert_eq!(
+            snapshot.restore_order(),
+            vec![
+                (Rs2Option::VisualPreset, 3.0),
+                (Rs2Option::Exposure, 8500.0),
+                (Rs2Option::LaserPower, 150.0),
+            ]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn options_snapshot_round_trips_through_serde() {
+        let snapshot = vec![(Rs2Option::Exposure, 8500.0), (Rs2Option::Gain, 16.0)]
+            .into_iter()
+            .collect::<OptionsSnapshot>();
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<OptionsSnapshot>(&json).unwrap(),
+            snapshot
+        );
+    }
 }
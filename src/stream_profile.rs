@@ -37,14 +37,19 @@
 //! See [the `StreamProfile` type](crate::stream_profile::StreamProfile) for more information.
 
 use crate::{
-    base::{Rs2Extrinsics, Rs2Intrinsics, Rs2MotionDeviceIntrinsics},
+    base::{Rs2Extrinsics, Rs2Intrinsics, Rs2MotionDeviceIntrinsics, Rs2Resolution},
     check_rs2_error,
-    kind::{Rs2Exception, Rs2Format, Rs2StreamKind},
+    kind::{Rs2Exception, Rs2Extension, Rs2Format, Rs2StreamKind},
 };
 use anyhow::Result;
 use num_traits::FromPrimitive;
 use realsense_sys as sys;
-use std::{convert::TryFrom, mem::MaybeUninit, ptr::NonNull};
+use std::{
+    convert::{TryFrom, TryInto},
+    mem::MaybeUninit,
+    os::raw::c_int,
+    ptr::NonNull,
+};
 use thiserror::Error;
 
 /// Type describing errors that can occur when trying to construct a stream profile.
@@ -95,6 +100,12 @@ pub enum DataError {
     /// Could not get motion intrinsics from the requested stream.
     #[error("Could not get motion intrinsics. Type: {0}; Reason: {1}")]
     CouldNotGetMotionIntrinsics(Rs2Exception, String),
+    /// This stream is not a video stream, and does not have a resolution.
+    #[error("Stream does not have a resolution")]
+    StreamDoesNotHaveResolution,
+    /// Could not get the resolution of the requested stream.
+    #[error("Could not get resolution. Type: {0}; Reason: {1}")]
+    CouldNotGetResolution(Rs2Exception, String),
 }
 
 /// Type for holding the stream profile information.
@@ -118,6 +129,9 @@ pub enum DataError {
 /// cases two and three above we return references to a stream profile owned by that type, so they
 /// may not.  In most cases you will probably want to grab the stream profile from the pipeline
 /// profile, which will give you all streams that are actively streaming from a given pipeline.
+///
+/// To keep a stream profile around for longer than the object it was obtained from, e.g. to cache
+/// the intrinsics of every stream, use [`try_clone`](StreamProfile::try_clone).
 #[derive(Debug)]
 pub struct StreamProfile {
     // Underlying non-null pointer from realsense-sys.
@@ -126,7 +140,7 @@ pub struct StreamProfile {
     // be manually deleted using `rs2_delete_stream_profile`. Streams are owned and managed by
     // their corresponding sensor, which are owned and managed by their corresponding devices.
     // Stream profile pointers should only be manually deleted if they are created by
-    /// `rs2_clone_stream_profile`, which we do not use in the high-level API.
+    /// `rs2_clone_stream_profile`, which is tracked by `should_drop`.
     ptr: NonNull<sys::rs2_stream_profile>,
     /// The kind of stream (e.g. depth, video, accelerometer, gyroscope, etc.)
     stream: Rs2StreamKind,
@@ -270,6 +284,47 @@ impl StreamProfile {
         }
     }
 
+    /// Clones the stream profile into a new, independently owned profile.
+    ///
+    /// The clone is not tied to the lifetime of the object the original profile was obtained from,
+    /// so e.g. the profile of a frame can be cloned and kept after the frame has been dropped. The
+    /// clone describes the same stream, with the same intrinsics and extrinsics, but librealsense2
+    /// assigns it a new [`unique_id`](Self::unique_id).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StreamConstructionError::CouldNotCloneProfile`] if the profile cannot be cloned.
+    ///
+    /// Returns [`StreamConstructionError::CouldNotRetrieveStreamData`] or
+    /// [`StreamConstructionError::CouldNotDetermineIsDefault`] if the clone cannot be constructed.
+    pub fn try_clone(&self) -> Result<StreamProfile, StreamConstructionError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let profile_ptr = sys::rs2_clone_stream_profile(
+                self.ptr.as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (self.stream as i32).try_into().unwrap(),
+                self.index as c_int,
+                #[allow(clippy::useless_conversion)]
+                (self.format as i32).try_into().unwrap(),
+                &mut err,
+            );
+            check_rs2_error!(err, StreamConstructionError::CouldNotCloneProfile)?;
+
+            let nonnull_profile_ptr = NonNull::new(profile_ptr).unwrap();
+            match Self::try_from(nonnull_profile_ptr) {
+                Ok(mut stream_profile) => {
+                    stream_profile.should_drop = true;
+                    Ok(stream_profile)
+                }
+                Err(e) => {
+                    sys::rs2_delete_stream_profile(nonnull_profile_ptr.as_ptr());
+                    Err(e)
+                }
+            }
+        }
+    }
+
     /// Predicate for whether or not the stream is a default stream.
     #[inline]
     pub fn is_default(&self) -> bool {
@@ -379,14 +434,8 @@ impl StreamProfile {
     ///
     /// Returns [`DataError::CouldNotGetIntrinsics`] if this call fails for any other reason.
     pub fn intrinsics(&self) -> Result<Rs2Intrinsics, DataError> {
-        match self.stream {
-            Rs2StreamKind::Depth => (),
-            Rs2StreamKind::Color => (),
-            Rs2StreamKind::Infrared => (),
-            Rs2StreamKind::Fisheye => (),
-            _ => {
-                return Err(DataError::StreamDoesNotHaveVideoIntrinsics);
-            }
+        if !self.is_video() {
+            return Err(DataError::StreamDoesNotHaveVideoIntrinsics);
         }
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
@@ -403,6 +452,41 @@ impl StreamProfile {
         }
     }
 
+    /// Get the resolution of the stream.
+    ///
+    /// Returns the width and height of the frames in the stream, in pixels, iff the stream is a
+    /// video stream and the stream pointer is valid. Otherwise returns an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DataError::StreamDoesNotHaveResolution`] if the stream is not a video stream.
+    ///
+    /// Returns [`DataError::CouldNotGetResolution`] if this call fails for any other reason.
+    pub fn resolution(&self) -> Result<Rs2Resolution, DataError> {
+        if !self.is_video() {
+            return Err(DataError::StreamDoesNotHaveResolution);
+        }
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let mut width = MaybeUninit::uninit();
+            let mut height = MaybeUninit::uninit();
+
+            sys::rs2_get_video_stream_resolution(
+                self.ptr.as_ptr(),
+                width.as_mut_ptr(),
+                height.as_mut_ptr(),
+                &mut err,
+            );
+            check_rs2_error!(err, DataError::CouldNotGetResolution)?;
+
+            Ok(Rs2Resolution {
+                width: width.assume_init() as usize,
+                height: height.assume_init() as usize,
+            })
+        }
+    }
+
     /// Get motion intrinsics from the stream.
     ///
     /// Returns a set of motion device intrinsics for the stream iff the stream has motion device
@@ -434,4 +518,38 @@ impl StreamProfile {
             Ok(Rs2MotionDeviceIntrinsics(intrinsics.assume_init()))
         }
     }
+
+    /// Predicate for whether the stream is a video stream, i.e. it streams images.
+    ///
+    /// This asks librealsense2 whether the profile is a video profile, rather than going by the
+    /// stream kind, since any kind of stream that streams images (e.g. confidence) is one.
+    fn is_video(&self) -> bool {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let is_video = sys::rs2_stream_profile_is(
+                self.ptr.as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (Rs2Extension::VideoProfile as i32).try_into().unwrap(),
+                &mut err,
+            );
+
+            if err.as_ref().is_none() {
+                is_video != 0
+            } else {
+                sys::rs2_free_error(err);
+                false
+            }
+        }
+    }
+
+    /// Get the underlying low-level pointer to the stream profile.
+    ///
+    /// # Safety
+    ///
+    /// This method is not intended to be called or used outside of the crate itself. Be warned, it
+    /// is _undefined behaviour_ to delete or try to drop this pointer in any context. If you do,
+    /// you risk a double-free or use-after-free error.
+    pub(crate) unsafe fn get_raw(&self) -> NonNull<sys::rs2_stream_profile> {
+        self.ptr
+    }
 }
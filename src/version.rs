@@ -0,0 +1,116 @@
+//! Functions for checking the version of librealsense2 that is loaded at runtime.
+//!
+//! The `realsense-sys` bindings are generated for a specific version of librealsense2, given by
+//! [`BINDINGS_API_VERSION`]. If the library that is linked at runtime is older, or has a
+//! different major version, the layout of structs and the values of enums may not match the
+//! bindings, which causes subtle bugs rather than a clean failure. [`check_compatible`] detects
+//! this, and is meant to be called once at startup.
+
+use crate::{check_rs2_error, kind::Rs2Exception};
+use realsense_sys as sys;
+use thiserror::Error;
+
+/// The `(major, minor, patch)` version of librealsense2 that the bindings were generated for.
+pub const BINDINGS_API_VERSION: (u32, u32, u32) = (
+    sys::RS2_API_MAJOR_VERSION,
+    sys::RS2_API_MINOR_VERSION,
+    sys::RS2_API_PATCH_VERSION,
+);
+
+/// Enumeration over possible errors that can occur when checking the version of librealsense2.
+#[derive(Error, Debug)]
+pub enum VersionError {
+    /// The version of librealsense2 could not be retrieved.
+    #[error("Could not get API version. Type: {0}; Reason: {1}")]
+    CouldNotGetApiVersion(Rs2Exception, String),
+    /// The version of librealsense2 loaded at runtime is not compatible with the bindings.
+    #[error("librealsense2 {runtime:?} is not compatible with bindings for {bindings:?}.")]
+    IncompatibleVersion {
+        /// The `(major, minor, patch)` version loaded at runtime.
+        runtime: (u32, u32, u32),
+        /// The `(major, minor, patch)` version the bindings were generated for.
+        bindings: (u32, u32, u32),
+    },
+}
+
+/// Gets the `(major, minor, patch)` version of librealsense2 that is loaded at runtime.
+///
+/// # Errors
+///
+/// Returns [`VersionError::CouldNotGetApiVersion`] if the version cannot be retrieved.
+pub fn api_version() -> Result<(u32, u32, u32), VersionError> {
+    unsafe {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        let version = sys::rs2_get_api_version(&mut err);
+        check_rs2_error!(err, VersionError::CouldNotGetApiVersion)?;
+
+        Ok(decode_api_version(version as u32))
+    }
+}
+
+/// Checks that the version of librealsense2 loaded at runtime is compatible with the bindings.
+///
+/// This follows the rule that librealsense2 itself applies when creating a context: the major
+/// versions must match, and the runtime minor version must be at least that of the bindings,
+/// since newer minor versions are backwards compatible.
+///
+/// # Errors
+///
+/// Returns [`VersionError::CouldNotGetApiVersion`] if the runtime version cannot be retrieved.
+///
+/// Returns [`VersionError::IncompatibleVersion`] if the runtime version is not compatible with
+/// [`BINDINGS_API_VERSION`].
+pub fn check_compatible() -> Result<(), VersionError> {
+    let runtime = api_version()?;
+    if is_compatible(runtime, BINDINGS_API_VERSION) {
+        Ok(())
+    } else {
+        Err(VersionError::IncompatibleVersion {
+            runtime,
+            bindings: BINDINGS_API_VERSION,
+        })
+    }
+}
+
+/// Splits a version encoded as `major * 10000 + minor * 100 + patch` into its components.
+fn decode_api_version(version: u32) -> (u32, u32, u32) {
+    (version / 10000, version / 100 % 100, version % 100)
+}
+
+/// Predicate for whether a `runtime` version of librealsense2 can be used with `bindings`.
+fn is_compatible(runtime: (u32, u32, u32), bindings: (u32, u32, u32)) -> bool {
+    runtime.0 == bindings.0 && runtime.1 >= bindings.1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bindings_version_matches_encoded_version() {
+        assert_eq!(
+            decode_api_version(sys::RS2_API_VERSION),
+            BINDINGS_API_VERSION
+        );
+    }
+
+    #[test]
+    fn decodes_api_version() {
+        assert_eq!(decode_api_version(25401), (2, 54, 1));
+        assert_eq!(decode_api_version(21000), (2, 10, 0));
+    }
+
+    #[test]
+    fn newer_minor_and_patch_versions_are_compatible() {
+        assert!(is_compatible((2, 54, 1), (2, 54, 1)));
+        assert!(is_compatible((2, 54, 0), (2, 54, 1)));
+        assert!(is_compatible((2, 55, 0), (2, 54, 1)));
+    }
+
+    #[test]
+    fn older_minor_and_other_major_versions_are_incompatible() {
+        assert!(!is_compatible((2, 50, 0), (2, 54, 1)));
+        assert!(!is_compatible((3, 54, 1), (2, 54, 1)));
+        assert!(!is_compatible((1, 60, 0), (2, 54, 1)));
+    }
+}
@@ -0,0 +1,102 @@
+//! Fixtures shared by the tests that require a connected device.
+
+// Each test crate only uses some of the fixtures.
+#![allow(dead_code)]
+
+use realsense_rust::{
+    config::Config,
+    context::Context,
+    device::Device,
+    kind::{Rs2CameraInfo, Rs2ProductLine},
+    pipeline::{ActivePipeline, InactivePipeline},
+};
+use std::{
+    collections::HashSet,
+    convert::TryFrom,
+    path::{Path, PathBuf},
+};
+
+/// Gets the first connected device of `product_line`, if any.
+///
+/// Tests which require a device of a specific product line do nothing when none is connected.
+pub fn connected_device(context: &Context, product_line: Rs2ProductLine) -> Option<Device> {
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(product_line);
+
+    context.query_devices(queryable_set).into_iter().next()
+}
+
+/// Gets the first connected D400 series device, if any.
+pub fn d400_device(context: &Context) -> Option<Device> {
+    connected_device(context, Rs2ProductLine::D400)
+}
+
+/// A recording of a connected device, which is deleted when dropped.
+///
+/// Tests which only need to read frames or query a device play back a recording, so that they
+/// see the same frames on every run.
+#[derive(Debug)]
+pub struct Recording {
+    /// The path of the bag file holding the recording.
+    path: PathBuf,
+}
+
+impl Drop for Recording {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl Recording {
+    /// Records `frames` framesets of the default streams of any connected device to a bag file
+    /// called `name` in the temporary directory.
+    pub fn new(context: &Context, name: &str, frames: usize) -> Self {
+        Self::with_config(context, name, Config::new(), frames)
+    }
+
+    /// Records `frames` framesets of the streams enabled in `config` to a bag file called `name`
+    /// in the temporary directory.
+    pub fn with_config(context: &Context, name: &str, mut config: Config, frames: usize) -> Self {
+        let recording = Self {
+            path: std::env::temp_dir().join(format!("realsense-rust-{}.bag", name)),
+        };
+        config.enable_record_to_file(&recording.path).unwrap();
+
+        let pipeline = InactivePipeline::try_from(context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+        for _ in 0..frames {
+            let _ = pipeline.wait(None).unwrap();
+        }
+        // Stopping the pipeline finalizes the recording.
+        drop(pipeline.stop());
+
+        recording
+    }
+
+    /// Records `frames` framesets of the streams enabled in `config` from the first connected
+    /// D400 series device, if any.
+    pub fn d400(context: &Context, name: &str, mut config: Config, frames: usize) -> Option<Self> {
+        let device = d400_device(context)?;
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        config.enable_device_from_serial(serial).unwrap();
+        Some(Self::with_config(context, name, config, frames))
+    }
+
+    /// Gets the path of the bag file holding the recording.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Gets a config which plays back the recording once, without looping.
+    pub fn playback_config(&self) -> Config {
+        let mut config = Config::new();
+        config.enable_device_from_file(&self.path, false).unwrap();
+        config
+    }
+
+    /// Starts a pipeline which plays back the recording once, without looping.
+    pub fn play(&self, context: &Context) -> ActivePipeline {
+        let pipeline = InactivePipeline::try_from(context).unwrap();
+        pipeline.start(Some(self.playback_config())).unwrap()
+    }
+}
@@ -3,12 +3,36 @@
 #![cfg(feature = "test-single-device")]
 
 use realsense_rust::{
+    base::Rs2Roi,
+    capture::Capture,
     config::Config,
     context::Context,
-    kind::{Rs2Format, Rs2ProductLine, Rs2StreamKind},
+    kind::{
+        OptionSetError, OutOfRangeMode, Rs2CameraInfo, Rs2Exception, Rs2Extension, Rs2Format,
+        Rs2LogSeverity, Rs2Option, Rs2ProductLine, Rs2StreamKind,
+    },
+    log,
     pipeline::InactivePipeline,
+    processing_blocks::{DecimationFilter, ThresholdFilter},
+    sensor::RoiSetError,
+    version,
 };
-use std::{collections::HashSet, convert::TryFrom};
+use std::{
+    collections::HashSet,
+    convert::TryFrom,
+    sync::mpsc,
+    task::Poll,
+    time::{Duration, Instant},
+};
+
+mod common;
+
+/// Ensure the librealsense2 loaded at runtime reports its version.
+#[test]
+fn runtime_api_version_is_version_2() {
+    let (major, _, _) = version::api_version().unwrap();
+    assert_eq!(major, 2);
+}
 
 /// Ensure at least one intel device is "connected" as far as the driver is concerned.
 ///
@@ -24,6 +48,510 @@ fn ensure_at_least_one_intel_device_connected() {
     assert!(!devices.is_empty());
 }
 
+/// A connected device should be found by both the device hub and a bounded wait on the context.
+#[test]
+fn can_wait_for_a_connected_device() {
+    let context = Context::new().unwrap();
+
+    let hub = context.create_device_hub().unwrap();
+    let device = hub.wait_for_device().unwrap();
+    assert!(hub.is_device_connected(&device));
+
+    let mut mask = HashSet::new();
+    mask.insert(Rs2ProductLine::AnyIntel);
+    assert!(context
+        .wait_for_device(mask, Duration::from_secs(1))
+        .is_some());
+}
+
+/// An empty mask should not filter out any devices, rather than matching no devices at all.
+#[test]
+fn empty_product_mask_queries_all_devices() {
+    let context = Context::new().unwrap();
+    let mut mask = HashSet::new();
+    mask.insert(Rs2ProductLine::Any);
+
+    let all_devices = context.query_devices(mask);
+    let unfiltered_devices = context.query_devices(HashSet::new());
+
+    assert!(!unfiltered_devices.is_empty());
+    assert_eq!(all_devices.len(), unfiltered_devices.len());
+}
+
+/// Filtering by depth product lines should never return a tracking device.
+#[test]
+fn depth_product_line_excludes_tracking_devices() {
+    let context = Context::new().unwrap();
+
+    let depth_devices = context.query_devices_by_product_line(Rs2ProductLine::Depth);
+    let tracking_devices = context.query_devices_by_product_line(Rs2ProductLine::Tracking);
+    let all_devices = context.query_devices_by_product_line(Rs2ProductLine::Any);
+
+    for device in &depth_devices {
+        let product_line = device.info(Rs2CameraInfo::ProductLine).unwrap();
+        assert_ne!(product_line.to_str().unwrap(), "T200");
+    }
+    assert!(depth_devices.len() + tracking_devices.len() <= all_devices.len());
+}
+
+/// The lazy device iterator should yield the same devices as a query.
+#[test]
+fn devices_yields_the_queried_devices() {
+    let context = Context::new().unwrap();
+    let all_devices = context.query_devices(HashSet::new());
+
+    let devices = context.devices();
+    assert_eq!(devices.size_hint(), (0, Some(all_devices.len())));
+
+    let serials = devices
+        .map(|device| device.serial_number())
+        .collect::<Vec<_>>();
+    let expected_serials = all_devices
+        .iter()
+        .map(|device| device.serial_number())
+        .collect::<Vec<_>>();
+    assert_eq!(serials, expected_serials);
+}
+
+/// Devices should only be constructed as the iterator reaches them, so a device that is
+/// disconnected after the iterator was created, but before it is reached, is skipped.
+///
+/// The device is disconnected with a hardware reset, and has reconnected by the end of the test.
+#[test]
+fn devices_are_constructed_lazily() {
+    let mut context = Context::new().unwrap();
+    let (sender, changes) = mpsc::channel();
+    context
+        .set_devices_changed_callback(move |_| {
+            let _ = sender.send(());
+        })
+        .unwrap();
+
+    let device = context.query_devices(HashSet::new()).remove(0);
+    let serial = device.serial_number();
+    let devices = context.devices();
+
+    device.hardware_reset().unwrap();
+    changes
+        .recv_timeout(Duration::from_secs(10))
+        .expect("The device was not disconnected by the hardware reset.");
+
+    let serials = devices
+        .map(|device| device.serial_number())
+        .collect::<Vec<_>>();
+    assert!(
+        !serials.contains(&serial),
+        "The disconnected device was constructed before the iterator reached it."
+    );
+
+    // Wait for the device to reconnect, so that the tests that follow can use it.
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while !context
+        .devices()
+        .any(|device| device.serial_number() == serial)
+    {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        changes
+            .recv_timeout(remaining)
+            .expect("The device did not reconnect after the hardware reset.");
+    }
+}
+
+#[test]
+fn device_info_accessors_match_camera_info() {
+    let context = Context::new().unwrap();
+    let devices = context.query_devices(HashSet::new());
+
+    for device in devices {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        assert_eq!(device.serial_number().unwrap(), serial.to_str().unwrap());
+
+        let name = device.info(Rs2CameraInfo::Name).unwrap();
+        assert_eq!(device.name().unwrap(), name.to_str().unwrap());
+
+        assert!(device.firmware_version().is_some());
+    }
+}
+
+#[test]
+fn sensors_describe_supported_options() {
+    let context = Context::new().unwrap();
+    let devices = context.query_devices(HashSet::new());
+
+    for device in devices {
+        for sensor in device.sensors() {
+            if sensor.supports_option(Rs2Option::Exposure) {
+                let description = sensor.get_option_description(Rs2Option::Exposure).unwrap();
+                assert!(!description.is_empty());
+            } else {
+                assert!(sensor.get_option_description(Rs2Option::Exposure).is_none());
+            }
+        }
+    }
+}
+
+#[test]
+fn sensors_enumerate_supported_options() {
+    let context = Context::new().unwrap();
+    let devices = context.query_devices(HashSet::new());
+
+    for device in devices {
+        for sensor in device.sensors() {
+            let options = sensor.supported_options();
+            let unique_options = options
+                .iter()
+                .map(|(option, _)| *option)
+                .collect::<HashSet<_>>();
+            assert_eq!(unique_options.len(), options.len());
+
+            for (option, range) in options {
+                assert!(sensor.supports_option(option));
+                assert!(range.min <= range.max);
+                assert!(range.step >= 0.0);
+                assert!(range.min <= range.default && range.default <= range.max);
+            }
+        }
+    }
+}
+
+#[test]
+fn failed_option_batch_restores_original_values() {
+    let context = Context::new().unwrap();
+    let devices = context.query_devices(HashSet::new());
+
+    for device in devices {
+        for mut sensor in device.sensors() {
+            let writable_option = sensor
+                .supported_options()
+                .into_iter()
+                .find(|(option, range)| {
+                    !sensor.is_option_read_only(*option)
+                        && range.min < range.max
+                        && range.step > 0.0
+                });
+
+            let (option, range) = match writable_option {
+                Some(o) => o,
+                None => continue,
+            };
+
+            let original_value = sensor.get_option(option).unwrap();
+            let new_value = if original_value == range.min {
+                range.max
+            } else {
+                range.min
+            };
+            let invalid_value = range.max + (range.max - range.min) + range.step;
+
+            let result = sensor.set_options(&[(option, new_value), (option, invalid_value)]);
+
+            assert!(result.is_err());
+            assert_eq!(sensor.get_option(option).unwrap(), original_value);
+        }
+    }
+}
+
+/// Restoring a snapshot right after taking it should not change any option.
+///
+/// Options that auto-exposure and auto-white-balance adjust on their own are not compared, since
+/// they may drift between the two snapshots.
+#[test]
+fn restoring_an_options_snapshot_leaves_options_unchanged() {
+    let context = Context::new().unwrap();
+    let devices = context.query_devices(HashSet::new());
+    let auto_adjusted_options = [
+        Rs2Option::Exposure,
+        Rs2Option::Gain,
+        Rs2Option::WhiteBalance,
+    ];
+
+    for device in devices {
+        for mut sensor in device.sensors() {
+            let mut snapshot = sensor.snapshot_options();
+            for (option, _) in snapshot.iter() {
+                assert!(!sensor.is_option_read_only(option));
+            }
+
+            sensor.restore_options(&snapshot).unwrap();
+            let mut restored_snapshot = sensor.snapshot_options();
+
+            for option in &auto_adjusted_options {
+                snapshot.remove(*option);
+                restored_snapshot.remove(*option);
+            }
+            assert_eq!(restored_snapshot, snapshot);
+        }
+    }
+}
+
+/// Options that a sensor does not support should be skipped when restoring a snapshot.
+#[test]
+fn restoring_an_options_snapshot_skips_unsupported_options() {
+    let context = Context::new().unwrap();
+    let devices = context.query_devices(HashSet::new());
+
+    for device in devices {
+        for mut sensor in device.sensors() {
+            let unsupported_option = [Rs2Option::LaserPower, Rs2Option::WhiteBalance]
+                .iter()
+                .copied()
+                .find(|option| !sensor.supports_option(*option));
+
+            let unsupported_option = match unsupported_option {
+                Some(option) => option,
+                None => continue,
+            };
+
+            let mut snapshot = sensor.snapshot_options();
+            snapshot.set(unsupported_option, 1.0);
+
+            sensor.restore_options(&snapshot).unwrap();
+            assert!(sensor.get_option(unsupported_option).is_none());
+        }
+    }
+}
+
+/// Every failed call should report the message of the underlying error, including when errors
+/// occur repeatedly.
+#[test]
+fn repeated_errors_report_their_messages() {
+    let context = Context::new().unwrap();
+    let devices = context.query_devices(HashSet::new());
+
+    for device in devices {
+        for mut sensor in device.sensors() {
+            let (option, range) =
+                match sensor
+                    .supported_options()
+                    .into_iter()
+                    .find(|(option, range)| {
+                        !sensor.is_option_read_only(*option) && range.min < range.max
+                    }) {
+                    Some(o) => o,
+                    None => continue,
+                };
+            let invalid_value = range.max + (range.max - range.min) + range.step;
+
+            for _ in 0..1000 {
+                match sensor.set_option(option, invalid_value) {
+                    Err(OptionSetError::CouldNotSetOption(_, message)) => {
+                        assert!(!message.is_empty());
+                    }
+                    other => panic!("Expected the value to be rejected, got {:?}", other),
+                }
+            }
+        }
+    }
+}
+
+/// Values above the maximum of an option should be clamped or rejected, depending on the mode.
+#[test]
+fn checked_option_values_above_max_are_clamped_or_rejected() {
+    let context = Context::new().unwrap();
+    let devices = context.query_devices(HashSet::new());
+
+    for device in devices {
+        for mut sensor in device.sensors() {
+            let (option, range) =
+                match sensor
+                    .supported_options()
+                    .into_iter()
+                    .find(|(option, range)| {
+                        !sensor.is_option_read_only(*option) && range.min < range.max
+                    }) {
+                    Some(o) => o,
+                    None => continue,
+                };
+            let snapshot = sensor.snapshot_options();
+            let original_value = sensor.get_option(option).unwrap();
+            let above_max = range.max + (range.max - range.min) + range.step;
+
+            match sensor.set_option_checked(option, above_max, OutOfRangeMode::Reject) {
+                Err(OptionSetError::ValueOutOfRange { value, max, .. }) => {
+                    assert_eq!(value, above_max);
+                    assert_eq!(max, range.max);
+                }
+                other => panic!("Expected the value to be rejected, got {:?}", other),
+            }
+            assert_eq!(sensor.get_option(option).unwrap(), original_value);
+
+            let set_value = sensor
+                .set_option_checked(option, above_max, OutOfRangeMode::Clamp)
+                .unwrap();
+            assert_eq!(set_value, range.max);
+
+            sensor.restore_options(&snapshot).unwrap();
+        }
+    }
+}
+
+#[test]
+fn only_depth_sensors_convert_into_depth_sensors() {
+    let context = Context::new().unwrap();
+    let devices = context.query_devices(HashSet::new());
+
+    for device in devices {
+        for sensor in device.sensors() {
+            let is_color_sensor = sensor.extension() == Rs2Extension::ColorSensor;
+
+            match sensor.try_into_depth_sensor() {
+                Ok(depth_sensor) => {
+                    assert!(!is_color_sensor);
+                    assert!(depth_sensor.depth_scale().unwrap() > 0.0);
+                }
+                Err(sensor) => {
+                    // The original sensor is handed back intact.
+                    assert!(sensor.supports_info(Rs2CameraInfo::Name));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn region_of_interest_requires_roi_support() {
+    let context = Context::new().unwrap();
+    let devices = context.query_devices(HashSet::new());
+
+    for device in devices {
+        for mut sensor in device.sensors() {
+            if sensor.supports_region_of_interest() {
+                continue;
+            }
+
+            assert!(sensor.get_region_of_interest().is_none());
+
+            let roi = Rs2Roi {
+                min_x: 0,
+                min_y: 0,
+                max_x: 1,
+                max_y: 1,
+            };
+            assert!(matches!(
+                sensor.set_region_of_interest(roi),
+                Err(RoiSetError::RoiNotSupported)
+            ));
+        }
+    }
+}
+
+#[test]
+fn extrinsics_from_a_profile_to_itself_are_identity() {
+    let context = Context::new().unwrap();
+    let devices = context.query_devices(HashSet::new());
+
+    for device in devices {
+        for sensor in device.sensors() {
+            for profile in sensor.stream_profiles() {
+                let extrinsics = profile.extrinsics(&profile).unwrap();
+                let point = [0.25, -0.5, 1.5];
+
+                assert_eq!(
+                    extrinsics.rotation(),
+                    [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]
+                );
+                assert_eq!(extrinsics.translation(), [0.0, 0.0, 0.0]);
+                assert_eq!(extrinsics.transform_point(point), point);
+            }
+        }
+    }
+}
+
+#[test]
+fn stream_profiles_describe_their_configuration() {
+    let context = Context::new().unwrap();
+    let devices = context.query_devices(HashSet::new());
+
+    for device in devices {
+        for sensor in device.sensors() {
+            for profile in sensor.stream_profiles() {
+                assert!(profile.framerate() >= 0);
+
+                match profile.kind() {
+                    Rs2StreamKind::Depth
+                    | Rs2StreamKind::Color
+                    | Rs2StreamKind::Infrared
+                    | Rs2StreamKind::Fisheye => {
+                        let resolution = profile.resolution().unwrap();
+                        let intrinsics = profile.intrinsics().unwrap();
+
+                        assert!(resolution.width > 0 && resolution.height > 0);
+                        assert_eq!(resolution.width, intrinsics.width());
+                        assert_eq!(resolution.height, intrinsics.height());
+                    }
+                    _ => assert!(profile.resolution().is_err()),
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn setting_an_invalid_option_value_reports_an_invalid_value_exception() {
+    let context = Context::new().unwrap();
+    let devices = context.query_devices(HashSet::new());
+
+    for device in devices {
+        for mut sensor in device.sensors() {
+            let writable_option = sensor
+                .supported_options()
+                .into_iter()
+                .find(|(option, range)| {
+                    !sensor.is_option_read_only(*option)
+                        && range.min < range.max
+                        && range.step > 0.0
+                });
+
+            if let Some((option, range)) = writable_option {
+                let invalid_value = range.max + (range.max - range.min) + range.step;
+
+                match sensor.set_option(option, invalid_value) {
+                    Err(OptionSetError::CouldNotSetOption(exception, _)) => {
+                        assert_eq!(exception, Rs2Exception::InvalidValue);
+                        assert!(!exception.is_recoverable());
+                    }
+                    result => panic!("Expected an invalid value exception, got {:?}", result),
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn cloned_devices_refer_to_the_same_device() {
+    let context = Context::new().unwrap();
+    let devices = context.query_devices(HashSet::new());
+    assert!(!devices.is_empty());
+
+    for device in devices {
+        let clone = device.try_clone().unwrap();
+        let serial_number = device.serial_number();
+
+        drop(device);
+
+        assert!(serial_number.is_some());
+        assert_eq!(clone.serial_number(), serial_number);
+        assert_eq!(
+            clone.sensors().len(),
+            clone.try_clone().unwrap().sensors().len()
+        );
+    }
+}
+
+#[test]
+fn can_log_to_console_and_file() {
+    log::log_to_console(Rs2LogSeverity::Debug).unwrap();
+
+    let path = std::env::temp_dir().join("can_log_to_console_and_file.log");
+    let _ = std::fs::remove_file(&path);
+
+    log::log_to_file(Rs2LogSeverity::Debug, &path).unwrap();
+
+    // Creating a context produces log messages at debug severity.
+    let _context = Context::new().unwrap();
+    assert!(path.exists());
+}
+
 #[test]
 fn can_resolve_all_streams_always() {
     let context = Context::new().unwrap();
@@ -58,4 +586,409 @@ fn cannot_resolve_bad_config() {
 
     assert!(!pipeline.can_resolve(&config));
     assert!(pipeline.resolve(&config).is_none());
+    assert!(config.resolve(&pipeline).is_err());
+}
+
+#[test]
+fn can_start_wait_for_frames_and_stop() {
+    let context = Context::new().unwrap();
+    let pipeline = InactivePipeline::try_from(&context).unwrap();
+
+    let mut pipeline = pipeline.start(None).unwrap();
+
+    let frames = pipeline.wait(Some(Duration::from_millis(5000))).unwrap();
+    assert!(!frames.is_empty());
+
+    let pipeline = pipeline.stop();
+
+    // The pipeline can be restarted once it has been stopped.
+    let mut pipeline = pipeline.start(None).unwrap();
+    assert!(pipeline.wait(None).is_ok());
+}
+
+/// Dropping the context while a pipeline is streaming must not invalidate the pipeline, since
+/// librealsense2 keeps the underlying context alive until the pipeline is dropped.
+#[test]
+fn pipeline_keeps_streaming_after_context_is_dropped() {
+    let mut context = Context::new().unwrap();
+    context.set_devices_changed_callback(|_| {}).unwrap();
+
+    let pipeline = InactivePipeline::try_from(&context).unwrap();
+    let mut pipeline = pipeline.start(None).unwrap();
+    let device = pipeline.profile().device().try_clone().unwrap();
+    let frames = pipeline.wait(Some(Duration::from_millis(5000))).unwrap();
+
+    drop(context);
+
+    assert!(!frames.is_empty());
+    drop(frames);
+    assert!(!pipeline.wait(None).unwrap().is_empty());
+    assert!(device.serial_number().is_some());
+
+    let pipeline = pipeline.stop();
+    drop(pipeline);
+    drop(device);
+}
+
+#[test]
+fn can_poll_and_try_wait_for_frames() {
+    let context = Context::new().unwrap();
+    let pipeline = InactivePipeline::try_from(&context).unwrap();
+
+    let mut pipeline = pipeline.start(None).unwrap();
+
+    let frames = pipeline
+        .try_wait(Some(Duration::from_millis(5000)))
+        .unwrap()
+        .unwrap();
+    assert!(!frames.is_empty());
+
+    let mut polled_frames = None;
+    for _ in 0..500 {
+        if let Poll::Ready(frames) = pipeline.poll().unwrap() {
+            polled_frames = Some(frames);
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert!(!polled_frames.unwrap().is_empty());
+}
+
+/// Once a recording that does not loop has been played back to its end, no more frames arrive, so
+/// polling and waiting without a timeout deterministically find no frames.
+#[test]
+fn poll_and_try_wait_find_no_frames_after_playback_ends() {
+    let context = Context::new().unwrap();
+    let recording = common::Recording::new(&context, "pending-poll-test", 10);
+    let mut pipeline = recording.play(&context);
+
+    let mut played_back = 0;
+    while pipeline
+        .try_wait(Some(Duration::from_secs(1)))
+        .unwrap()
+        .is_some()
+    {
+        played_back += 1;
+        assert!(
+            played_back <= 100,
+            "The playback did not end after the frames that were recorded."
+        );
+    }
+    assert!(played_back > 0);
+
+    assert!(pipeline.poll().unwrap().is_pending());
+    assert!(pipeline
+        .try_wait(Some(Duration::from_millis(0)))
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn recorded_bag_can_be_played_back() {
+    let path = std::env::temp_dir().join("realsense-rust-recorded-bag-test.bag");
+    let context = Context::new().unwrap();
+
+    let mut config = Config::new();
+    config.enable_record_to_file(&path).unwrap();
+
+    let pipeline = InactivePipeline::try_from(&context).unwrap();
+    let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+    let recorder = pipeline.profile().device().as_recorder().unwrap();
+    assert!(recorder.filename().unwrap().ends_with(".bag"));
+    recorder.pause().unwrap();
+    recorder.resume().unwrap();
+
+    for _ in 0..30 {
+        let _ = pipeline.wait(None).unwrap();
+    }
+
+    // Stopping the pipeline finalizes the recording.
+    let pipeline = pipeline.stop();
+    drop(pipeline);
+
+    let mut config = Config::new();
+    config.enable_device_from_file(&path, false).unwrap();
+
+    let pipeline = InactivePipeline::try_from(&context).unwrap();
+    let mut pipeline = pipeline.start(Some(config)).unwrap();
+    assert!(pipeline.profile().device().as_recorder().is_none());
+
+    let frames = pipeline.wait(None).unwrap();
+    assert!(!frames.is_empty());
+    assert!(!pipeline.profile().device().sensors().is_empty());
+
+    let _pipeline = pipeline.stop();
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// Gets the names of the threads of this process.
+///
+/// Only Linux exposes the threads of a process, so this is empty on other platforms.
+fn thread_names() -> Vec<String> {
+    std::fs::read_dir("/proc/self/task")
+        .into_iter()
+        .flatten()
+        .filter_map(|task| std::fs::read_to_string(task.ok()?.path().join("comm")).ok())
+        .map(|name| name.trim_end().to_owned())
+        .collect()
+}
+
+#[test]
+fn capture_of_played_back_bag_gets_frames_and_drops_cleanly() {
+    let context = Context::new().unwrap();
+    let recording = common::Recording::new(&context, "capture-test", 30);
+
+    let capture = Capture::new(recording.play(&context), 4).unwrap();
+
+    // Bound the waits, so that the test fails instead of hanging if frames stop arriving.
+    let mut captured = 0;
+    for _ in 0..10 {
+        if let Some(frames) = capture.next_frame(Duration::from_secs(1)).unwrap() {
+            assert!(!frames.is_empty());
+            captured += 1;
+            if captured == 5 {
+                break;
+            }
+        }
+    }
+    assert_eq!(captured, 5, "The capture did not receive enough framesets.");
+
+    // Dropping the capture joins its thread and releases the frames that were not taken.
+    drop(capture);
+}
+
+#[test]
+fn capture_stop_joins_its_thread() {
+    let context = Context::new().unwrap();
+    let recording = common::Recording::new(&context, "capture-stop-test", 30);
+
+    let capture = Capture::new(recording.play(&context), 4).unwrap();
+    assert!(capture
+        .next_frame(Duration::from_secs(5))
+        .unwrap()
+        .is_some());
+    if cfg!(target_os = "linux") {
+        assert!(thread_names().iter().any(|name| name == "rs2-capture"));
+    }
+
+    let _pipeline = capture.stop();
+    assert!(
+        !thread_names().iter().any(|name| name == "rs2-capture"),
+        "The capture thread is still running after stop returned."
+    );
+}
+
+/// A recording only holds the streams it was recorded with, so requesting any other resolution
+/// cannot be satisfied.
+#[test]
+fn config_with_impossible_resolution_cannot_be_resolved_against_playback() {
+    let path = std::env::temp_dir().join("realsense-rust-can-resolve-test.bag");
+    let context = Context::new().unwrap();
+
+    let mut config = Config::new();
+    config.enable_record_to_file(&path).unwrap();
+
+    let pipeline = InactivePipeline::try_from(&context).unwrap();
+    let mut pipeline = pipeline.start(Some(config)).unwrap();
+    for _ in 0..30 {
+        let _ = pipeline.wait(None).unwrap();
+    }
+    // Stopping the pipeline finalizes the recording.
+    let pipeline = pipeline.stop();
+
+    let mut config = Config::new();
+    config.enable_device_from_file(&path, false).unwrap();
+    assert!(config.can_resolve(&pipeline));
+
+    config
+        .enable_stream(Rs2StreamKind::Depth, None, 12345, 6789, Rs2Format::Z16, 30)
+        .unwrap();
+    assert!(!config.can_resolve(&pipeline));
+
+    drop(pipeline);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn pipelines_can_play_back_two_recordings_simultaneously() {
+    let context = Context::new().unwrap();
+    let paths = [
+        std::env::temp_dir().join("realsense-rust-multi-pipeline-test-0.bag"),
+        std::env::temp_dir().join("realsense-rust-multi-pipeline-test-1.bag"),
+    ];
+
+    for path in paths.iter() {
+        let mut config = Config::new();
+        config.enable_record_to_file(path).unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+        for _ in 0..30 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+        // Stopping the pipeline finalizes the recording.
+        let _pipeline = pipeline.stop();
+    }
+
+    let mut pipelines = Vec::new();
+    for path in paths.iter() {
+        let mut config = Config::new();
+        config.enable_device_from_file(path, false).unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        pipelines.push(pipeline.start(Some(config)).unwrap());
+    }
+
+    for _ in 0..10 {
+        for pipeline in pipelines.iter_mut() {
+            assert!(!pipeline.wait(None).unwrap().is_empty());
+        }
+    }
+
+    for pipeline in pipelines {
+        let _pipeline = pipeline.stop();
+    }
+    for path in paths.iter() {
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn playback_can_seek_within_a_recording() {
+    let path = std::env::temp_dir().join("realsense-rust-playback-seek-test.bag");
+    let context = Context::new().unwrap();
+
+    let mut config = Config::new();
+    config.enable_record_to_file(&path).unwrap();
+
+    let pipeline = InactivePipeline::try_from(&context).unwrap();
+    let mut pipeline = pipeline.start(Some(config)).unwrap();
+    for _ in 0..60 {
+        let _ = pipeline.wait(None).unwrap();
+    }
+    drop(pipeline.stop());
+
+    let mut config = Config::new();
+    config.enable_device_from_file(&path, false).unwrap();
+
+    let pipeline = InactivePipeline::try_from(&context).unwrap();
+    let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+    let playback = pipeline.profile().device().as_playback().unwrap();
+    assert!(playback.file_path().unwrap().ends_with(".bag"));
+
+    playback.set_real_time(false).unwrap();
+    assert!(!playback.is_real_time().unwrap());
+
+    let duration = playback.duration().unwrap();
+    assert!(duration > Duration::ZERO);
+
+    let target = duration / 2;
+    playback.seek(target).unwrap();
+
+    let frames = pipeline.wait(None).unwrap();
+    assert!(!frames.is_empty());
+
+    let playback = pipeline.profile().device().as_playback().unwrap();
+    assert!(playback.current_position().unwrap() >= target);
+
+    let _pipeline = pipeline.stop();
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn can_stream_frames_asynchronously() {
+    use futures_core::Stream;
+    use realsense_rust::pipeline::Backpressure;
+    use std::{
+        pin::Pin,
+        sync::Arc,
+        task::{Context as TaskContext, Wake},
+        thread::{self, Thread},
+    };
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let context = Context::new().unwrap();
+    let pipeline = InactivePipeline::try_from(&context).unwrap();
+    let pipeline = pipeline.start(None).unwrap();
+
+    let mut stream = pipeline.into_frame_stream(2, Backpressure::DropOldest);
+
+    let waker = Arc::new(ThreadWaker(thread::current())).into();
+    let mut task_context = TaskContext::from_waker(&waker);
+
+    let mut count = 0;
+    while count < 10 {
+        match Pin::new(&mut stream).poll_next(&mut task_context) {
+            Poll::Ready(Some(frames)) => {
+                assert!(!frames.unwrap().is_empty());
+                count += 1;
+            }
+            Poll::Ready(None) => panic!("Frame stream ended unexpectedly"),
+            Poll::Pending => thread::park_timeout(Duration::from_millis(100)),
+        }
+    }
+
+    let _pipeline = stream.stop();
+}
+
+#[test]
+fn active_stream_profiles_reflect_opened_profiles() {
+    let context = Context::new().unwrap();
+    let devices = context.query_devices(HashSet::new());
+    assert!(!devices.is_empty());
+
+    for mut sensor in devices[0].sensors() {
+        // Open the default profiles of two different streams, which are compatible with each
+        // other, e.g. depth and infrared on a stereo module.
+        let mut streams = HashSet::new();
+        let mut profiles = sensor
+            .stream_profiles()
+            .into_iter()
+            .filter(|p| p.is_default() && streams.insert((p.kind(), p.index())))
+            .collect::<Vec<_>>();
+        if profiles.len() < 2 {
+            continue;
+        }
+        profiles.truncate(2);
+
+        assert!(sensor.active_stream_profiles().is_empty());
+
+        sensor.open(&profiles).unwrap();
+        assert_eq!(sensor.active_stream_profiles().len(), 2);
+
+        sensor.close().unwrap();
+        assert!(sensor.active_stream_profiles().is_empty());
+    }
+}
+
+#[test]
+fn processing_blocks_report_their_options() {
+    let decimation = DecimationFilter::new().unwrap();
+    let options = decimation.supported_options();
+    let (_, magnitude) = options
+        .iter()
+        .find(|(option, _)| *option == Rs2Option::FilterMagnitude)
+        .unwrap();
+    assert!(magnitude.min <= magnitude.default && magnitude.default <= magnitude.max);
+    assert!(magnitude.step > 0.0);
+
+    let threshold = ThresholdFilter::new().unwrap();
+    let options = threshold.supported_options();
+    for expected in [Rs2Option::MinDistance, Rs2Option::MaxDistance] {
+        let (_, range) = options
+            .iter()
+            .find(|(option, _)| *option == expected)
+            .unwrap();
+        assert!(range.min < range.max);
+    }
 }
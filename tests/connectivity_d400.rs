@@ -3,29 +3,41 @@
 #![cfg(feature = "test-single-device")]
 
 use realsense_rust::{
-    base::Rs2Roi,
-    config::Config,
+    base::{Rs2Resolution, Rs2Roi},
+    capture,
+    config::{Config, ConfigBuilder, OfferedStream, UnsupportedStreamsError},
     context::Context,
-    frame::{ColorFrame, DepthFrame, FrameEx, InfraredFrame},
-    kind::{Rs2CameraInfo, Rs2Extension, Rs2Format, Rs2Option, Rs2ProductLine, Rs2StreamKind},
+    device::L515SettingsError,
+    frame::{
+        ColorFrame, DepthError, DepthFrame, FormatError, FrameDataError, FrameEx, InfraredFrame,
+        KeepLast, PixelKind, PoseFrame,
+    },
+    frame_queue::FrameQueue,
+    kind::{
+        Rs2CameraInfo, Rs2Extension, Rs2Format, Rs2Option, Rs2StreamKind, Rs2TimestampDomain,
+        Rs2VisualPreset,
+    },
     pipeline::InactivePipeline,
+    processing_blocks::{
+        Align, CustomProcessingBlock, DecimationFilter, DisparityTransform, PointCloud, Syncer,
+        ThresholdFilter, UnitsTransform,
+    },
 };
 use std::{
     collections::{HashMap, HashSet},
     convert::TryFrom,
+    sync::Arc,
+    task::Poll,
     time::Duration,
 };
 
+mod common;
+
 #[test]
 fn d400_can_resolve_color_and_depth_and_infrared() {
     let context = Context::new().unwrap();
 
-    let mut queryable_set = HashSet::new();
-    queryable_set.insert(Rs2ProductLine::D400);
-
-    let devices = context.query_devices(queryable_set);
-
-    if let Some(device) = devices.get(0) {
+    if let Some(device) = common::d400_device(&context) {
         let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
         let mut config = Config::new();
 
@@ -74,12 +86,7 @@ fn d400_can_resolve_color_and_depth_and_infrared() {
 fn d400_streams_at_expected_framerate() {
     let context = Context::new().unwrap();
 
-    let mut queryable_set = HashSet::new();
-    queryable_set.insert(Rs2ProductLine::D400);
-
-    let devices = context.query_devices(queryable_set);
-
-    if let Some(device) = devices.get(0) {
+    if let Some(device) = common::d400_device(&context) {
         let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
         let mut config = Config::new();
 
@@ -146,12 +153,7 @@ fn d400_streams_at_expected_framerate() {
 fn d400_streams_are_distinct() {
     let context = Context::new().unwrap();
 
-    let mut queryable_set = HashSet::new();
-    queryable_set.insert(Rs2ProductLine::D400);
-
-    let devices = context.query_devices(queryable_set);
-
-    if let Some(device) = devices.get(0) {
+    if let Some(device) = common::d400_device(&context) {
         let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
         let mut config = Config::new();
 
@@ -198,6 +200,23 @@ fn d400_streams_are_distinct() {
             frames.frames_of_type::<InfraredFrame>().len(),
             expected_frame_count - 2
         );
+
+        let color_frame = frames.first_of_type::<ColorFrame>().unwrap();
+        assert!(color_frame.is_color());
+        assert!(!color_frame.is_infrared());
+        assert_eq!(color_frame.infrared_index(), None);
+
+        let mut infrared_indices: Vec<usize> = frames
+            .iter::<InfraredFrame>()
+            .map(|frame| {
+                assert!(frame.is_infrared() && !frame.is_color());
+                frame.infrared_index().unwrap()
+            })
+            .collect();
+        infrared_indices.sort_unstable();
+        if usb_val >= 3.0 {
+            assert_eq!(infrared_indices, vec![1, 2]);
+        }
     }
 }
 
@@ -231,12 +250,7 @@ fn d400_streams_check_supported_but_ignored_sensor_options() {
 
     let context = Context::new().unwrap();
 
-    let mut queryable_set = HashSet::new();
-    queryable_set.insert(Rs2ProductLine::L500);
-
-    let devices = context.query_devices(queryable_set);
-
-    if let Some(device) = devices.get(0) {
+    if let Some(device) = common::d400_device(&context) {
         // Grab the sensor list
         for mut sensor in device.sensors() {
             for (option, val) in &options_to_set {
@@ -293,12 +307,7 @@ fn d400_streams_check_supported_but_ignored_sensor_options() {
 fn d400_frame_numbers_increase() {
     let context = Context::new().unwrap();
 
-    let mut queryable_set = HashSet::new();
-    queryable_set.insert(Rs2ProductLine::D400);
-
-    let devices = context.query_devices(queryable_set);
-
-    if let Some(device) = devices.get(0) {
+    if let Some(device) = common::d400_device(&context) {
         let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
         let mut config = Config::new();
 
@@ -336,12 +345,7 @@ fn d400_frame_numbers_increase() {
 fn d400_region_of_interest_accessible() {
     let context = Context::new().unwrap();
 
-    let mut queryable_set = HashSet::new();
-    queryable_set.insert(Rs2ProductLine::D400);
-
-    let devices = context.query_devices(queryable_set);
-
-    if let Some(device) = devices.get(0) {
+    if let Some(device) = common::d400_device(&context) {
         let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
         let mut config = Config::new();
 
@@ -386,3 +390,1945 @@ fn d400_region_of_interest_accessible() {
         color_sensor.set_region_of_interest(roi).unwrap();
     }
 }
+
+/// Verify that converting a depth frame to disparity and back reproduces the original depth.
+#[test]
+fn d400_disparity_transform_round_trips_depth() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+        let width = depth_frame.width();
+        let height = depth_frame.height();
+
+        let samples: Vec<(usize, usize, f32)> = (1..8)
+            .flat_map(|i| (1..8).map(move |j| (i * width / 8, j * height / 8)))
+            .map(|(col, row)| (col, row, depth_frame.distance(col, row).unwrap()))
+            .collect();
+
+        let mut to_disparity = DisparityTransform::depth_to_disparity().unwrap();
+        let mut to_depth = DisparityTransform::disparity_to_depth().unwrap();
+
+        let disparity_frame = to_disparity.process(depth_frame).unwrap();
+        let round_tripped = to_depth.process(disparity_frame).unwrap();
+
+        assert_eq!(round_tripped.width(), width);
+        assert_eq!(round_tripped.height(), height);
+
+        for (col, row, distance) in samples {
+            let new_distance = round_tripped.distance(col, row).unwrap();
+            assert!(
+                (new_distance - distance).abs() <= 0.01 * distance + 0.001,
+                "Distance at ({}, {}) was {} before round trip, but {} after",
+                col,
+                row,
+                distance,
+                new_distance
+            );
+        }
+    }
+}
+
+#[test]
+fn d400_pointcloud_has_a_point_per_depth_pixel() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Color, None, 0, 0, Rs2Format::Rgb8, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+        let color_frame = frameset.frames_of_type::<ColorFrame>().pop().unwrap();
+
+        let (width, height) = (depth_frame.width(), depth_frame.height());
+        let pixel_count = width * height;
+        let valid_depth_count = depth_frame
+            .iter()
+            .filter(|pixel| !matches!(pixel, PixelKind::Z16 { depth: 0 }))
+            .count();
+
+        let texture = frameset.first_of_type::<ColorFrame>().unwrap();
+
+        let mut pointcloud = PointCloud::new().unwrap();
+        pointcloud.map_to(color_frame).unwrap();
+        let points = pointcloud.calculate(depth_frame).unwrap();
+
+        let valid_point_count = points.vertices().iter().filter(|v| v.xyz[2] != 0.0).count();
+
+        assert_eq!(points.points_count(), pixel_count);
+        assert_eq!(points.texture_coordinates().len(), pixel_count);
+        assert!(valid_point_count <= valid_depth_count);
+
+        // Points calculated from a depth frame are organized in a grid of its resolution.
+        assert_eq!(points.width(), Some(width));
+        assert_eq!(points.height(), Some(height));
+        assert_eq!(
+            points.width().unwrap() * points.height().unwrap(),
+            points.points_count()
+        );
+
+        // Every point maps to a pixel within the color frame it was mapped to.
+        let texture_pixels = points.texture_pixels(&texture);
+        assert_eq!(texture_pixels.len(), pixel_count);
+        for (col, row) in texture_pixels {
+            assert!(col < texture.width() && row < texture.height());
+            assert!(texture.get(col, row).is_some());
+        }
+    }
+}
+
+#[test]
+fn d400_frame_queue_dequeues_frames_in_order() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        let queue = FrameQueue::new(5).unwrap();
+        assert!(queue.is_empty());
+        assert!(queue.poll::<DepthFrame>().unwrap().is_pending());
+
+        let mut frame_numbers = Vec::new();
+        for _ in 0..5 {
+            let frameset = pipeline.wait(None).unwrap();
+            let depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+            frame_numbers.push(depth_frame.frame_number());
+            queue.enqueue(depth_frame);
+        }
+        assert_eq!(queue.len(), 5);
+
+        for frame_number in frame_numbers {
+            let depth_frame = queue
+                .wait::<DepthFrame>(Some(Duration::from_millis(100)))
+                .unwrap();
+            assert_eq!(depth_frame.frame_number(), frame_number);
+        }
+        assert!(queue.is_empty());
+    }
+}
+
+#[test]
+fn d400_composite_frame_can_be_iterated_repeatedly() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Color, None, 0, 0, Rs2Format::Rgb8, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        let frameset = pipeline.wait(None).unwrap();
+
+        let first_count = frameset.iter::<DepthFrame>().count();
+        let second_count = frameset.iter::<DepthFrame>().count();
+        assert_eq!(first_count, 1);
+        assert_eq!(first_count, second_count);
+
+        // Frames taken from the iterator outlive it, and can be moved to other threads.
+        let frames: Vec<ColorFrame> = frameset.iter().collect();
+        let handle = std::thread::spawn(move || frames.len());
+        assert_eq!(handle.join().unwrap(), 1);
+        assert_eq!(frameset.frames_of_type::<ColorFrame>().len(), 1);
+    }
+}
+
+#[test]
+fn d400_composite_frame_gets_first_frame_of_type() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Color, None, 0, 0, Rs2Format::Rgb8, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        let frameset = pipeline.wait(None).unwrap();
+
+        let color_frame = frameset.first_of_type::<ColorFrame>().unwrap();
+        assert_eq!(color_frame.stream_profile().kind(), Rs2StreamKind::Color);
+        let depth_frame = frameset.first_of_type::<DepthFrame>().unwrap();
+        assert_eq!(depth_frame.stream_profile().kind(), Rs2StreamKind::Depth);
+        assert!(frameset.first_of_type::<PoseFrame>().is_none());
+    }
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn d400_color_frame_can_be_saved_as_png() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Color, None, 640, 0, Rs2Format::Rgb8, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        let frames = pipeline.wait(None).unwrap();
+        let color_frame = frames.frames_of_type::<ColorFrame>().pop().unwrap();
+        let color_image = color_frame.to_image().unwrap();
+
+        let path = std::env::temp_dir().join("d400_color_frame_can_be_saved_as_png.png");
+        color_image.save(&path).unwrap();
+
+        let saved = image::open(&path).unwrap().into_rgb8();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(saved.width() as usize, color_frame.width());
+        assert_eq!(saved.height() as usize, color_frame.height());
+        assert_eq!(saved.as_raw(), color_image.as_bytes());
+    }
+}
+
+#[cfg(feature = "ndarray")]
+#[test]
+fn d400_depth_and_points_frames_convert_to_arrays() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        let frames = pipeline.wait(None).unwrap();
+        let depth_frame = frames.frames_of_type::<DepthFrame>().pop().unwrap();
+        let (width, height) = (depth_frame.width(), depth_frame.height());
+
+        let depths = depth_frame.to_array2().unwrap();
+        assert_eq!(depths.dim(), (height, width));
+        match depth_frame.get(width / 2, height / 2).unwrap() {
+            PixelKind::Z16 { depth } => assert_eq!(depths[[height / 2, width / 2]], *depth),
+            _ => panic!("depth frame should hold Z16 pixels"),
+        }
+
+        let mut pointcloud = PointCloud::new().unwrap();
+        let points_frame = pointcloud.calculate(depth_frame).unwrap();
+        assert_eq!(points_frame.to_array2().dim(), (width * height, 3));
+    }
+}
+
+#[test]
+fn d400_advanced_mode_json_round_trips() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let advanced_mode = device.as_advanced_mode().unwrap();
+
+        // Enabling advanced mode resets the device, so only test devices where it already is.
+        if !advanced_mode.is_enabled().unwrap() {
+            return;
+        }
+
+        let json = advanced_mode.serialize_json().unwrap();
+        advanced_mode.load_json(&json).unwrap();
+        assert_eq!(advanced_mode.serialize_json().unwrap(), json);
+    }
+}
+
+/// Verify that the depth control group can be changed and restored in advanced mode.
+#[test]
+fn d400_advanced_mode_depth_control_round_trips() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let advanced_mode = device.as_advanced_mode().unwrap();
+
+        // Enabling advanced mode resets the device, so only test devices where it already is.
+        if !advanced_mode.is_enabled().unwrap() {
+            return;
+        }
+
+        let original = advanced_mode.depth_control().unwrap();
+        let (min, max) = advanced_mode.depth_control_range().unwrap();
+        assert!(min.score_threshold_b <= original.score_threshold_b);
+        assert!(original.score_threshold_b <= max.score_threshold_b);
+
+        let mut tuned = original;
+        tuned.texture_count_threshold =
+            if original.texture_count_threshold < max.texture_count_threshold {
+                original.texture_count_threshold + 1
+            } else {
+                original.texture_count_threshold - 1
+            };
+        advanced_mode.set_depth_control(&tuned).unwrap();
+        assert_eq!(advanced_mode.depth_control().unwrap(), tuned);
+
+        advanced_mode.set_depth_control(&original).unwrap();
+        assert_eq!(advanced_mode.depth_control().unwrap(), original);
+    }
+}
+
+/// Verify that the calibration table of a device can be read and applied again.
+///
+/// The table is not written to the device, so the calibration is unchanged once it is reset.
+#[test]
+fn d400_calibration_table_round_trips() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let auto_calibrated = device.as_auto_calibrated().unwrap();
+
+        let table = auto_calibrated.calibration_table().unwrap();
+        assert!(!table.is_empty());
+
+        auto_calibrated.set_calibration_table(&table).unwrap();
+        assert_eq!(auto_calibrated.calibration_table().unwrap(), table);
+    }
+}
+
+/// Verify that the threshold filter discards depth outside of the distance range.
+#[test]
+fn d400_threshold_filter_discards_depth_outside_of_range() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+        let width = depth_frame.width();
+        let height = depth_frame.height();
+
+        let samples: Vec<(usize, usize, f32)> = (1..8)
+            .flat_map(|i| (1..8).map(move |j| (i * width / 8, j * height / 8)))
+            .map(|(col, row)| (col, row, depth_frame.distance(col, row).unwrap()))
+            .collect();
+
+        let mut threshold = ThresholdFilter::new().unwrap();
+        threshold.set_distance_range(0.5, 2.0).unwrap();
+        let thresholded = threshold.process(depth_frame).unwrap();
+
+        for (col, row, distance) in samples {
+            let new_distance = thresholded.distance(col, row).unwrap();
+            if distance > 2.0 {
+                assert_eq!(
+                    new_distance, 0.0,
+                    "Distance at ({}, {}) was {} before thresholding, but {} after",
+                    col, row, distance, new_distance
+                );
+            } else {
+                assert!(new_distance == 0.0 || (0.5..=2.0).contains(&new_distance));
+            }
+        }
+    }
+}
+
+/// Verify that decimating with a magnitude of two halves the resolution of depth frames.
+#[test]
+fn d400_decimation_filter_reduces_resolution() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 640, 480, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+        let width = depth_frame.width();
+        let height = depth_frame.height();
+
+        let mut decimation = DecimationFilter::new().unwrap();
+        decimation.set_magnitude(2).unwrap();
+        let decimated = decimation.process(depth_frame).unwrap();
+
+        assert_eq!(decimated.width(), width / 2);
+        assert_eq!(decimated.height(), height / 2);
+    }
+}
+
+/// Verify that the units transform produces the same distances as querying every pixel.
+#[test]
+fn d400_units_transform_matches_distance() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+        let width = depth_frame.width();
+        let height = depth_frame.height();
+
+        let samples: Vec<(usize, usize, f32)> = (1..8)
+            .flat_map(|i| (1..8).map(move |j| (i * width / 8, j * height / 8)))
+            .map(|(col, row)| (col, row, depth_frame.distance(col, row).unwrap()))
+            .collect();
+
+        // Raw depth is not floating point, so it cannot be read as such.
+        assert!(matches!(
+            depth_frame.as_slice_f32(),
+            Err(FrameDataError::UnsupportedFormat(FormatError(
+                Rs2Format::Z16
+            )))
+        ));
+
+        let mut units_transform = UnitsTransform::new().unwrap();
+        let meters = units_transform.process(depth_frame).unwrap();
+        assert_eq!(meters.stream_profile().format(), Rs2Format::Distance);
+
+        let row_length = meters.stride() / std::mem::size_of::<f32>();
+        let distances = meters.as_slice_f32().unwrap();
+
+        for (col, row, distance) in samples {
+            let new_distance = distances[row * row_length + col];
+            assert!(
+                (new_distance - distance).abs() <= 0.001,
+                "Distance at ({}, {}) was {}, but transformed to {}",
+                col,
+                row,
+                distance,
+                new_distance
+            );
+        }
+    }
+}
+
+/// Verify that a syncer groups depth and color frames that were split up back into framesets.
+#[test]
+fn d400_syncer_regroups_depth_and_color() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Color, None, 0, 0, Rs2Format::Rgb8, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let mut syncer = Syncer::new().unwrap();
+
+        // The syncer only learns which streams exist as their frames arrive, so the first few
+        // framesets it outputs may be incomplete.
+        let mut found_pair = false;
+        for _ in 0..10 {
+            let frameset = pipeline.wait(None).unwrap();
+            let depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+            let color_frame = frameset.frames_of_type::<ColorFrame>().pop().unwrap();
+
+            syncer.queue(depth_frame).unwrap();
+            syncer.queue(color_frame).unwrap();
+
+            while let Poll::Ready(synced) = syncer.poll().unwrap() {
+                if synced.count() == 2 {
+                    found_pair = true;
+                }
+            }
+            if found_pair {
+                break;
+            }
+        }
+        assert!(found_pair);
+    }
+}
+
+/// Verify that only the most recent frames are retained by a `KeepLast` buffer.
+#[test]
+fn d400_keep_last_retains_most_recent_depth_frames() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let capacity = 3;
+        let mut buffer = KeepLast::new(capacity);
+        let mut frame_numbers = Vec::new();
+
+        for _ in 0..capacity + 2 {
+            let frameset = pipeline.wait(None).unwrap();
+            let depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+            frame_numbers.push(depth_frame.frame_number());
+            buffer.push(depth_frame);
+        }
+
+        assert_eq!(buffer.len(), capacity);
+        let retained: Vec<u64> = buffer.frames().iter().map(|f| f.frame_number()).collect();
+        assert_eq!(retained, frame_numbers[2..]);
+    }
+}
+
+/// Verify that writes to the mutable data of a frame are visible when reading the frame.
+#[test]
+fn d400_depth_frame_data_can_be_mutated_in_place() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        // A frame from the pipeline may still be read through the frameset or the frame pool.
+        let frameset = pipeline.wait(None).unwrap();
+        let mut depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+        drop(frameset);
+        assert!(depth_frame.data_mut().is_err());
+
+        // A newly allocated frame is only referred to by the processing block, until it is output.
+        let depth: u16 = 1234;
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut fill = CustomProcessingBlock::from_fn(move |frame: DepthFrame, source| {
+            let profile = frame.stream_profile();
+            let mut output = source
+                .pool()
+                .allocate_uninitialized_video_frame(&frame, profile, frame.width(), frame.height())
+                .unwrap();
+            for pixel in output.data_mut().unwrap().chunks_exact_mut(2) {
+                pixel.copy_from_slice(&depth.to_ne_bytes());
+            }
+
+            let corners = [(0, 0), (output.width() - 1, output.height() - 1)];
+            let read = corners
+                .iter()
+                .map(|(col, row)| match output.get(*col, *row) {
+                    Some(PixelKind::Z16 { depth }) => Some(*depth),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            sender.send(read).unwrap();
+            source.frame_ready(output).unwrap();
+        })
+        .unwrap();
+
+        fill.queue(depth_frame).unwrap();
+        let read = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(read, vec![Some(depth), Some(depth)]);
+    }
+}
+
+/// Verify that deprojecting a depth frame on the CPU yields a point per valid depth pixel, which
+/// agrees with the point cloud processing block.
+#[test]
+fn d400_depth_frame_deprojects_to_points() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+
+        let valid_depth_count = depth_frame
+            .iter()
+            .filter(|pixel| !matches!(pixel, PixelKind::Z16 { depth: 0 }))
+            .count();
+
+        let intrinsics = depth_frame.stream_profile().intrinsics().unwrap();
+        let points = depth_frame.deproject_to_points(&intrinsics).unwrap();
+
+        assert_eq!(points.len(), valid_depth_count);
+        assert!(points.iter().all(|p| p[2] > 0.0));
+
+        let mut pointcloud = PointCloud::new().unwrap();
+        let vertices = pointcloud.calculate(depth_frame).unwrap();
+        let block_points: Vec<[f32; 3]> = vertices
+            .vertices()
+            .iter()
+            .map(|v| v.xyz)
+            .filter(|xyz| xyz[2] != 0.0)
+            .collect();
+
+        assert_eq!(block_points.len(), points.len());
+        for (cpu, block) in points.iter().zip(&block_points) {
+            for axis in 0..3 {
+                assert!((cpu[axis] - block[axis]).abs() < 0.001);
+            }
+        }
+    }
+}
+
+/// Verify that a single depth frame can be read from several threads at once.
+#[test]
+fn d400_depth_frame_can_be_shared_between_threads() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = Arc::new(frameset.frames_of_type::<DepthFrame>().pop().unwrap());
+        // The frameset is dropped first, so the threads hold the last references to the frame.
+        drop(frameset);
+
+        let thread_count = 4;
+        let rows_per_thread = depth_frame.height() / thread_count;
+        let handles: Vec<_> = (0..thread_count)
+            .map(|i| {
+                let depth_frame = Arc::clone(&depth_frame);
+                std::thread::spawn(move || {
+                    (i * rows_per_thread..(i + 1) * rows_per_thread)
+                        .flat_map(|row| (0..depth_frame.width()).map(move |col| (col, row)))
+                        .filter(|&(col, row)| depth_frame.distance(col, row).unwrap() > 0.0)
+                        .count()
+                })
+            })
+            .collect();
+        drop(depth_frame);
+
+        let valid_count: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+        assert!(valid_count > 0);
+    }
+}
+
+/// Verify that the sensors of a D400 recording can be enumerated, and include a depth sensor.
+#[test]
+fn d400_playback_has_a_depth_sensor() {
+    let context = Context::new().unwrap();
+    let config = ConfigBuilder::new().depth(640, 480, 30).build().unwrap();
+    if let Some(recording) = common::Recording::d400(&context, "depth-sensor-test", config, 30) {
+        let pipeline = recording.play(&context);
+        let playback = pipeline.profile().device();
+        assert!(!playback.sensors().is_empty());
+
+        let depth_sensor = playback.first_depth_sensor().unwrap();
+        assert!(depth_sensor.depth_scale().unwrap() > 0.0);
+    }
+}
+
+/// Verify that a configuration from the builder resolves against a recording of the same streams.
+#[test]
+fn d400_config_builder_resolves_against_playback() {
+    let context = Context::new().unwrap();
+    let builder = ConfigBuilder::new()
+        .depth(640, 480, 30)
+        .color(640, 480, 30, Rs2Format::Rgb8);
+    if let Some(recording) = common::Recording::d400(
+        &context,
+        "config-builder-test",
+        builder.build().unwrap(),
+        30,
+    ) {
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let profile = builder.file(recording.path()).resolve(&pipeline).unwrap();
+        let streams = profile.streams();
+        assert_eq!(streams.len(), 2);
+        assert!(streams
+            .iter()
+            .any(|s| s.kind() == Rs2StreamKind::Depth && s.format() == Rs2Format::Z16));
+        assert!(streams
+            .iter()
+            .any(|s| s.kind() == Rs2StreamKind::Color && s.format() == Rs2Format::Rgb8));
+    }
+}
+
+/// Verify that a configuration from the builder which a recording cannot satisfy fails to resolve,
+/// listing the streams of the recording.
+#[test]
+fn d400_config_builder_reports_offered_streams_of_playback() {
+    let context = Context::new().unwrap();
+    let config = ConfigBuilder::new().depth(640, 480, 30).build().unwrap();
+    if let Some(recording) = common::Recording::d400(&context, "offered-streams-test", config, 30) {
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let error = ConfigBuilder::new()
+            .color(640, 480, 30, Rs2Format::Rgb8)
+            .file(recording.path())
+            .resolve(&pipeline)
+            .unwrap_err();
+        let error = error.downcast::<UnsupportedStreamsError>().unwrap();
+
+        let depth = OfferedStream {
+            kind: Rs2StreamKind::Depth,
+            index: 0,
+            resolution: Some(Rs2Resolution {
+                width: 640,
+                height: 480,
+            }),
+            format: Rs2Format::Z16,
+            framerate: 30,
+        };
+        assert_eq!(error.offered, vec![depth]);
+        assert!(error.to_string().contains(&depth.to_string()));
+    }
+}
+
+/// Verify that the sensors of a D400 recording cannot be used as pose sensors.
+#[test]
+fn d400_playback_has_no_pose_sensor() {
+    let context = Context::new().unwrap();
+    let config = ConfigBuilder::new().depth(640, 480, 30).build().unwrap();
+    if let Some(recording) = common::Recording::d400(&context, "pose-sensor-test", config, 30) {
+        let mut pipeline = recording.play(&context);
+        let playback = pipeline.profile().device();
+        assert!(playback.as_playback().is_some());
+        assert!(playback.first_pose_sensor().is_none());
+        for sensor in playback.sensors() {
+            assert!(sensor.try_into_pose_sensor().is_err());
+        }
+
+        let _ = pipeline.wait(None).unwrap();
+    }
+}
+
+/// Verify that a single profile can be opened and streamed directly from a sensor of a recording.
+#[test]
+fn d400_playback_sensor_streams_a_single_profile() {
+    let mut context = Context::new().unwrap();
+    let config = ConfigBuilder::new()
+        .depth(640, 480, 30)
+        .infrared(1, 640, 480, 30)
+        .build()
+        .unwrap();
+    if let Some(recording) = common::Recording::d400(&context, "sensor-streaming-test", config, 30)
+    {
+        let device = context.add_device(recording.path()).unwrap();
+        let mut sensor = device.first_depth_sensor().unwrap();
+        let profile = sensor
+            .stream_profiles_of_kind(Rs2StreamKind::Depth)
+            .pop()
+            .unwrap();
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        sensor.open(&[profile]).unwrap();
+        sensor
+            .start(move |frame: DepthFrame| {
+                let _ = sender.send(frame.frame_number());
+            })
+            .unwrap();
+
+        assert!(receiver.recv_timeout(Duration::from_secs(5)).is_ok());
+        sensor.stop().unwrap();
+        sensor.close().unwrap();
+    }
+}
+
+/// Verify that the callback of a streaming sensor is only passed frames of its frame type.
+///
+/// Depth and infrared frames are both video frames, so this only passes if frames of the wrong
+/// stream kind are dropped as well as frames of the wrong extension.
+#[test]
+fn d400_sensor_callback_drops_frames_of_other_kinds() {
+    let mut context = Context::new().unwrap();
+    let config = ConfigBuilder::new()
+        .depth(640, 480, 30)
+        .infrared(1, 640, 480, 30)
+        .build()
+        .unwrap();
+    if let Some(recording) = common::Recording::d400(&context, "sensor-filter-test", config, 30) {
+        let device = context.add_device(recording.path()).unwrap();
+        let mut sensor = device.first_depth_sensor().unwrap();
+        let profiles = sensor.stream_profiles();
+        assert!(profiles.iter().any(|p| p.kind() == Rs2StreamKind::Depth));
+        assert!(profiles.iter().any(|p| p.kind() == Rs2StreamKind::Infrared));
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        sensor.open(&profiles).unwrap();
+        sensor
+            .start(move |frame: InfraredFrame| {
+                let _ = sender.send(frame.stream_profile().kind());
+            })
+            .unwrap();
+
+        for _ in 0..10 {
+            let kind = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+            assert_eq!(kind, Rs2StreamKind::Infrared);
+        }
+        sensor.stop().unwrap();
+        sensor.close().unwrap();
+    }
+}
+
+/// Verify that a cloned stream profile remains usable after the frame it came from is dropped.
+#[test]
+fn d400_cloned_stream_profile_outlives_its_frame() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 640, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+        let intrinsics = depth_frame.stream_profile().intrinsics().unwrap();
+        let profile = depth_frame.stream_profile().try_clone().unwrap();
+
+        drop(depth_frame);
+        drop(frameset);
+        let _pipeline = pipeline.stop();
+
+        assert_eq!(profile.kind(), Rs2StreamKind::Depth);
+        assert_eq!(profile.format(), Rs2Format::Z16);
+
+        let cloned_intrinsics = profile.intrinsics().unwrap();
+        assert_eq!(cloned_intrinsics.width(), intrinsics.width());
+        assert_eq!(cloned_intrinsics.height(), intrinsics.height());
+        assert_eq!(
+            [cloned_intrinsics.fx(), cloned_intrinsics.fy()],
+            [intrinsics.fx(), intrinsics.fy()]
+        );
+        assert_eq!(
+            [cloned_intrinsics.ppx(), cloned_intrinsics.ppy()],
+            [intrinsics.ppx(), intrinsics.ppy()]
+        );
+    }
+}
+
+/// Verify that toggling global time on the depth sensor changes the domain of depth timestamps.
+#[test]
+fn d400_global_time_changes_timestamp_domain() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let mut depth_sensor = device.first_depth_sensor().unwrap();
+        if !depth_sensor.supports_option(Rs2Option::GlobalTimeEnabled) {
+            return;
+        }
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+
+        for enabled in [true, false] {
+            depth_sensor.enable_global_time(enabled).unwrap();
+            assert_eq!(depth_sensor.is_global_time_enabled(), enabled);
+
+            let mut config = Config::new();
+            config
+                .enable_device_from_serial(serial)
+                .unwrap()
+                .disable_all_streams()
+                .unwrap()
+                .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+                .unwrap();
+
+            let pipeline = InactivePipeline::try_from(&context).unwrap();
+            let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+            // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+            for _ in 0..5 {
+                let _ = pipeline.wait(None).unwrap();
+            }
+
+            let frameset = pipeline.wait(None).unwrap();
+            let depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+            assert_eq!(
+                depth_frame.timestamp_domain() == Rs2TimestampDomain::GlobalTime,
+                enabled
+            );
+
+            drop(depth_frame);
+            drop(frameset);
+            let _pipeline = pipeline.stop();
+        }
+
+        // Global time is enabled by default, so restore that for the other tests.
+        depth_sensor.enable_global_time(true).unwrap();
+    }
+}
+
+/// Verify that a visual preset can be applied to the depth sensor and read back.
+#[test]
+fn d400_can_set_and_get_visual_preset() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let mut depth_sensor = device.first_depth_sensor().unwrap();
+        if !depth_sensor.supports_option(Rs2Option::VisualPreset) {
+            return;
+        }
+
+        depth_sensor
+            .set_visual_preset(Rs2VisualPreset::HighAccuracy)
+            .unwrap();
+        assert_eq!(
+            depth_sensor.get_visual_preset(),
+            Some(Rs2VisualPreset::HighAccuracy)
+        );
+
+        // Restore the default preset for the other tests.
+        depth_sensor
+            .set_option_enum(Rs2Option::VisualPreset, Rs2VisualPreset::Default)
+            .unwrap();
+        assert_eq!(
+            depth_sensor.get_visual_preset(),
+            Some(Rs2VisualPreset::Default)
+        );
+    }
+}
+
+/// Verify that the temperatures recorded for the depth sensor are plausible, if there are any.
+#[test]
+fn d400_depth_sensor_reports_plausible_temperatures() {
+    let context = Context::new().unwrap();
+    let config = ConfigBuilder::new().depth(640, 480, 30).build().unwrap();
+    if let Some(recording) = common::Recording::d400(&context, "temperature-test", config, 30) {
+        let pipeline = recording.play(&context);
+        let depth_sensor = pipeline.profile().device().first_depth_sensor().unwrap();
+        let health = depth_sensor.health_check();
+
+        assert_eq!(health.asic_temperature, depth_sensor.asic_temperature());
+        assert_eq!(
+            health.emitter_enabled.is_some(),
+            depth_sensor.supports_option(Rs2Option::EmitterEnabled)
+        );
+        for temperature in [health.asic_temperature, health.projector_temperature]
+            .iter()
+            .flatten()
+        {
+            assert!((0.0..100.0).contains(temperature));
+        }
+    }
+}
+
+/// Verify that the shared data of a frame remains valid after the frame itself is dropped.
+#[test]
+fn d400_frame_bytes_outlive_their_frame() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.first_of_type::<DepthFrame>().unwrap();
+        let (width, stride) = (depth_frame.width(), depth_frame.stride());
+        let depths: Vec<u16> = depth_frame
+            .iter()
+            .map(|pixel| match pixel {
+                PixelKind::Z16 { depth } => *depth,
+                pixel => panic!("expected a Z16 pixel, got {:?}", pixel),
+            })
+            .collect();
+        let data_size = depth_frame.get_data_size();
+
+        let bytes = depth_frame.into_arc_slice();
+        drop(frameset);
+        let _pipeline = pipeline.stop();
+
+        assert_eq!(bytes.len(), data_size);
+        let shared = Arc::clone(&bytes);
+        let copied = std::thread::spawn(move || {
+            shared
+                .chunks(stride)
+                .flat_map(|row| row[..width * 2].chunks(2))
+                .map(|depth| u16::from_ne_bytes([depth[0], depth[1]]))
+                .collect::<Vec<u16>>()
+        })
+        .join()
+        .unwrap();
+        assert_eq!(copied, depths);
+    }
+}
+
+/// Verify that depth frames report that they come from a depth sensor.
+#[test]
+fn d400_depth_frame_sensor_is_a_depth_sensor() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.first_of_type::<DepthFrame>().unwrap();
+        let extensions = depth_frame.sensor_extensions().unwrap();
+
+        assert!(extensions.contains(&Rs2Extension::DepthSensor));
+        assert!(!extensions.contains(&Rs2Extension::MotionSensor));
+        assert!(depth_frame
+            .sensor()
+            .unwrap()
+            .try_into_depth_sensor()
+            .is_ok());
+    }
+}
+
+/// Verify that depth and color frames are only aligned after passing through an align block.
+#[test]
+fn d400_depth_and_color_are_aligned_after_align_block() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Color, None, 0, 0, Rs2Format::Rgb8, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let frameset = pipeline.wait(None).unwrap();
+        assert!(!frameset.are_aligned().unwrap());
+
+        let mut align = Align::new(Rs2StreamKind::Color).unwrap();
+        let aligned = align.process(frameset).unwrap();
+        assert!(aligned.are_aligned().unwrap());
+
+        let depth_frame = aligned.first_of_type::<DepthFrame>().unwrap();
+        let color_frame = aligned.first_of_type::<ColorFrame>().unwrap();
+        assert_eq!(depth_frame.width(), color_frame.width());
+        assert_eq!(depth_frame.height(), color_frame.height());
+    }
+}
+
+/// Verify that the summary of a frame identifies the frame.
+#[test]
+fn d400_frame_summary_contains_frame_number() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.first_of_type::<DepthFrame>().unwrap();
+        let summary = depth_frame.to_string();
+
+        assert!(summary.starts_with("Depth frame"));
+        assert!(summary.contains(&format!("#{}", depth_frame.frame_number())));
+        assert!(summary.contains(&format!("{}x{}", depth_frame.width(), depth_frame.height())));
+        assert!(format!("{:?}", depth_frame).contains(&depth_frame.frame_number().to_string()));
+    }
+}
+
+/// Verify that the L515 settings cannot be acquired from a D400 device.
+#[test]
+fn d400_has_no_l515_settings() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        match device.l515_settings() {
+            Err(L515SettingsError::NotAnL515(product_line)) => {
+                assert_eq!(product_line.as_deref(), Some("D400"));
+            }
+            other => panic!("Expected the product line to be rejected, got {:?}", other),
+        }
+    }
+}
+
+/// Verify that processing blocks work with a custom queue size.
+#[test]
+fn d400_processing_blocks_with_custom_queue_size_process_frames() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let mut threshold = ThresholdFilter::new_with_queue_size(1).unwrap();
+        let depth_frame = pipeline
+            .wait(None)
+            .unwrap()
+            .frames_of_type::<DepthFrame>()
+            .pop()
+            .unwrap();
+        let (width, height) = (depth_frame.width(), depth_frame.height());
+        let thresholded = threshold.process(depth_frame).unwrap();
+        assert_eq!(thresholded.width(), width);
+        assert_eq!(thresholded.height(), height);
+
+        let mut decimation = DecimationFilter::new().unwrap();
+        decimation.set_queue_size(4).unwrap();
+        for _ in 0..2 {
+            let depth_frame = pipeline
+                .wait(None)
+                .unwrap()
+                .frames_of_type::<DepthFrame>()
+                .pop()
+                .unwrap();
+            decimation.queue(depth_frame).unwrap();
+        }
+        for _ in 0..2 {
+            assert!(decimation.wait(Some(Duration::from_secs(1))).is_ok());
+        }
+    }
+}
+
+/// Verify that a snapshot of a recording has matching color and depth dimensions.
+#[test]
+fn d400_snapshot_from_playback_has_matching_dimensions() {
+    let context = Context::new().unwrap();
+    let config = ConfigBuilder::new()
+        .depth(640, 480, 30)
+        .color(848, 480, 30, Rs2Format::Rgb8)
+        .build()
+        .unwrap();
+    if let Some(recording) = common::Recording::d400(&context, "snapshot-test", config, 30) {
+        let mut pipeline = recording.play(&context);
+
+        let snapshot = capture::snapshot(&mut pipeline).unwrap();
+        assert_eq!(snapshot.width(), 848);
+        assert_eq!(snapshot.height(), 480);
+        assert_eq!(
+            snapshot.depth_meters.len(),
+            snapshot.width() * snapshot.height()
+        );
+        assert_eq!(snapshot.intrinsics.width(), snapshot.width());
+        assert_eq!(snapshot.intrinsics.height(), snapshot.height());
+        assert!(snapshot.depth_meters.iter().all(|d| *d >= 0.0));
+    }
+}
+
+/// Verify that a frame extracted from a composite frame outlives the iterator and the composite.
+#[test]
+fn d400_extracted_frame_outlives_its_composite_frame() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Color, None, 0, 0, Rs2Format::Rgb8, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.iter::<DepthFrame>().next().unwrap();
+        let expected_size = depth_frame.get_data_size();
+        drop(frameset);
+
+        // Cycle the frame pool, so that a frame without its own reference would be reused.
+        for _ in 0..30 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        assert_eq!(depth_frame.get_data_size(), expected_size);
+        depth_frame.validate_data_size().unwrap();
+        let (col, row) = (depth_frame.width() / 2, depth_frame.height() / 2);
+        assert!(depth_frame.get(col, row).is_some());
+        assert!(depth_frame.distance(col, row).is_ok());
+    }
+}
+
+/// Verify that depth aligned to color on the CPU has the color resolution and plausible depth.
+#[test]
+fn d400_depth_aligned_to_color_on_the_cpu_has_color_resolution() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 640, 480, Rs2Format::Z16, 30)
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Color, None, 1280, 720, Rs2Format::Rgb8, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+        let color_frame = frameset.frames_of_type::<ColorFrame>().pop().unwrap();
+
+        let aligned = depth_frame.align_to(color_frame.stream_profile()).unwrap();
+        assert_eq!(aligned.width(), 1280);
+        assert_eq!(aligned.height(), 720);
+        assert_eq!(aligned.as_slice().len(), 1280 * 720);
+        assert_eq!(aligned.units(), depth_frame.units().unwrap());
+
+        // Depth is copied rather than interpolated, so every valid value lies within the range
+        // of the original frame.
+        let original: Vec<u16> = depth_frame
+            .iter()
+            .filter_map(|pixel| match pixel {
+                PixelKind::Z16 { depth } => Some(*depth),
+                _ => None,
+            })
+            .filter(|depth| *depth != 0)
+            .collect();
+        let (min, max) = (
+            *original.iter().min().unwrap(),
+            *original.iter().max().unwrap(),
+        );
+        let valid: Vec<u16> = aligned
+            .as_slice()
+            .iter()
+            .copied()
+            .filter(|depth| *depth != 0)
+            .collect();
+        assert!(!valid.is_empty());
+        assert!(valid.iter().all(|depth| (min..=max).contains(depth)));
+    }
+}
+
+/// Verify that typed metadata is preserved in a recording.
+#[test]
+fn d400_frame_from_playback_has_frame_counter() {
+    let context = Context::new().unwrap();
+    let config = ConfigBuilder::new().depth(640, 480, 30).build().unwrap();
+    if let Some(recording) = common::Recording::d400(&context, "metadata-test", config, 30) {
+        let mut pipeline = recording.play(&context);
+        let frames = pipeline.wait(None).unwrap();
+        let depth_frame = frames.frames_of_type::<DepthFrame>().pop().unwrap();
+        assert!(depth_frame.frame_counter().unwrap().is_some());
+    }
+}
+
+/// Verify that the frames of a composite frame can be iterated from both ends without yielding a
+/// frame twice.
+#[test]
+fn d400_composite_frame_iter_is_double_ended() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Infrared, Some(1), 0, 0, Rs2Format::Y8, 30)
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Infrared, Some(2), 0, 0, Rs2Format::Y8, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let frames = pipeline.wait(None).unwrap();
+        let forward: Vec<usize> = frames
+            .iter::<InfraredFrame>()
+            .map(|frame| frame.infrared_index().unwrap())
+            .collect();
+        let mut backward: Vec<usize> = frames
+            .iter::<InfraredFrame>()
+            .rev()
+            .map(|frame| frame.infrared_index().unwrap())
+            .collect();
+        assert_eq!(forward.len(), 2);
+        backward.reverse();
+        assert_eq!(backward, forward);
+
+        let mut iter = frames.iter::<InfraredFrame>();
+        assert_eq!(iter.size_hint(), (0, Some(frames.count())));
+        let first = iter.next().unwrap().infrared_index();
+        let last = iter.next_back().unwrap().infrared_index();
+        assert_ne!(first, last);
+        assert!(iter.next().is_none());
+        assert!(iter.next_back().is_none());
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+    }
+}
+
+/// Verify that turning the IR emitter off and on again is reflected when reading its state.
+#[test]
+fn d400_emitter_state_reflects_changes() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let mut depth_sensor = device.first_depth_sensor().unwrap();
+        if depth_sensor.emitter_enabled().is_none() {
+            return;
+        }
+
+        depth_sensor.set_emitter_enabled(false).unwrap();
+        assert_eq!(depth_sensor.emitter_enabled(), Some(false));
+
+        // The emitter is on by default, so restore that for the other tests.
+        depth_sensor.set_emitter_enabled(true).unwrap();
+        assert_eq!(depth_sensor.emitter_enabled(), Some(true));
+
+        if depth_sensor.supports_option(Rs2Option::EmitterOnOff) {
+            depth_sensor.set_emitter_on_off(true).unwrap();
+            assert_eq!(depth_sensor.get_option(Rs2Option::EmitterOnOff), Some(1.0));
+            depth_sensor.set_emitter_on_off(false).unwrap();
+            assert_eq!(depth_sensor.get_option(Rs2Option::EmitterOnOff), Some(0.0));
+        }
+    }
+}
+
+/// Verify that filtering the profiles of the depth sensor by kind only gives depth profiles.
+#[test]
+fn d400_depth_sensor_profiles_can_be_filtered_by_kind() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let depth_sensor = device.first_depth_sensor().unwrap();
+
+        let depth_profiles = depth_sensor.stream_profiles_of_kind(Rs2StreamKind::Depth);
+        assert!(!depth_profiles.is_empty());
+        assert!(depth_profiles
+            .iter()
+            .all(|profile| profile.kind() == Rs2StreamKind::Depth));
+
+        // The stereo module also streams infrared, so filtering leaves out some profiles.
+        let all_profiles = depth_sensor.stream_profiles();
+        assert!(depth_profiles.len() < all_profiles.len());
+        assert_eq!(
+            depth_sensor
+                .stream_profiles_of_kind(Rs2StreamKind::Any)
+                .len(),
+            all_profiles.len()
+        );
+    }
+}
+
+/// Verify that the depth histogram counts every valid pixel of a depth frame.
+#[test]
+fn d400_depth_histogram_sums_to_valid_pixels() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let frames = pipeline.wait(None).unwrap();
+        let depth_frame = frames.first_of_type::<DepthFrame>().unwrap();
+
+        let histogram = depth_frame.histogram(64).unwrap();
+        assert_eq!(histogram.len(), 64);
+
+        let pixels = depth_frame.width() * depth_frame.height();
+        let fraction = depth_frame.valid_pixel_fraction().unwrap();
+        assert!((0.0..=1.0).contains(&fraction));
+        assert_eq!(
+            histogram.iter().sum::<u32>(),
+            (fraction * pixels as f32).round() as u32
+        );
+    }
+}
+
+/// Verify that the profile of a started pipeline reports the device and streams it resolved to.
+#[test]
+fn d400_pipeline_profile_reports_resolved_streams() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Color, None, 0, 0, Rs2Format::Rgb8, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let pipeline = pipeline.start(Some(config)).unwrap();
+        let profile = pipeline.profile();
+
+        assert_eq!(
+            profile.device().info(Rs2CameraInfo::SerialNumber),
+            device.info(Rs2CameraInfo::SerialNumber)
+        );
+
+        let streams = profile.streams();
+        assert_eq!(streams.len(), 2);
+        let depth = streams
+            .iter()
+            .find(|stream| stream.kind() == Rs2StreamKind::Depth)
+            .unwrap();
+        let color = streams
+            .iter()
+            .find(|stream| stream.kind() == Rs2StreamKind::Color)
+            .unwrap();
+
+        assert!(depth.intrinsics().is_ok());
+        assert!(color.intrinsics().is_ok());
+        assert!(depth.extrinsics(color).is_ok());
+    }
+}
+
+/// Verify that distances can only be queried for pixels within the frame.
+#[test]
+fn d400_distance_outside_of_frame_is_out_of_bounds() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+        let (width, height) = (depth_frame.width(), depth_frame.height());
+
+        assert!(depth_frame.distance(width - 1, height - 1).is_ok());
+        for (col, row) in [(width, height), (width, 0), (0, height)] {
+            match depth_frame.distance(col, row) {
+                Err(DepthError::PixelOutOfBounds {
+                    col: c,
+                    row: r,
+                    width: w,
+                    height: h,
+                }) => assert_eq!((c, r, w, h), (col, row, width, height)),
+                result => panic!("expected an out of bounds error, got {:?}", result),
+            }
+        }
+    }
+}
+
+/// Verify that the distance of a pixel is its raw depth scaled by the depth units of the frame.
+#[test]
+fn d400_distance_is_raw_depth_times_frame_units() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+        let units = depth_frame.units().unwrap();
+        assert!(units > 0.0);
+
+        let (width, height) = (depth_frame.width(), depth_frame.height());
+        for row in (0..height).step_by(height / 8) {
+            for col in (0..width).step_by(width / 8) {
+                let raw = match depth_frame.get(col, row).unwrap() {
+                    PixelKind::Z16 { depth } => *depth,
+                    pixel => panic!("expected a Z16 pixel, got {:?}", pixel),
+                };
+                let distance = depth_frame.distance(col, row).unwrap();
+                assert!((distance - raw as f32 * units).abs() <= f32::EPSILON * distance.max(1.0));
+            }
+        }
+    }
+}
+
+/// Verify that a custom processing block can pass frames through, and output frames it allocated.
+#[test]
+fn d400_custom_processing_block_outputs_frames() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        // Startup-phase: On startup the RealSense often drops some frames. Skip those.
+        for _ in 0..5 {
+            let _ = pipeline.wait(None).unwrap();
+        }
+
+        let mut pass_through = CustomProcessingBlock::from_fn(|frame: DepthFrame, source| {
+            source.frame_ready(frame).unwrap();
+        })
+        .unwrap();
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+        let frame_number = depth_frame.frame_number();
+        let width = depth_frame.width();
+        let height = depth_frame.height();
+
+        pass_through.queue(depth_frame).unwrap();
+        let output: DepthFrame = pass_through.wait(None).unwrap();
+        assert_eq!(output.frame_number(), frame_number);
+        assert_eq!(output.width(), width);
+        assert_eq!(output.height(), height);
+
+        // Output a frame of constant depth, at half the resolution of the input.
+        let depth: u16 = 1234;
+        let mut constant = CustomProcessingBlock::from_fn(move |frame: DepthFrame, source| {
+            let profile = frame.stream_profile();
+            let mut output = source
+                .pool()
+                .allocate_uninitialized_video_frame(
+                    &frame,
+                    profile,
+                    frame.width() / 2,
+                    frame.height() / 2,
+                )
+                .unwrap();
+            for pixel in output.data_mut().unwrap().chunks_exact_mut(2) {
+                pixel.copy_from_slice(&depth.to_ne_bytes());
+            }
+            source.frame_ready(output).unwrap();
+        })
+        .unwrap();
+
+        let frameset = pipeline.wait(None).unwrap();
+        let depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+        constant.queue(depth_frame).unwrap();
+        let output: DepthFrame = constant.wait(None).unwrap();
+        assert_eq!(output.width(), width / 2);
+        assert_eq!(output.height(), height / 2);
+        match output.get(0, 0).unwrap() {
+            PixelKind::Z16 { depth: d } => assert_eq!(*d, depth),
+            _ => panic!("Depth frame does not hold Z16 data"),
+        }
+    }
+}
+
+/// Verify that the closure of a custom processing block is not invoked with frames of another
+/// stream kind, even though infrared frames could be converted to depth frames as video frames.
+#[test]
+fn d400_custom_processing_block_drops_frames_of_other_kinds() {
+    let context = Context::new().unwrap();
+    let config = ConfigBuilder::new()
+        .depth(640, 480, 30)
+        .infrared(1, 640, 480, 30)
+        .build()
+        .unwrap();
+    if let Some(recording) =
+        common::Recording::d400(&context, "custom-block-filter-test", config, 30)
+    {
+        let mut pipeline = recording.play(&context);
+        let mut pass_through = CustomProcessingBlock::from_fn(|frame: DepthFrame, source| {
+            source.frame_ready(frame).unwrap();
+        })
+        .unwrap();
+
+        let frameset = pipeline.wait(None).unwrap();
+        let infrared_frame = frameset.frames_of_type::<InfraredFrame>().pop().unwrap();
+        pass_through.queue(infrared_frame).unwrap();
+        // The closure runs before `queue` returns, so a frame it output would already be here.
+        assert!(matches!(
+            pass_through.poll::<DepthFrame>().unwrap(),
+            Poll::Pending
+        ));
+
+        let depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+        let frame_number = depth_frame.frame_number();
+        pass_through.queue(depth_frame).unwrap();
+        let output: DepthFrame = pass_through.wait(None).unwrap();
+        assert_eq!(output.frame_number(), frame_number);
+    }
+}
+
+/// Verify that frames allocated from the frame pool of a processing block hold the given data, and
+/// that the pool reuses the buffers of output frames once they are dropped.
+#[test]
+fn d400_frame_pool_recycles_output_buffers() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::d400_device(&context) {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        let depth: u16 = 1234;
+        let mut constant = CustomProcessingBlock::from_fn(move |frame: DepthFrame, source| {
+            let bytes = depth.to_ne_bytes().repeat(frame.width() * frame.height());
+            let output = source
+                .pool()
+                .allocate_video_frame(&frame, frame.stream_profile(), &bytes)
+                .unwrap();
+            source.frame_ready(output).unwrap();
+        })
+        .unwrap();
+
+        let frame_count = 30;
+        let mut buffers = HashSet::new();
+        for _ in 0..frame_count {
+            let frameset = pipeline.wait(None).unwrap();
+            let depth_frame = frameset.frames_of_type::<DepthFrame>().pop().unwrap();
+            constant.queue(depth_frame).unwrap();
+
+            let output: DepthFrame = constant.wait(None).unwrap();
+            match output.get(0, 0).unwrap() {
+                PixelKind::Z16 { depth: d } => assert_eq!(*d, depth),
+                _ => panic!("Depth frame does not hold Z16 data"),
+            }
+            buffers.insert(unsafe { output.get_data() } as *const _ as usize);
+        }
+
+        // Every output is dropped before the next one is allocated, so its buffer is reused.
+        assert!(buffers.len() < frame_count);
+    }
+}
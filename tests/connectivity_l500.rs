@@ -6,25 +6,18 @@ use realsense_rust::{
     config::Config,
     context::Context,
     frame::{ColorFrame, DepthFrame, InfraredFrame},
-    kind::{Rs2CameraInfo, Rs2Format, Rs2Option, Rs2ProductLine, Rs2StreamKind},
+    kind::{Rs2CameraInfo, Rs2DigitalGain, Rs2Format, Rs2Option, Rs2ProductLine, Rs2StreamKind},
     pipeline::InactivePipeline,
 };
-use std::{
-    collections::{HashMap, HashSet},
-    convert::TryFrom,
-    time::Duration,
-};
+use std::{collections::HashMap, convert::TryFrom, time::Duration};
+
+mod common;
 
 #[test]
 fn l500_can_resolve_color_and_depth_and_infrared() {
     let context = Context::new().unwrap();
 
-    let mut queryable_set = HashSet::new();
-    queryable_set.insert(Rs2ProductLine::L500);
-
-    let devices = context.query_devices(queryable_set);
-
-    if let Some(device) = devices.get(0) {
+    if let Some(device) = common::connected_device(&context, Rs2ProductLine::L500) {
         let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
         let mut config = Config::new();
 
@@ -51,12 +44,7 @@ fn l500_can_resolve_color_and_depth_and_infrared() {
 fn l500_streams_at_expected_framerate() {
     let context = Context::new().unwrap();
 
-    let mut queryable_set = HashSet::new();
-    queryable_set.insert(Rs2ProductLine::L500);
-
-    let devices = context.query_devices(queryable_set);
-
-    if let Some(device) = devices.get(0) {
+    if let Some(device) = common::connected_device(&context, Rs2ProductLine::L500) {
         let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
         let mut config = Config::new();
 
@@ -144,12 +132,7 @@ fn l500_streams_at_expected_framerate() {
 fn l500_streams_are_distinct() {
     let context = Context::new().unwrap();
 
-    let mut queryable_set = HashSet::new();
-    queryable_set.insert(Rs2ProductLine::L500);
-
-    let devices = context.query_devices(queryable_set);
-
-    if let Some(device) = devices.get(0) {
+    if let Some(device) = common::connected_device(&context, Rs2ProductLine::L500) {
         let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
         let mut config = Config::new();
         config
@@ -209,12 +192,7 @@ fn l500_streams_check_supported_but_ignored_sensor_options() {
 
     let context = Context::new().unwrap();
 
-    let mut queryable_set = HashSet::new();
-    queryable_set.insert(Rs2ProductLine::L500);
-
-    let devices = context.query_devices(queryable_set);
-
-    if let Some(device) = devices.get(0) {
+    if let Some(device) = common::connected_device(&context, Rs2ProductLine::L500) {
         // Grab the sensor list
         for mut sensor in device.sensors() {
             for (option, val) in &options_to_set {
@@ -263,3 +241,23 @@ fn l500_streams_check_supported_but_ignored_sensor_options() {
         }
     }
 }
+
+#[test]
+fn l500_settings_can_be_read_and_written() {
+    let context = Context::new().unwrap();
+
+    if let Some(device) = common::connected_device(&context, Rs2ProductLine::L500) {
+        let mut settings = device.l515_settings().unwrap();
+
+        let gain = settings.digital_gain().unwrap();
+        settings.set_digital_gain(Rs2DigitalGain::Low).unwrap();
+        assert_eq!(settings.digital_gain(), Some(Rs2DigitalGain::Low));
+        settings.set_digital_gain(gain).unwrap();
+
+        assert!(settings.laser_power().is_some());
+        assert!(settings.min_distance().is_some());
+        assert!(settings.noise_filter_level().is_some());
+        assert!(settings.confidence_threshold().is_some());
+        assert!(settings.receiver_gain().is_some());
+    }
+}